@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS tags(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- Tag name
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS quote_tags(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- Tagged quote
+    quote_id INT NOT NULL,
+    -- Tag applied to the quote
+    tag_id INT NOT NULL,
+    FOREIGN KEY (quote_id) REFERENCES quotes(id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+);
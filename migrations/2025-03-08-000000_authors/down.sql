@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+-- Merged author names are left canonicalized.
+DROP TABLE IF EXISTS author_aliases;
+DROP TABLE IF EXISTS authors;
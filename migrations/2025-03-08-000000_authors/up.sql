@@ -0,0 +1,24 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS authors(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- Canonical author name
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS author_aliases(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- Author the alias resolves to
+    author_id INT NOT NULL,
+    -- Alternative spelling of the author name
+    alias TEXT NOT NULL UNIQUE,
+    FOREIGN KEY (author_id) REFERENCES authors(id)
+);
+
+-- Merge known spelling variants into their canonical name.
+UPDATE quotes SET author = 'Friedrich Nietzsche' WHERE author = 'Friederich Nietzsche';
+
+INSERT INTO authors (id, name)
+    SELECT ROW_NUMBER() OVER (ORDER BY author) - 1, author
+    FROM (SELECT DISTINCT author FROM quotes);
+
+INSERT INTO author_aliases (id, author_id, alias)
+    SELECT 0, id, 'Friederich Nietzsche' FROM authors WHERE name = 'Friedrich Nietzsche';
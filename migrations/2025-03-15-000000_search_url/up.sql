@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- URL that was opened for the search
+ALTER TABLE searches ADD COLUMN url TEXT;
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE searches DROP COLUMN exact;
+ALTER TABLE searches DROP COLUMN exclude;
+ALTER TABLE searches DROP COLUMN filetype;
+ALTER TABLE searches DROP COLUMN intitle;
+ALTER TABLE searches DROP COLUMN inurl;
+ALTER TABLE searches DROP COLUMN after;
+ALTER TABLE searches DROP COLUMN before;
+ALTER TABLE searches DROP COLUMN any_of;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Exact phrase the results must contain
+ALTER TABLE searches ADD COLUMN exact TEXT;
+-- Comma separated terms the results must not contain
+ALTER TABLE searches ADD COLUMN exclude TEXT;
+-- File type of the results
+ALTER TABLE searches ADD COLUMN filetype TEXT;
+-- Text the result titles must contain
+ALTER TABLE searches ADD COLUMN intitle TEXT;
+-- Text the result URLs must contain
+ALTER TABLE searches ADD COLUMN inurl TEXT;
+-- Results must be published after this date
+ALTER TABLE searches ADD COLUMN after DATE;
+-- Results must be published before this date
+ALTER TABLE searches ADD COLUMN before DATE;
+-- Comma separated terms of which the results must contain at least one
+ALTER TABLE searches ADD COLUMN any_of TEXT;
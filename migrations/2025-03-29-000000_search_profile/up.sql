@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Name of the search profile used for the search
+ALTER TABLE searches ADD COLUMN profile TEXT;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Whether the search is marked as a favorite
+ALTER TABLE searches ADD COLUMN starred BOOLEAN NOT NULL DEFAULT 0;
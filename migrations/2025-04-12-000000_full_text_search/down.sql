@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS searches_fts_insert;
+DROP TRIGGER IF EXISTS searches_fts_delete;
+DROP TRIGGER IF EXISTS searches_fts_update;
+DROP TRIGGER IF EXISTS quotes_fts_insert;
+DROP TRIGGER IF EXISTS quotes_fts_delete;
+DROP TRIGGER IF EXISTS quotes_fts_update;
+DROP TRIGGER IF EXISTS tasks_fts_insert;
+DROP TRIGGER IF EXISTS tasks_fts_delete;
+DROP TRIGGER IF EXISTS tasks_fts_update;
+DROP TABLE IF EXISTS searches_fts;
+DROP TABLE IF EXISTS quotes_fts;
+DROP TABLE IF EXISTS tasks_fts;
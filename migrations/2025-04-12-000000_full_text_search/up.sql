@@ -0,0 +1,48 @@
+-- Your SQL goes here
+-- Full text indexes of searches, quotes and tasks. The rowid of each index
+-- row is the id of the row it indexes, and triggers keep them in sync.
+CREATE VIRTUAL TABLE IF NOT EXISTS searches_fts USING fts5(query, website, allintext, exact);
+CREATE VIRTUAL TABLE IF NOT EXISTS quotes_fts USING fts5(quote, author);
+CREATE VIRTUAL TABLE IF NOT EXISTS tasks_fts USING fts5(task, desc);
+
+INSERT INTO searches_fts(rowid, query, website, allintext, exact)
+    SELECT id, query, website, allintext, exact FROM searches;
+INSERT INTO quotes_fts(rowid, quote, author)
+    SELECT id, quote, author FROM quotes;
+INSERT INTO tasks_fts(rowid, task, desc)
+    SELECT id, task, desc FROM tasks;
+
+CREATE TRIGGER IF NOT EXISTS searches_fts_insert AFTER INSERT ON searches BEGIN
+    INSERT INTO searches_fts(rowid, query, website, allintext, exact)
+        VALUES (new.id, new.query, new.website, new.allintext, new.exact);
+END;
+CREATE TRIGGER IF NOT EXISTS searches_fts_delete AFTER DELETE ON searches BEGIN
+    DELETE FROM searches_fts WHERE rowid = old.id;
+END;
+CREATE TRIGGER IF NOT EXISTS searches_fts_update AFTER UPDATE ON searches BEGIN
+    DELETE FROM searches_fts WHERE rowid = old.id;
+    INSERT INTO searches_fts(rowid, query, website, allintext, exact)
+        VALUES (new.id, new.query, new.website, new.allintext, new.exact);
+END;
+
+CREATE TRIGGER IF NOT EXISTS quotes_fts_insert AFTER INSERT ON quotes BEGIN
+    INSERT INTO quotes_fts(rowid, quote, author) VALUES (new.id, new.quote, new.author);
+END;
+CREATE TRIGGER IF NOT EXISTS quotes_fts_delete AFTER DELETE ON quotes BEGIN
+    DELETE FROM quotes_fts WHERE rowid = old.id;
+END;
+CREATE TRIGGER IF NOT EXISTS quotes_fts_update AFTER UPDATE ON quotes BEGIN
+    DELETE FROM quotes_fts WHERE rowid = old.id;
+    INSERT INTO quotes_fts(rowid, quote, author) VALUES (new.id, new.quote, new.author);
+END;
+
+CREATE TRIGGER IF NOT EXISTS tasks_fts_insert AFTER INSERT ON tasks BEGIN
+    INSERT INTO tasks_fts(rowid, task, desc) VALUES (new.id, new.task, new.desc);
+END;
+CREATE TRIGGER IF NOT EXISTS tasks_fts_delete AFTER DELETE ON tasks BEGIN
+    DELETE FROM tasks_fts WHERE rowid = old.id;
+END;
+CREATE TRIGGER IF NOT EXISTS tasks_fts_update AFTER UPDATE ON tasks BEGIN
+    DELETE FROM tasks_fts WHERE rowid = old.id;
+    INSERT INTO tasks_fts(rowid, task, desc) VALUES (new.id, new.task, new.desc);
+END;
@@ -0,0 +1,19 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS bookmarks(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- Bookmarked URL
+    url TEXT NOT NULL UNIQUE,
+    -- Title of the page
+    title TEXT,
+    -- Time the bookmark was added
+    time_stamp TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bookmark_tags(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- Tagged bookmark
+    bookmark_id INT NOT NULL,
+    -- Tag applied to the bookmark
+    tag_id INT NOT NULL,
+    FOREIGN KEY (bookmark_id) REFERENCES bookmarks(id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+);
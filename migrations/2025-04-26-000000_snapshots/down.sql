@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS snapshots_fts_delete;
+DROP TABLE IF EXISTS snapshots_fts;
+DROP TABLE IF EXISTS snapshots;
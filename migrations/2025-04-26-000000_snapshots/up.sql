@@ -0,0 +1,21 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS snapshots(
+    id INT AUTO_INCREMENT PRIMARY KEY NOT NULL,
+    -- URL the page was saved from
+    url TEXT NOT NULL,
+    -- Title of the page
+    title TEXT,
+    -- Readable text of the page, compressed with gzip
+    content BLOB NOT NULL,
+    -- Length of the text before compression, in bytes
+    size INT NOT NULL,
+    -- Time the page was saved
+    time_stamp TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+-- The text is compressed in the table, so the index keeps its own copy of
+-- the start of it. Indexing the whole text would store it uncompressed a
+-- second time. Rows are added along with the snapshot.
+CREATE VIRTUAL TABLE IF NOT EXISTS snapshots_fts USING fts5(title, url, summary);
+CREATE TRIGGER IF NOT EXISTS snapshots_fts_delete AFTER DELETE ON snapshots BEGIN
+    DELETE FROM snapshots_fts WHERE rowid = old.id;
+END;
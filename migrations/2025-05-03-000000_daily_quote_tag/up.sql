@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Tag the daily quote was picked for, or NULL for the untagged daily quote.
+ALTER TABLE daily_quotes ADD COLUMN tag TEXT;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Whether the daily quote was the first draw of a deck, so that the current
+-- deck does not depend on how many quotes were ever drawn.
+ALTER TABLE daily_quotes ADD COLUMN deck_start BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+-- Merged authors cannot be told apart again, so nothing is undone.
+SELECT 1;
@@ -0,0 +1,27 @@
+-- Your SQL goes here
+-- Merge authors whose names differ only in case or surrounding whitespace
+-- into the first one registered, as lookups treat them as the same author.
+CREATE TEMP TABLE author_variants AS
+    SELECT a.id AS id, a.name AS name, c.id AS canonical_id, c.name AS canonical
+    FROM authors a
+    JOIN authors c ON c.id = (
+        SELECT MIN(id) FROM authors WHERE lower(trim(name)) = lower(trim(a.name))
+    )
+    WHERE a.id <> c.id;
+
+UPDATE author_aliases
+    SET author_id = (SELECT canonical_id FROM author_variants v WHERE v.id = author_id)
+    WHERE author_id IN (SELECT id FROM author_variants);
+UPDATE quotes
+    SET author = (SELECT canonical FROM author_variants v WHERE v.name = author)
+    WHERE author IN (SELECT name FROM author_variants);
+DELETE FROM authors WHERE id IN (SELECT id FROM author_variants);
+DROP TABLE author_variants;
+
+-- Move quotes attributed to any alias, in any case, to its author.
+UPDATE quotes
+    SET author = (
+        SELECT a.name FROM author_aliases al JOIN authors a ON a.id = al.author_id
+        WHERE lower(al.alias) = lower(trim(quotes.author))
+    )
+    WHERE lower(trim(author)) IN (SELECT lower(alias) FROM author_aliases);
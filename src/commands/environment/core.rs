@@ -1,14 +1,19 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{Read, Write},
     path::PathBuf,
 };
 
 use chrono::NaiveDateTime;
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 /// Environment settings for the application.
+///
+/// Settings missing from the config file use their default values.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Environment {
     /// Git user name to use in commit signatures.
     pub git_name: String,
@@ -18,15 +23,324 @@ pub struct Environment {
     pub git_dir: PathBuf,
     /// Git ignore url base.
     pub git_ignore_url: String,
+    /// Number of seconds cached .gitignore templates are used before they are
+    /// fetched again.
+    pub git_ignore_cache_ttl: u64,
+    /// Local clone of github/gitignore used when the templates cannot be
+    /// fetched.
+    pub git_ignore_repo: PathBuf,
+    /// Directory of custom .gitignore templates.
+    pub git_ignore_dir: PathBuf,
+    /// Directory of project templates for `git new --template`, which take
+    /// precedence over the built-in ones.
+    pub git_template_dir: PathBuf,
+    /// License of repos created by `git new --template`: mit, apache-2.0,
+    /// unlicense or none.
+    pub git_license: String,
     /// Database connection string.
     pub conn_str: String,
     /// API Key for [Custom Search JSON API](https://developers.google.com/custom-search/v1/overview).
     pub google_search_api_key: String,
     /// Engine ID for [Custom Search JSON API](https://developers.google.com/custom-search/v1/using_rest#making_a_request).
     pub google_search_engine_id: String,
+    /// Base URL of the Custom Search JSON API.
+    pub google_search_api_url: String,
+    /// Number of seconds search results are cached for. 0 disables the cache.
+    pub search_cache_ttl: u64,
+    /// Search engine used when none is given.
+    pub search_engine: SearchEngine,
+    /// Base URL of a self-hosted SearXNG instance.
+    pub searxng_url: String,
+    /// Interface language of searches, such as `en`.
+    pub search_lang: String,
+    /// Country searches are run from, such as `us`.
+    pub search_country: String,
+    /// Safe search filtering of searches.
+    pub search_safe: SafeSearch,
+    /// Number of results per page.
+    pub search_num: u8,
+    /// Policy used to pick a new daily quote.
+    pub quote_rotation: QuoteRotation,
+    /// Number of days a daily quote is not repeated for with the `recent` policy.
+    pub quote_rotation_days: u32,
+    /// URL of a JSON quote API to fetch quotes from.
+    pub quote_api_url: String,
+    /// URL of an RSS or Atom feed to fetch quotes from.
+    pub quote_feed_url: String,
+    /// Directory of quote files to fetch quotes from.
+    pub quote_dir: PathBuf,
+    /// Number of seconds to wait for a web request.
+    pub http_timeout: u64,
+    /// Number of times a failed web request is retried.
+    pub http_retries: u32,
+    /// User agent sent with web requests.
+    pub http_user_agent: String,
+    /// Proxy for web requests. When empty, the `HTTPS_PROXY` and
+    /// `HTTP_PROXY` environment variables are used.
+    pub http_proxy: String,
+    /// Search bangs such as `!rs`, mapped to URL templates with a `{query}`
+    /// placeholder.
+    pub bangs: BTreeMap<String, String>,
+    /// Layout of the `welcome` dashboard.
+    pub welcome: WelcomeConfig,
+    /// Named sets of search settings, such as `work` or `de`.
+    pub search_profiles: BTreeMap<String, SearchProfile>,
+}
+
+/// Policies for picking a new daily quote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteRotation {
+    /// Pick any quote at random.
+    Random,
+    /// Avoid quotes shown in the last `quote_rotation_days` days.
+    #[default]
+    Recent,
+    /// Cycle through a shuffled deck until every quote has been shown.
+    Deck,
+}
+
+impl std::fmt::Display for QuoteRotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuoteRotation::Random => write!(f, "random"),
+            QuoteRotation::Recent => write!(f, "recent"),
+            QuoteRotation::Deck => write!(f, "deck"),
+        }
+    }
+}
+
+/// Search engines that queries can be sent to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEngine {
+    #[default]
+    Google,
+    #[value(name = "duckduckgo")]
+    DuckDuckGo,
+    Bing,
+    Kagi,
+    /// A self-hosted SearXNG instance at `searxng_url`.
+    #[value(name = "searxng")]
+    SearXng,
+    /// Crate documentation on docs.rs.
+    #[value(name = "docsrs")]
+    DocsRs,
+    /// Crates on crates.io.
+    #[value(name = "cratesio")]
+    CratesIo,
+    /// Code search on GitHub.
+    #[value(name = "github")]
+    GitHub,
+}
+
+impl std::fmt::Display for SearchEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchEngine::Google => write!(f, "google"),
+            SearchEngine::DuckDuckGo => write!(f, "duckduckgo"),
+            SearchEngine::Bing => write!(f, "bing"),
+            SearchEngine::Kagi => write!(f, "kagi"),
+            SearchEngine::SearXng => write!(f, "searxng"),
+            SearchEngine::DocsRs => write!(f, "docsrs"),
+            SearchEngine::CratesIo => write!(f, "cratesio"),
+            SearchEngine::GitHub => write!(f, "github"),
+        }
+    }
+}
+
+/// Safe search filtering levels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SafeSearch {
+    /// Do not filter results.
+    #[default]
+    Off,
+    /// Filter explicit results.
+    Active,
+}
+
+impl std::fmt::Display for SafeSearch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SafeSearch::Off => write!(f, "off"),
+            SafeSearch::Active => write!(f, "active"),
+        }
+    }
+}
+
+/// Locale settings of a web search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchLocale {
+    /// Language code, such as `en`.
+    pub lang: String,
+    /// Country code, such as `us`.
+    pub country: String,
+    pub safe: SafeSearch,
+    /// Number of results per page.
+    pub num: u8,
+}
+
+/// A named set of search settings.
+///
+/// Settings that are not set keep their value from the environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchProfile {
+    pub lang: Option<String>,
+    pub country: Option<String>,
+    pub safe: Option<SafeSearch>,
+    pub num: Option<u8>,
+}
+
+impl SearchProfile {
+    /// Override the settings of `locale` with the ones set in the profile.
+    pub fn apply(&self, locale: SearchLocale) -> SearchLocale {
+        SearchLocale {
+            lang: self.lang.clone().unwrap_or(locale.lang),
+            country: self.country.clone().unwrap_or(locale.country),
+            safe: self.safe.unwrap_or(locale.safe),
+            num: self.num.unwrap_or(locale.num),
+        }
+    }
+}
+
+impl std::fmt::Display for SearchProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        if let Some(lang) = &self.lang {
+            parts.push(format!("lang={}", lang));
+        }
+        if let Some(country) = &self.country {
+            parts.push(format!("country={}", country));
+        }
+        if let Some(safe) = &self.safe {
+            parts.push(format!("safe={}", safe));
+        }
+        if let Some(num) = &self.num {
+            parts.push(format!("num={}", num));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Sections that can be shown on the `welcome` dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WelcomeSection {
+    /// The welcome message and date.
+    Greeting,
+    /// The daily quote.
+    Quote,
+    /// Tasks that are due today or overdue.
+    Tasks,
+    /// Tasks that are in progress.
+    Timers,
+    /// Repos under `git_dir` with uncommitted changes.
+    Repos,
+    /// The most recent searches.
+    Searches,
+}
+
+impl WelcomeSection {
+    /// Every section, in the default order.
+    pub const ALL: [WelcomeSection; 6] = [
+        WelcomeSection::Greeting,
+        WelcomeSection::Quote,
+        WelcomeSection::Tasks,
+        WelcomeSection::Timers,
+        WelcomeSection::Repos,
+        WelcomeSection::Searches,
+    ];
+}
+
+impl std::fmt::Display for WelcomeSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WelcomeSection::Greeting => write!(f, "greeting"),
+            WelcomeSection::Quote => write!(f, "quote"),
+            WelcomeSection::Tasks => write!(f, "tasks"),
+            WelcomeSection::Timers => write!(f, "timers"),
+            WelcomeSection::Repos => write!(f, "repos"),
+            WelcomeSection::Searches => write!(f, "searches"),
+        }
+    }
+}
+
+/// A section of the `welcome` dashboard and whether it is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionConfig {
+    pub name: WelcomeSection,
+    pub enabled: bool,
+}
+
+/// Layout of the `welcome` dashboard.
+///
+/// Sections are shown in the order they are listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WelcomeConfig {
+    /// Number of recent searches to show.
+    pub search_count: i64,
+    pub sections: Vec<SectionConfig>,
+}
+
+impl WelcomeConfig {
+    /// Enable only the given sections, in the given order.
+    ///
+    /// The remaining sections are kept after them, disabled.
+    pub fn set_sections(&mut self, enabled: &[WelcomeSection]) {
+        let mut sections: Vec<SectionConfig> = enabled
+            .iter()
+            .map(|name| SectionConfig {
+                name: *name,
+                enabled: true,
+            })
+            .collect();
+        for name in WelcomeSection::ALL {
+            if !enabled.contains(&name) {
+                sections.push(SectionConfig {
+                    name,
+                    enabled: false,
+                });
+            }
+        }
+        self.sections = sections;
+    }
+
+    /// The enabled sections, in order.
+    pub fn enabled_sections(&self) -> Vec<WelcomeSection> {
+        let mut seen = vec![];
+        for section in self.sections.iter().filter(|s| s.enabled) {
+            if !seen.contains(&section.name) {
+                seen.push(section.name);
+            }
+        }
+        seen
+    }
+}
+
+impl Default for WelcomeConfig {
+    fn default() -> Self {
+        let mut config = Self {
+            search_count: 5,
+            sections: vec![],
+        };
+        config.set_sections(&WelcomeSection::ALL);
+        config
+    }
 }
 
 impl Environment {
+    /// The default locale of web searches.
+    pub fn search_locale(&self) -> SearchLocale {
+        SearchLocale {
+            lang: self.search_lang.clone(),
+            country: self.search_country.clone(),
+            safe: self.search_safe,
+            num: self.search_num,
+        }
+    }
+
     /// Saves the environment settings.
     ///
     /// The old settings are overwritten.
@@ -94,13 +408,57 @@ impl Default for Environment {
             git_email: "user.name@email.com".into(),
             git_dir: "~/Code".into(),
             git_ignore_url: "https://www.toptal.com/developers/gitignore/api/".into(),
+            git_ignore_cache_ttl: 60 * 60 * 24 * 7,
+            git_ignore_repo: "~/Code/github/gitignore".into(),
+            git_ignore_dir: crate::APP_DIR.join("gitignore"),
+            git_template_dir: crate::APP_DIR.join("templates"),
+            git_license: "mit".into(),
             conn_str: crate::SQL_FILE.display().to_string(),
             google_search_api_key: "google_search_api_key".into(),
             google_search_engine_id: "google_search_engine_id".into(),
+            google_search_api_url: "https://www.googleapis.com/customsearch/v1".into(),
+            search_cache_ttl: 60 * 60 * 24,
+            search_engine: SearchEngine::default(),
+            searxng_url: "http://localhost:8080".into(),
+            search_lang: "en".into(),
+            search_country: "us".into(),
+            search_safe: SafeSearch::default(),
+            search_num: 10,
+            quote_rotation: QuoteRotation::default(),
+            quote_rotation_days: 30,
+            quote_api_url: "https://zenquotes.io/api/quotes".into(),
+            quote_feed_url: "https://www.brainyquote.com/link/quotebr.rss".into(),
+            quote_dir: crate::APP_DIR.join("quotes"),
+            http_timeout: 30,
+            http_retries: 2,
+            http_user_agent: concat!("ShellCommander/", env!("CARGO_PKG_VERSION")).into(),
+            http_proxy: String::new(),
+            bangs: default_bangs(),
+            welcome: WelcomeConfig::default(),
+            search_profiles: BTreeMap::new(),
         }
     }
 }
 
+/// The bangs available before any are added.
+fn default_bangs() -> BTreeMap<String, String> {
+    [
+        ("crate", "https://crates.io/search?q={query}"),
+        ("ddg", "https://duckduckgo.com/?q={query}"),
+        ("g", "https://www.google.com/search?q={query}"),
+        ("gh", "https://github.com/search?q={query}"),
+        ("rs", "https://docs.rs/releases/search?query={query}"),
+        ("std", "https://doc.rust-lang.org/std/?search={query}"),
+        (
+            "w",
+            "https://en.wikipedia.org/wiki/Special:Search?search={query}",
+        ),
+    ]
+    .into_iter()
+    .map(|(bang, url)| (bang.to_string(), url.to_string()))
+    .collect()
+}
+
 pub fn time_now() -> NaiveDateTime {
     chrono::Utc::now().naive_utc()
 }
@@ -1,7 +1,8 @@
 pub mod core;
 use super::Environment;
 use crate::{Colorize, ENV};
-use clap::Subcommand;
+use clap::{Args, Subcommand};
+use core::{QuoteRotation, SafeSearch, SearchEngine, WelcomeSection};
 
 use super::CommandHandler;
 /// A set of utilities for interacting with the environment.
@@ -11,75 +12,15 @@ pub(crate) enum EnvCommands {
     ///
     /// The environment variables are set using flags. If no flags are
     /// provided, the command will exit.
-    Set {
-        /// Git user name to use in commit signatures.
-        #[arg(short = None, long)]
-        git_name: Option<String>,
-        /// Git email to use in commit signatures.
-        #[arg(short = None, long)]
-        git_email: Option<String>,
-        /// Git directory to clone repos to.
-        #[arg(short = None, long)]
-        git_dir: Option<String>,
-        /// Git ignore url base.
-        #[arg(short = None, long)]
-        git_ignore_url: Option<String>,
-        /// Database connection string.
-        #[arg(short = None, long)]
-        conn_str: Option<String>,
-        #[arg(short = None, long)]
-        google_search_api_key: Option<String>,
-        #[arg(short = None, long)]
-        google_search_engine_id: Option<String>,
-    },
+    Set(Box<SetArgs>),
     /// Get an environment variable.
     ///
     /// If no flags are provided, all environment variables are returned.
-    Get {
-        /// Git user name to use in commit signatures.
-        #[arg(short = None, long)]
-        git_name: bool,
-        /// Git email to use in commit signatures.
-        #[arg(short = None, long)]
-        git_email: bool,
-        /// Git directory to clone repos to.
-        #[arg(short = None, long)]
-        git_dir: bool,
-        /// Git ignore url base.
-        #[arg(short = None, long)]
-        git_ignore_url: bool,
-        /// Database connection string.
-        #[arg(short = None, long)]
-        conn_str: bool,
-        #[arg(short = None, long)]
-        google_search_api_key: bool,
-        #[arg(short = None, long)]
-        google_search_engine_id: bool,
-    },
+    Get(EnvFlags),
     /// Reset an environment variable.
     ///
     /// If no flags are provided, all environment variables are reset.
-    Reset {
-        /// Git user name to use in commit signatures.
-        #[arg(short = None, long)]
-        git_name: bool,
-        /// Git email to use in commit signatures.
-        #[arg(short = None, long)]
-        git_email: bool,
-        /// Git directory to clone repos to.
-        #[arg(short = None, long)]
-        git_dir: bool,
-        /// Git ignore url base.
-        #[arg(short = None, long)]
-        git_ignore_url: bool,
-        /// Database connection string.
-        #[arg(short = None, long)]
-        conn_str: bool,
-        #[arg(short = None, long)]
-        google_search_api_key: bool,
-        #[arg(short = None, long)]
-        google_search_engine_id: bool,
-    },
+    Reset(EnvFlags),
     /// Save the environment settings.
     Save,
     /// Load the environment settings.
@@ -90,60 +31,241 @@ pub(crate) enum EnvCommands {
     GenerateDotEnv,
 }
 
+/// New values for environment variables.
+#[derive(Debug, Args)]
+pub(crate) struct SetArgs {
+    /// Git user name to use in commit signatures.
+    #[arg(short = None, long)]
+    git_name: Option<String>,
+    /// Git email to use in commit signatures.
+    #[arg(short = None, long)]
+    git_email: Option<String>,
+    /// Git directory to clone repos to.
+    #[arg(short = None, long)]
+    git_dir: Option<String>,
+    /// Git ignore url base.
+    #[arg(short = None, long)]
+    git_ignore_url: Option<String>,
+    /// Database connection string.
+    #[arg(short = None, long)]
+    conn_str: Option<String>,
+    #[arg(short = None, long)]
+    google_search_api_key: Option<String>,
+    #[arg(short = None, long)]
+    google_search_engine_id: Option<String>,
+    /// Policy used to pick a new daily quote.
+    #[arg(short = None, long, value_enum)]
+    quote_rotation: Option<QuoteRotation>,
+    /// Number of days a daily quote is not repeated for.
+    #[arg(short = None, long)]
+    quote_rotation_days: Option<u32>,
+    /// URL of a JSON quote API to fetch quotes from.
+    #[arg(short = None, long)]
+    quote_api_url: Option<String>,
+    /// URL of an RSS or Atom feed to fetch quotes from.
+    #[arg(short = None, long)]
+    quote_feed_url: Option<String>,
+    /// Directory of quote files to fetch quotes from.
+    #[arg(short = None, long)]
+    quote_dir: Option<String>,
+    /// Sections shown by `welcome`, in order. Unlisted sections are hidden.
+    #[arg(short = None, long, value_enum, value_delimiter = ',')]
+    welcome_sections: Option<Vec<WelcomeSection>>,
+    /// Number of recent searches shown by `welcome`.
+    #[arg(short = None, long)]
+    welcome_search_count: Option<i64>,
+    /// Base URL of the Custom Search JSON API.
+    #[arg(short = None, long)]
+    google_search_api_url: Option<String>,
+    /// Number of seconds search results are cached for. 0 disables the cache.
+    #[arg(short = None, long)]
+    search_cache_ttl: Option<u64>,
+    /// Search engine used when none is given.
+    #[arg(short = None, long, value_enum)]
+    search_engine: Option<SearchEngine>,
+    /// Base URL of a self-hosted SearXNG instance.
+    #[arg(short = None, long)]
+    searxng_url: Option<String>,
+    /// Interface language of searches, such as `en`.
+    #[arg(short = None, long)]
+    search_lang: Option<String>,
+    /// Country searches are run from, such as `us`.
+    #[arg(short = None, long)]
+    search_country: Option<String>,
+    /// Safe search filtering of searches.
+    #[arg(short = None, long, value_enum)]
+    search_safe: Option<SafeSearch>,
+    /// Number of results per page.
+    #[arg(short = None, long, value_parser = clap::value_parser!(u8).range(1..=100))]
+    search_num: Option<u8>,
+    /// Number of seconds to wait for a web request.
+    #[arg(short = None, long)]
+    http_timeout: Option<u64>,
+    /// Number of times a failed web request is retried.
+    #[arg(short = None, long)]
+    http_retries: Option<u32>,
+    /// User agent sent with web requests.
+    #[arg(short = None, long)]
+    http_user_agent: Option<String>,
+    /// Proxy for web requests. When empty, the `HTTPS_PROXY` and `HTTP_PROXY` environment variables are used.
+    #[arg(short = None, long)]
+    http_proxy: Option<String>,
+    /// Number of seconds cached .gitignore templates are used before they are fetched again.
+    #[arg(short = None, long)]
+    git_ignore_cache_ttl: Option<u64>,
+    /// Local clone of github/gitignore used when the templates cannot be fetched.
+    #[arg(short = None, long)]
+    git_ignore_repo: Option<String>,
+    /// Directory of custom .gitignore templates.
+    #[arg(short = None, long)]
+    git_ignore_dir: Option<String>,
+    /// Directory of project templates for `git new --template`, which take precedence over the built-in ones.
+    #[arg(short = None, long)]
+    git_template_dir: Option<String>,
+    /// License of repos created by `git new --template`: mit, apache-2.0, unlicense or none.
+    #[arg(short = None, long)]
+    git_license: Option<String>,
+}
+
+/// Environment variables to get or reset.
+#[derive(Debug, Args)]
+pub(crate) struct EnvFlags {
+    /// Git user name to use in commit signatures.
+    #[arg(short = None, long)]
+    git_name: bool,
+    /// Git email to use in commit signatures.
+    #[arg(short = None, long)]
+    git_email: bool,
+    /// Git directory to clone repos to.
+    #[arg(short = None, long)]
+    git_dir: bool,
+    /// Git ignore url base.
+    #[arg(short = None, long)]
+    git_ignore_url: bool,
+    /// Database connection string.
+    #[arg(short = None, long)]
+    conn_str: bool,
+    #[arg(short = None, long)]
+    google_search_api_key: bool,
+    #[arg(short = None, long)]
+    google_search_engine_id: bool,
+    /// Policy used to pick a new daily quote.
+    #[arg(short = None, long)]
+    quote_rotation: bool,
+    /// Number of days a daily quote is not repeated for.
+    #[arg(short = None, long)]
+    quote_rotation_days: bool,
+    /// URL of a JSON quote API to fetch quotes from.
+    #[arg(short = None, long)]
+    quote_api_url: bool,
+    /// URL of an RSS or Atom feed to fetch quotes from.
+    #[arg(short = None, long)]
+    quote_feed_url: bool,
+    /// Directory of quote files to fetch quotes from.
+    #[arg(short = None, long)]
+    quote_dir: bool,
+    /// Sections shown by `welcome`, in order.
+    #[arg(short = None, long)]
+    welcome_sections: bool,
+    /// Number of recent searches shown by `welcome`.
+    #[arg(short = None, long)]
+    welcome_search_count: bool,
+    /// Base URL of the Custom Search JSON API.
+    #[arg(short = None, long)]
+    google_search_api_url: bool,
+    /// Number of seconds search results are cached for. 0 disables the cache.
+    #[arg(short = None, long)]
+    search_cache_ttl: bool,
+    /// Search engine used when none is given.
+    #[arg(short = None, long)]
+    search_engine: bool,
+    /// Base URL of a self-hosted SearXNG instance.
+    #[arg(short = None, long)]
+    searxng_url: bool,
+    /// Interface language of searches, such as `en`.
+    #[arg(short = None, long)]
+    search_lang: bool,
+    /// Country searches are run from, such as `us`.
+    #[arg(short = None, long)]
+    search_country: bool,
+    /// Safe search filtering of searches.
+    #[arg(short = None, long)]
+    search_safe: bool,
+    /// Number of results per page.
+    #[arg(short = None, long)]
+    search_num: bool,
+    /// Number of seconds to wait for a web request.
+    #[arg(short = None, long)]
+    http_timeout: bool,
+    /// Number of times a failed web request is retried.
+    #[arg(short = None, long)]
+    http_retries: bool,
+    /// User agent sent with web requests.
+    #[arg(short = None, long)]
+    http_user_agent: bool,
+    /// Proxy for web requests. When empty, the `HTTPS_PROXY` and `HTTP_PROXY` environment variables are used.
+    #[arg(short = None, long)]
+    http_proxy: bool,
+    /// Number of seconds cached .gitignore templates are used before they are fetched again.
+    #[arg(short = None, long)]
+    git_ignore_cache_ttl: bool,
+    /// Local clone of github/gitignore used when the templates cannot be fetched.
+    #[arg(short = None, long)]
+    git_ignore_repo: bool,
+    /// Directory of custom .gitignore templates.
+    #[arg(short = None, long)]
+    git_ignore_dir: bool,
+    /// Directory of project templates for `git new --template`, which take precedence over the built-in ones.
+    #[arg(short = None, long)]
+    git_template_dir: bool,
+    /// License of repos created by `git new --template`: mit, apache-2.0, unlicense or none.
+    #[arg(short = None, long)]
+    git_license: bool,
+}
+
+impl EnvFlags {
+    /// Returns true if no flags were provided.
+    fn is_empty(&self) -> bool {
+        !(self.git_name
+            || self.git_email
+            || self.git_dir
+            || self.git_ignore_url
+            || self.conn_str
+            || self.google_search_api_key
+            || self.google_search_engine_id
+            || self.quote_rotation
+            || self.quote_rotation_days
+            || self.quote_api_url
+            || self.quote_feed_url
+            || self.quote_dir
+            || self.welcome_sections
+            || self.welcome_search_count
+            || self.google_search_api_url
+            || self.search_cache_ttl
+            || self.search_engine
+            || self.searxng_url
+            || self.search_lang
+            || self.search_country
+            || self.search_safe
+            || self.search_num
+            || self.http_timeout
+            || self.http_retries
+            || self.http_user_agent
+            || self.http_proxy
+            || self.git_ignore_cache_ttl
+            || self.git_ignore_repo
+            || self.git_ignore_dir
+            || self.git_template_dir
+            || self.git_license)
+    }
+}
+
 impl CommandHandler for EnvCommands {
     fn handle(&self) -> crate::Result<()> {
         match self {
-            EnvCommands::Set {
-                git_name,
-                git_email,
-                git_dir,
-                git_ignore_url,
-                conn_str,
-                google_search_api_key,
-                google_search_engine_id,
-            } => set_env(
-                git_name,
-                git_email,
-                git_dir,
-                git_ignore_url,
-                conn_str,
-                google_search_api_key,
-                google_search_engine_id,
-            ),
-            EnvCommands::Get {
-                git_name,
-                git_email,
-                git_dir,
-                git_ignore_url,
-                conn_str,
-                google_search_api_key,
-                google_search_engine_id,
-            } => get_env(
-                git_name,
-                git_email,
-                git_dir,
-                git_ignore_url,
-                conn_str,
-                google_search_api_key,
-                google_search_engine_id,
-            ),
-            EnvCommands::Reset {
-                git_name,
-                git_email,
-                git_dir,
-                git_ignore_url,
-                conn_str,
-                google_search_api_key,
-                google_search_engine_id,
-            } => reset_env(
-                git_name,
-                git_email,
-                git_dir,
-                git_ignore_url,
-                conn_str,
-                google_search_api_key,
-                google_search_engine_id,
-            ),
+            EnvCommands::Set(args) => set_env(args),
+            EnvCommands::Get(flags) => get_env(flags),
+            EnvCommands::Reset(flags) => reset_env(flags),
             EnvCommands::Save => {
                 crate::ENV.lock().unwrap().save();
                 Ok(())
@@ -161,27 +283,19 @@ impl CommandHandler for EnvCommands {
     }
 }
 
-fn set_env(
-    git_name: &Option<String>,
-    git_email: &Option<String>,
-    git_dir: &Option<String>,
-    git_ignore_url: &Option<String>,
-    conn_str: &Option<String>,
-    google_search_api_key: &Option<String>,
-    google_search_engine_id: &Option<String>,
-) -> crate::Result<()> {
+fn set_env(args: &SetArgs) -> crate::Result<()> {
     let env = &mut ENV.lock().unwrap();
-    if let Some(git_name) = git_name {
+    if let Some(git_name) = &args.git_name {
         println!("Setting {} to: {}", "Git User Name".magenta(), git_name);
         env.git_name = git_name.clone();
         println!("{} set to: {}", "Git User Name".magenta(), env.git_name);
     }
-    if let Some(git_email) = git_email {
+    if let Some(git_email) = &args.git_email {
         println!("Setting {} to: {}", "Git Email".magenta(), git_email);
         env.git_email = git_email.clone();
         println!("{} set to: {}", "Git Email".magenta(), env.git_email);
     }
-    if let Some(git_dir) = git_dir {
+    if let Some(git_dir) = &args.git_dir {
         println!("Setting {} to: {}", "Git Directory".magenta(), git_dir);
         env.git_dir = git_dir.clone().into();
         println!(
@@ -190,7 +304,7 @@ fn set_env(
             env.git_dir.display()
         );
     }
-    if let Some(git_ignore_url) = git_ignore_url {
+    if let Some(git_ignore_url) = &args.git_ignore_url {
         println!(
             "Setting {} to: {}",
             "Git Ignore URL".magenta(),
@@ -203,12 +317,12 @@ fn set_env(
             env.git_ignore_url
         );
     }
-    if let Some(conn_str) = conn_str {
+    if let Some(conn_str) = &args.conn_str {
         println!("Setting {} to: {}", "Connection String".magenta(), conn_str);
         env.conn_str = conn_str.clone();
         println!("{} set to: {}", "Connection String".magenta(), env.conn_str);
     }
-    if let Some(google_search_api_key) = google_search_api_key {
+    if let Some(google_search_api_key) = &args.google_search_api_key {
         println!(
             "Setting {} to: {}",
             "Google Search API Key".magenta(),
@@ -218,10 +332,10 @@ fn set_env(
         println!(
             "{} set to: {}",
             "Google Search API Key".magenta(),
-            env.google_search_engine_id
+            env.google_search_api_key
         );
     }
-    if let Some(google_search_engine_id) = google_search_engine_id {
+    if let Some(google_search_engine_id) = &args.google_search_engine_id {
         println!(
             "Setting {} to: {}",
             "Google Search Engine ID".magenta(),
@@ -234,117 +348,587 @@ fn set_env(
             env.google_search_engine_id
         );
     }
+    if let Some(quote_rotation) = &args.quote_rotation {
+        println!(
+            "Setting {} to: {}",
+            "Quote Rotation".magenta(),
+            quote_rotation
+        );
+        env.quote_rotation = *quote_rotation;
+        println!(
+            "{} set to: {}",
+            "Quote Rotation".magenta(),
+            env.quote_rotation
+        );
+    }
+    if let Some(quote_rotation_days) = &args.quote_rotation_days {
+        println!(
+            "Setting {} to: {}",
+            "Quote Rotation Days".magenta(),
+            quote_rotation_days
+        );
+        env.quote_rotation_days = *quote_rotation_days;
+        println!(
+            "{} set to: {}",
+            "Quote Rotation Days".magenta(),
+            env.quote_rotation_days
+        );
+    }
+    if let Some(quote_api_url) = &args.quote_api_url {
+        println!(
+            "Setting {} to: {}",
+            "Quote API URL".magenta(),
+            quote_api_url
+        );
+        env.quote_api_url = quote_api_url.clone();
+        println!(
+            "{} set to: {}",
+            "Quote API URL".magenta(),
+            env.quote_api_url
+        );
+    }
+    if let Some(quote_feed_url) = &args.quote_feed_url {
+        println!(
+            "Setting {} to: {}",
+            "Quote Feed URL".magenta(),
+            quote_feed_url
+        );
+        env.quote_feed_url = quote_feed_url.clone();
+        println!(
+            "{} set to: {}",
+            "Quote Feed URL".magenta(),
+            env.quote_feed_url
+        );
+    }
+    if let Some(quote_dir) = &args.quote_dir {
+        println!("Setting {} to: {}", "Quote Directory".magenta(), quote_dir);
+        env.quote_dir = quote_dir.clone().into();
+        println!(
+            "{} set to: {}",
+            "Quote Directory".magenta(),
+            env.quote_dir.display()
+        );
+    }
+    if let Some(welcome_sections) = &args.welcome_sections {
+        env.welcome.set_sections(welcome_sections);
+        println!(
+            "{} set to: {}",
+            "Welcome Sections".magenta(),
+            sections_display(&env.welcome.enabled_sections())
+        );
+    }
+    if let Some(welcome_search_count) = &args.welcome_search_count {
+        env.welcome.search_count = *welcome_search_count;
+        println!(
+            "{} set to: {}",
+            "Welcome Search Count".magenta(),
+            env.welcome.search_count
+        );
+    }
+    if let Some(google_search_api_url) = &args.google_search_api_url {
+        println!(
+            "Setting {} to: {}",
+            "Google Search API URL".magenta(),
+            google_search_api_url
+        );
+        env.google_search_api_url = google_search_api_url.clone();
+        println!(
+            "{} set to: {}",
+            "Google Search API URL".magenta(),
+            env.google_search_api_url
+        );
+    }
+    if let Some(search_cache_ttl) = &args.search_cache_ttl {
+        println!(
+            "Setting {} to: {}",
+            "Search Cache TTL".magenta(),
+            search_cache_ttl
+        );
+        env.search_cache_ttl = *search_cache_ttl;
+        println!(
+            "{} set to: {}",
+            "Search Cache TTL".magenta(),
+            env.search_cache_ttl
+        );
+    }
+    if let Some(search_engine) = &args.search_engine {
+        println!(
+            "Setting {} to: {}",
+            "Search Engine".magenta(),
+            search_engine
+        );
+        env.search_engine = *search_engine;
+        println!(
+            "{} set to: {}",
+            "Search Engine".magenta(),
+            env.search_engine
+        );
+    }
+    if let Some(searxng_url) = &args.searxng_url {
+        println!("Setting {} to: {}", "SearXNG URL".magenta(), searxng_url);
+        env.searxng_url = searxng_url.clone();
+        println!("{} set to: {}", "SearXNG URL".magenta(), env.searxng_url);
+    }
+    if let Some(search_lang) = &args.search_lang {
+        println!(
+            "Setting {} to: {}",
+            "Search Language".magenta(),
+            search_lang
+        );
+        env.search_lang = search_lang.clone();
+        println!(
+            "{} set to: {}",
+            "Search Language".magenta(),
+            env.search_lang
+        );
+    }
+    if let Some(search_country) = &args.search_country {
+        println!(
+            "Setting {} to: {}",
+            "Search Country".magenta(),
+            search_country
+        );
+        env.search_country = search_country.clone();
+        println!(
+            "{} set to: {}",
+            "Search Country".magenta(),
+            env.search_country
+        );
+    }
+    if let Some(search_safe) = &args.search_safe {
+        println!("Setting {} to: {}", "Safe Search".magenta(), search_safe);
+        env.search_safe = *search_safe;
+        println!("{} set to: {}", "Safe Search".magenta(), env.search_safe);
+    }
+    if let Some(search_num) = &args.search_num {
+        println!(
+            "Setting {} to: {}",
+            "Search Results Per Page".magenta(),
+            search_num
+        );
+        env.search_num = *search_num;
+        println!(
+            "{} set to: {}",
+            "Search Results Per Page".magenta(),
+            env.search_num
+        );
+    }
+    if let Some(http_timeout) = &args.http_timeout {
+        println!("Setting {} to: {}", "HTTP Timeout".magenta(), http_timeout);
+        env.http_timeout = *http_timeout;
+        println!("{} set to: {}", "HTTP Timeout".magenta(), env.http_timeout);
+    }
+    if let Some(http_retries) = &args.http_retries {
+        println!("Setting {} to: {}", "HTTP Retries".magenta(), http_retries);
+        env.http_retries = *http_retries;
+        println!("{} set to: {}", "HTTP Retries".magenta(), env.http_retries);
+    }
+    if let Some(http_user_agent) = &args.http_user_agent {
+        println!(
+            "Setting {} to: {}",
+            "HTTP User Agent".magenta(),
+            http_user_agent
+        );
+        env.http_user_agent = http_user_agent.clone();
+        println!(
+            "{} set to: {}",
+            "HTTP User Agent".magenta(),
+            env.http_user_agent
+        );
+    }
+    if let Some(http_proxy) = &args.http_proxy {
+        println!("Setting {} to: {}", "HTTP Proxy".magenta(), http_proxy);
+        env.http_proxy = http_proxy.clone();
+        println!("{} set to: {}", "HTTP Proxy".magenta(), env.http_proxy);
+    }
+    if let Some(git_ignore_cache_ttl) = &args.git_ignore_cache_ttl {
+        println!(
+            "Setting {} to: {}",
+            "Git Ignore Cache TTL".magenta(),
+            git_ignore_cache_ttl
+        );
+        env.git_ignore_cache_ttl = *git_ignore_cache_ttl;
+        println!(
+            "{} set to: {}",
+            "Git Ignore Cache TTL".magenta(),
+            env.git_ignore_cache_ttl
+        );
+    }
+    if let Some(git_ignore_repo) = &args.git_ignore_repo {
+        println!(
+            "Setting {} to: {}",
+            "Git Ignore Repo".magenta(),
+            git_ignore_repo
+        );
+        env.git_ignore_repo = git_ignore_repo.clone().into();
+        println!(
+            "{} set to: {}",
+            "Git Ignore Repo".magenta(),
+            env.git_ignore_repo.display()
+        );
+    }
+    if let Some(git_ignore_dir) = &args.git_ignore_dir {
+        println!(
+            "Setting {} to: {}",
+            "Git Ignore Directory".magenta(),
+            git_ignore_dir
+        );
+        env.git_ignore_dir = git_ignore_dir.clone().into();
+        println!(
+            "{} set to: {}",
+            "Git Ignore Directory".magenta(),
+            env.git_ignore_dir.display()
+        );
+    }
+    if let Some(git_template_dir) = &args.git_template_dir {
+        println!(
+            "Setting {} to: {}",
+            "Git Template Directory".magenta(),
+            git_template_dir
+        );
+        env.git_template_dir = git_template_dir.clone().into();
+        println!(
+            "{} set to: {}",
+            "Git Template Directory".magenta(),
+            env.git_template_dir.display()
+        );
+    }
+    if let Some(git_license) = &args.git_license {
+        println!("Setting {} to: {}", "Git License".magenta(), git_license);
+        env.git_license = git_license.clone();
+        println!("{} set to: {}", "Git License".magenta(), env.git_license);
+    }
     env.save();
     Ok(())
 }
 
-fn get_env(
-    git_name: &bool,
-    git_email: &bool,
-    git_dir: &bool,
-    git_ignore_url: &bool,
-    conn_str: &bool,
-    google_search_api_key: &bool,
-    google_search_engine_id: &bool,
-) -> crate::Result<()> {
-    let mut add_all = false;
-    if !git_name
-        && !git_email
-        && !git_dir
-        && !git_ignore_url
-        && !conn_str
-        && !google_search_api_key
-        && !google_search_engine_id
-    {
-        add_all = true;
-    }
+fn get_env(flags: &EnvFlags) -> crate::Result<()> {
+    let add_all = flags.is_empty();
     let mut env_str = String::with_capacity(256);
     let env = ENV.lock().unwrap();
-    if add_all || *git_name {
+    if add_all || flags.git_name {
         env_str.push_str(&format!(
             "{}: {}\n",
             "Git User Name".magenta(),
             env.git_name
         ));
     }
-    if add_all || *git_email {
+    if add_all || flags.git_email {
         env_str.push_str(&format!("{}: {}\n", "Git Email".magenta(), env.git_email));
     }
-    if add_all || *git_dir {
+    if add_all || flags.git_dir {
         env_str.push_str(&format!(
             "{}: {}\n",
             "Git Directory".magenta(),
             env.git_dir.display()
         ));
     }
-    if add_all || *git_ignore_url {
+    if add_all || flags.git_ignore_url {
         env_str.push_str(&format!(
             "{}: {}\n",
             "Git Ignore URL".magenta(),
             env.git_ignore_url
         ));
     }
-    if add_all || *conn_str {
+    if add_all || flags.conn_str {
         env_str.push_str(&format!(
             "{}: {}\n",
             "Connection String".magenta(),
             env.conn_str
         ));
     }
-    if add_all || *google_search_api_key {
+    if add_all || flags.google_search_api_key {
         env_str.push_str(&format!(
             "{}: {}\n",
             "Google Search API Key".magenta(),
             env.google_search_api_key
         ));
     }
-    if add_all || *google_search_engine_id {
+    if add_all || flags.google_search_engine_id {
         env_str.push_str(&format!(
             "{}: {}\n",
             "Google Search Engine ID".magenta(),
             env.google_search_engine_id
         ));
     }
+    if add_all || flags.quote_rotation {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Quote Rotation".magenta(),
+            env.quote_rotation
+        ));
+    }
+    if add_all || flags.quote_rotation_days {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Quote Rotation Days".magenta(),
+            env.quote_rotation_days
+        ));
+    }
+    if add_all || flags.quote_api_url {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Quote API URL".magenta(),
+            env.quote_api_url
+        ));
+    }
+    if add_all || flags.quote_feed_url {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Quote Feed URL".magenta(),
+            env.quote_feed_url
+        ));
+    }
+    if add_all || flags.quote_dir {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Quote Directory".magenta(),
+            env.quote_dir.display()
+        ));
+    }
+    if add_all || flags.welcome_sections {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Welcome Sections".magenta(),
+            sections_display(&env.welcome.enabled_sections())
+        ));
+    }
+    if add_all || flags.welcome_search_count {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Welcome Search Count".magenta(),
+            env.welcome.search_count
+        ));
+    }
+    if add_all || flags.google_search_api_url {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Google Search API URL".magenta(),
+            env.google_search_api_url
+        ));
+    }
+    if add_all || flags.search_cache_ttl {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Search Cache TTL".magenta(),
+            env.search_cache_ttl
+        ));
+    }
+    if add_all || flags.search_engine {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Search Engine".magenta(),
+            env.search_engine
+        ));
+    }
+    if add_all || flags.searxng_url {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "SearXNG URL".magenta(),
+            env.searxng_url
+        ));
+    }
+    if add_all || flags.search_lang {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Search Language".magenta(),
+            env.search_lang
+        ));
+    }
+    if add_all || flags.search_country {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Search Country".magenta(),
+            env.search_country
+        ));
+    }
+    if add_all || flags.search_safe {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Safe Search".magenta(),
+            env.search_safe
+        ));
+    }
+    if add_all || flags.search_num {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Search Results Per Page".magenta(),
+            env.search_num
+        ));
+    }
+    if add_all || flags.http_timeout {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "HTTP Timeout".magenta(),
+            env.http_timeout
+        ));
+    }
+    if add_all || flags.http_retries {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "HTTP Retries".magenta(),
+            env.http_retries
+        ));
+    }
+    if add_all || flags.http_user_agent {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "HTTP User Agent".magenta(),
+            env.http_user_agent
+        ));
+    }
+    if add_all || flags.http_proxy {
+        env_str.push_str(&format!("{}: {}\n", "HTTP Proxy".magenta(), env.http_proxy));
+    }
+    if add_all || flags.git_ignore_cache_ttl {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Git Ignore Cache TTL".magenta(),
+            env.git_ignore_cache_ttl
+        ));
+    }
+    if add_all || flags.git_ignore_repo {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Git Ignore Repo".magenta(),
+            env.git_ignore_repo.display()
+        ));
+    }
+    if add_all || flags.git_ignore_dir {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Git Ignore Directory".magenta(),
+            env.git_ignore_dir.display()
+        ));
+    }
+    if add_all || flags.git_template_dir {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Git Template Directory".magenta(),
+            env.git_template_dir.display()
+        ));
+    }
+    if add_all || flags.git_license {
+        env_str.push_str(&format!(
+            "{}: {}\n",
+            "Git License".magenta(),
+            env.git_license
+        ));
+    }
     print!("{}", env_str);
     Ok(())
 }
 
-fn reset_env(
-    git_name: &bool,
-    git_email: &bool,
-    git_dir: &bool,
-    git_ignore_url: &bool,
-    conn_str: &bool,
-    google_search_api_key: &bool,
-    google_search_engine_id: &bool,
-) -> crate::Result<()> {
+fn reset_env(flags: &EnvFlags) -> crate::Result<()> {
+    let reset_all = flags.is_empty();
     let default_env = Environment::default();
     let env = &mut ENV.lock().unwrap();
-    if *git_name {
+    if reset_all || flags.git_name {
         env.git_name = default_env.git_name;
     }
-    if *git_email {
+    if reset_all || flags.git_email {
         env.git_email = default_env.git_email;
     }
-    if *git_dir {
+    if reset_all || flags.git_dir {
         env.git_dir = default_env.git_dir;
     }
-    if *git_ignore_url {
+    if reset_all || flags.git_ignore_url {
         env.git_ignore_url = default_env.git_ignore_url;
     }
-    if *conn_str {
+    if reset_all || flags.conn_str {
         env.conn_str = default_env.conn_str;
     }
-    if *google_search_api_key {
+    if reset_all || flags.google_search_api_key {
         env.google_search_api_key = default_env.google_search_api_key;
     }
-    if *google_search_engine_id {
+    if reset_all || flags.google_search_engine_id {
         env.google_search_engine_id = default_env.google_search_engine_id;
     }
+    if reset_all || flags.quote_rotation {
+        env.quote_rotation = default_env.quote_rotation;
+    }
+    if reset_all || flags.quote_rotation_days {
+        env.quote_rotation_days = default_env.quote_rotation_days;
+    }
+    if reset_all || flags.quote_api_url {
+        env.quote_api_url = default_env.quote_api_url;
+    }
+    if reset_all || flags.quote_feed_url {
+        env.quote_feed_url = default_env.quote_feed_url;
+    }
+    if reset_all || flags.quote_dir {
+        env.quote_dir = default_env.quote_dir;
+    }
+    if reset_all || flags.welcome_sections {
+        env.welcome.sections = default_env.welcome.sections;
+    }
+    if reset_all || flags.welcome_search_count {
+        env.welcome.search_count = default_env.welcome.search_count;
+    }
+    if reset_all || flags.google_search_api_url {
+        env.google_search_api_url = default_env.google_search_api_url;
+    }
+    if reset_all || flags.search_cache_ttl {
+        env.search_cache_ttl = default_env.search_cache_ttl;
+    }
+    if reset_all || flags.search_engine {
+        env.search_engine = default_env.search_engine;
+    }
+    if reset_all || flags.searxng_url {
+        env.searxng_url = default_env.searxng_url;
+    }
+    if reset_all || flags.search_lang {
+        env.search_lang = default_env.search_lang;
+    }
+    if reset_all || flags.search_country {
+        env.search_country = default_env.search_country;
+    }
+    if reset_all || flags.search_safe {
+        env.search_safe = default_env.search_safe;
+    }
+    if reset_all || flags.search_num {
+        env.search_num = default_env.search_num;
+    }
+    if reset_all || flags.http_timeout {
+        env.http_timeout = default_env.http_timeout;
+    }
+    if reset_all || flags.http_retries {
+        env.http_retries = default_env.http_retries;
+    }
+    if reset_all || flags.http_user_agent {
+        env.http_user_agent = default_env.http_user_agent;
+    }
+    if reset_all || flags.http_proxy {
+        env.http_proxy = default_env.http_proxy;
+    }
+    if reset_all || flags.git_ignore_cache_ttl {
+        env.git_ignore_cache_ttl = default_env.git_ignore_cache_ttl;
+    }
+    if reset_all || flags.git_ignore_repo {
+        env.git_ignore_repo = default_env.git_ignore_repo;
+    }
+    if reset_all || flags.git_ignore_dir {
+        env.git_ignore_dir = default_env.git_ignore_dir;
+    }
+    if reset_all || flags.git_template_dir {
+        env.git_template_dir = default_env.git_template_dir;
+    }
+    if reset_all || flags.git_license {
+        env.git_license = default_env.git_license;
+    }
     env.save();
     Ok(())
 }
 
+/// Format a list of welcome sections.
+fn sections_display(sections: &[WelcomeSection]) -> String {
+    sections
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 fn get_files() -> crate::Result<()> {
     println!(
         "\n{}: {}",
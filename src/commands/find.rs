@@ -0,0 +1,111 @@
+use clap::{Args, ValueEnum};
+
+use crate::Colorize;
+use crate::database::models::find::{FindHit, MATCH_END, MATCH_START};
+use crate::database::sqlite::{FtsTable, find};
+
+use super::CommandHandler;
+
+/// Options for the `find` command.
+#[derive(Debug, Args)]
+pub struct FindArgs {
+    /// Text to find. Every word must match.
+    #[arg(required = true)]
+    text: Vec<String>,
+    /// Where to look. Defaults to everywhere.
+    #[arg(short = 'i', long = "in", value_enum, value_delimiter = ',')]
+    scopes: Vec<FindScope>,
+    /// Maximum number of matches to show.
+    #[arg(short = 'n', long, default_value_t = 20)]
+    limit: i64,
+}
+
+/// Places that can be searched with `find`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FindScope {
+    Tasks,
+    Quotes,
+    /// The search history.
+    History,
+    /// Pages saved with `web save`. Only the start of each page is searched.
+    Pages,
+}
+
+impl FindScope {
+    const ALL: [FindScope; 4] = [
+        FindScope::Tasks,
+        FindScope::Quotes,
+        FindScope::History,
+        FindScope::Pages,
+    ];
+
+    fn table(&self) -> FtsTable {
+        match self {
+            FindScope::Tasks => FtsTable::Tasks,
+            FindScope::Quotes => FtsTable::Quotes,
+            FindScope::History => FtsTable::Searches,
+            FindScope::Pages => FtsTable::Snapshots,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            FindScope::Tasks => "task",
+            FindScope::Quotes => "quote",
+            FindScope::History => "search",
+            FindScope::Pages => "page",
+        }
+    }
+}
+
+impl CommandHandler for FindArgs {
+    fn handle(&self) -> crate::Result<()> {
+        let text = self.text.join(" ");
+        let scopes = if self.scopes.is_empty() {
+            FindScope::ALL.to_vec()
+        } else {
+            self.scopes.clone()
+        };
+
+        let mut hits: Vec<(FindScope, FindHit)> = vec![];
+        for scope in scopes {
+            let found = find(scope.table(), &text, self.limit).map_err(|e| e.to_string())?;
+            hits.extend(found.into_iter().map(|hit| (scope, hit)));
+        }
+        hits.sort_by(|a, b| a.1.rank.total_cmp(&b.1.rank));
+        hits.truncate(self.limit.max(0) as usize);
+
+        if hits.is_empty() {
+            println!("No matches found.");
+            return Ok(());
+        }
+        let width = hits
+            .iter()
+            .map(|(scope, hit)| scope.label().len() + hit.id.to_string().len() + 3)
+            .max()
+            .unwrap_or(0);
+        for (scope, hit) in hits {
+            let tag = format!("{:<width$}", format!("[{} {}]", scope.label(), hit.id));
+            let mut line = format!("{} {}", tag.cyan(), highlight(&hit.title));
+            if !hit.detail.is_empty() {
+                line.push_str(&format!(" — {}", highlight(&hit.detail)));
+            }
+            println!("{}", line);
+        }
+        Ok(())
+    }
+}
+
+/// Color the matching terms of a hit.
+fn highlight(text: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in text.split([MATCH_START, MATCH_END]).enumerate() {
+        //  Every other part is inside a match.
+        if i % 2 == 1 {
+            out.push_str(&part.yellow());
+        } else {
+            out.push_str(part);
+        }
+    }
+    out.replace('\n', " ")
+}
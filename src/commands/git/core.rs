@@ -183,11 +183,7 @@ fn print(state: &mut State) {
     let stats = state.progress.as_ref().unwrap();
     let network_pct = (100 * stats.received_objects()) / stats.total_objects();
     let index_pct = (100 * stats.indexed_objects()) / stats.total_objects();
-    let co_pct = if state.total > 0 {
-        (100 * state.current) / state.total
-    } else {
-        0
-    };
+    let co_pct = (100 * state.current).checked_div(state.total).unwrap_or(0);
     let kbytes = stats.received_bytes() / 1024;
     if stats.received_objects() == stats.total_objects() {
         if !state.newline {
@@ -310,7 +306,7 @@ pub fn add_files(paths: &Vec<String>, update: Option<bool>) -> crate::Result<usi
     let items_added = RefCell::new(0_usize);
     let cb = &mut |path: &Path, _matched_spec: &[u8]| -> i32 {
         let status = repo.status_file(path).unwrap();
-        let ret = if status.contains(git2::Status::WT_MODIFIED)
+        if status.contains(git2::Status::WT_MODIFIED)
             || status.contains(git2::Status::WT_NEW)
             || status.contains(git2::Status::WT_RENAMED)
             || status.contains(git2::Status::WT_TYPECHANGE)
@@ -324,8 +320,7 @@ pub fn add_files(paths: &Vec<String>, update: Option<bool>) -> crate::Result<usi
             0
         } else {
             1
-        };
-        ret
+        }
     };
 
     let cb = if !is_update {
@@ -350,7 +345,18 @@ pub fn add_files(paths: &Vec<String>, update: Option<bool>) -> crate::Result<usi
     Ok(*items_added.borrow())
 }
 
+/// Returns true if the repository has uncommitted or untracked changes.
+pub fn is_dirty(repo: &Repository) -> Result<bool, git2::Error> {
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut opts))?;
+    Ok(statuses.iter().any(|e| e.status() != git2::Status::CURRENT))
+}
+
 /// Create a new commit that references the current HEAD.
+///
+/// If HEAD has no commit yet, such as in a new repo, the commit has no
+/// parents.
 pub fn create_commit(repo: &Repository, commit_msg: String) -> Result<Oid, git2::Error> {
     // Get the index and write it as a tree
     let mut index = repo.index()?;
@@ -358,8 +364,12 @@ pub fn create_commit(repo: &Repository, commit_msg: String) -> Result<Oid, git2:
     let tree = repo.find_tree(tree_oid)?;
 
     // Get the HEAD reference and its commit
-    let head = repo.head()?;
-    let parent_commit = head.peel_to_commit()?;
+    let parent_commit = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
 
     let name = crate::ENV.lock().unwrap().git_name.clone();
     let email = crate::ENV.lock().unwrap().git_email.clone();
@@ -368,13 +378,55 @@ pub fn create_commit(repo: &Repository, commit_msg: String) -> Result<Oid, git2:
 
     // Create the commit
     let commit_oid = repo.commit(
-        Some("HEAD"),      // the name of the reference to update
-        &sig,              // the author signature
-        &sig,              // the committer signature
-        &commit_msg,       // the commit message
-        &tree,             // the tree object this commit points to
-        &[&parent_commit], // parents of the commit
+        Some("HEAD"), // the name of the reference to update
+        &sig,         // the author signature
+        &sig,         // the committer signature
+        &commit_msg,  // the commit message
+        &tree,        // the tree object this commit points to
+        &parents,     // parents of the commit
     )?;
 
     Ok(commit_oid)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    /// Write a file in the work tree of `repo` and stage it.
+    fn stage(repo: &Repository, path: &str, text: &str) {
+        std::fs::write(repo.workdir().unwrap().join(path), text).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+    }
+
+    #[test]
+    fn first_commit_has_no_parents() {
+        let env = TestEnv::new();
+        {
+            let mut env = crate::ENV.lock().unwrap();
+            env.git_name = "Ada".into();
+            env.git_email = "ada@example.com".into();
+        }
+        let repo = Repository::init(env.dir.join("repo")).unwrap();
+        assert!(repo.head().is_err());
+
+        stage(&repo, "README.md", "# repo\n");
+        let first = create_commit(&repo, "Initial commit".to_string()).unwrap();
+        let commit = repo.find_commit(first).unwrap();
+        assert_eq!(commit.parent_count(), 0);
+        assert_eq!(commit.message(), Some("Initial commit"));
+        assert_eq!(commit.author().name(), Some("Ada"));
+        assert_eq!(commit.author().email(), Some("ada@example.com"));
+        assert!(commit.tree().unwrap().get_name("README.md").is_some());
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+
+        stage(&repo, "main.rs", "fn main() {}\n");
+        let second = create_commit(&repo, "Add main".to_string()).unwrap();
+        let commit = repo.find_commit(second).unwrap();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [first]);
+        assert_eq!(repo.head().unwrap().target(), Some(second));
+    }
+}
@@ -0,0 +1,258 @@
+//! Detect which .gitignore templates a working tree needs.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// How deep into the working tree marker files are looked for.
+const MAX_DEPTH: usize = 3;
+
+/// Directories that are never scanned, as they hold dependencies or build
+/// output rather than sources. Hidden directories are not scanned either.
+const SKIPPED_DIRS: [&str; 5] = ["target", "node_modules", "build", "dist", "zig-out"];
+
+/// A template and the marker that suggests it.
+#[derive(Debug, Clone, Copy)]
+struct Marker {
+    template: &'static str,
+    kind: MarkerKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MarkerKind {
+    /// A file with this name.
+    File(&'static str),
+    /// A file with this extension.
+    Extension(&'static str),
+    /// A directory with this name.
+    Dir(&'static str),
+}
+
+const MARKERS: [Marker; 16] = [
+    Marker {
+        template: "rust",
+        kind: MarkerKind::File("Cargo.toml"),
+    },
+    Marker {
+        template: "node",
+        kind: MarkerKind::File("package.json"),
+    },
+    Marker {
+        template: "python",
+        kind: MarkerKind::File("pyproject.toml"),
+    },
+    Marker {
+        template: "python",
+        kind: MarkerKind::File("setup.py"),
+    },
+    Marker {
+        template: "python",
+        kind: MarkerKind::File("requirements.txt"),
+    },
+    Marker {
+        template: "cmake",
+        kind: MarkerKind::File("CMakeLists.txt"),
+    },
+    Marker {
+        template: "zig",
+        kind: MarkerKind::Extension("zig"),
+    },
+    Marker {
+        template: "go",
+        kind: MarkerKind::File("go.mod"),
+    },
+    Marker {
+        template: "visualstudiocode",
+        kind: MarkerKind::Dir(".vscode"),
+    },
+    Marker {
+        template: "jetbrains",
+        kind: MarkerKind::Dir(".idea"),
+    },
+    Marker {
+        template: "macos",
+        kind: MarkerKind::File(".DS_Store"),
+    },
+    Marker {
+        template: "macos",
+        kind: MarkerKind::File("._.DS_Store"),
+    },
+    Marker {
+        template: "windows",
+        kind: MarkerKind::File("Thumbs.db"),
+    },
+    Marker {
+        template: "windows",
+        kind: MarkerKind::File("desktop.ini"),
+    },
+    Marker {
+        template: "linux",
+        kind: MarkerKind::File(".directory"),
+    },
+    Marker {
+        template: "linux",
+        kind: MarkerKind::Extension("swp"),
+    },
+];
+
+/// A template detected in a working tree.
+#[derive(Debug, Clone)]
+pub struct Detection {
+    /// Name of the template.
+    pub template: &'static str,
+    /// The first file or directory that suggested the template, relative
+    /// to the scanned directory.
+    pub evidence: PathBuf,
+}
+
+/// Find the templates a working tree needs from its marker files, in the
+/// order of [`MARKERS`].
+pub fn detect_templates(root: &Path) -> Vec<Detection> {
+    let mut found: Vec<Detection> = vec![];
+    let mut dirs = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = path.is_dir();
+            for marker in MARKERS {
+                let matches = match marker.kind {
+                    MarkerKind::File(file) => !is_dir && name == file,
+                    MarkerKind::Extension(ext) => {
+                        !is_dir && path.extension().is_some_and(|e| e == ext)
+                    }
+                    MarkerKind::Dir(marker_dir) => is_dir && name == marker_dir,
+                };
+                if matches && !found.iter().any(|d| d.template == marker.template) {
+                    found.push(Detection {
+                        template: marker.template,
+                        evidence: path.strip_prefix(root).unwrap_or(&path).to_path_buf(),
+                    });
+                }
+            }
+            if is_dir
+                && depth < MAX_DEPTH
+                && !name.starts_with('.')
+                && !SKIPPED_DIRS.contains(&name.as_str())
+            {
+                dirs.push((path, depth + 1));
+            }
+        }
+    }
+
+    found.sort_by_key(|d| {
+        MARKERS
+            .iter()
+            .position(|m| m.template == d.template)
+            .unwrap_or(usize::MAX)
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    /// Create empty files at `paths` under `root`, with their parents.
+    fn touch(root: &Path, paths: &[&str]) {
+        for path in paths {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+    }
+
+    fn detected(root: &Path) -> Vec<(&'static str, String)> {
+        detect_templates(root)
+            .into_iter()
+            .map(|d| (d.template, d.evidence.display().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn marker_files_and_extensions() {
+        let env = TestEnv::new();
+        touch(
+            &env.dir,
+            &[
+                "src/main.zig",
+                "requirements.txt",
+                "Cargo.toml",
+                ".notes.swp",
+            ],
+        );
+        assert_eq!(
+            detected(&env.dir),
+            [
+                ("rust", "Cargo.toml".to_string()),
+                ("python", "requirements.txt".to_string()),
+                ("zig", "src/main.zig".to_string()),
+                ("linux", ".notes.swp".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn editor_directories() {
+        let env = TestEnv::new();
+        touch(&env.dir, &[".vscode/settings.json", ".idea/workspace.xml"]);
+        //  A file named like a marker directory is not one.
+        touch(&env.dir, &["docs/.idea"]);
+        assert_eq!(
+            detected(&env.dir),
+            [
+                ("visualstudiocode", ".vscode".to_string()),
+                ("jetbrains", ".idea".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_evidence_is_kept() {
+        let env = TestEnv::new();
+        touch(&env.dir, &["a/setup.py", "pyproject.toml"]);
+        //  Files are looked at before the directories next to them.
+        assert_eq!(
+            detected(&env.dir),
+            [("python", "pyproject.toml".to_string())]
+        );
+    }
+
+    #[test]
+    fn scans_up_to_max_depth() {
+        let env = TestEnv::new();
+        touch(&env.dir, &["a/b/c/go.mod", "a/b/c/d/package.json"]);
+        assert_eq!(MAX_DEPTH, 3);
+        assert_eq!(detected(&env.dir), [("go", "a/b/c/go.mod".to_string())]);
+    }
+
+    #[test]
+    fn skips_dependency_build_and_hidden_directories() {
+        let env = TestEnv::new();
+        touch(
+            &env.dir,
+            &[
+                "target/debug/build.zig",
+                "node_modules/left-pad/package.json",
+                "dist/setup.py",
+                ".cache/go.mod",
+            ],
+        );
+        assert!(detected(&env.dir).is_empty());
+    }
+
+    #[test]
+    fn missing_directory() {
+        let env = TestEnv::new();
+        assert!(detect_templates(&env.dir.join("missing")).is_empty());
+    }
+}
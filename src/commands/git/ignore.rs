@@ -0,0 +1,510 @@
+//! Commands for `.gitignore` files.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Subcommand, ValueEnum};
+
+use crate::commands::CommandHandler;
+use crate::{Colorize, expand_sanitized_home};
+
+use super::detect::detect_templates;
+use super::ignore_file::IgnoreFile;
+use super::providers::{ApiProvider, IgnoreProvider, ProviderKind};
+
+/// A set of .gitignore utilities.
+#[derive(Debug, Subcommand)]
+pub(crate) enum GitIgnoreCommands {
+    /// List the available .gitignore templates.
+    List {
+        /// Filter the list of templates.
+        name: Option<String>,
+        /// Only list the templates of this provider.
+        #[arg(short = 'p', long, value_enum)]
+        provider: Option<ProviderKind>,
+        /// Fetch the list even if it is cached.
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Generate a new .gitignore file using the templates provided.
+    ///
+    /// If the `create_file` flag is set then the templates are added to the
+    /// .gitignore file of the current directory, as with `git ignore add`.
+    /// Otherwise, the output will be printed to the console.
+    ///
+    /// Templates are looked up in the custom templates, then the API, then
+    /// the local clone of github/gitignore. Prefix a template with a provider
+    /// to only look there, such as `custom:acme`.
+    Fetch {
+        /// Templates to use in the .gitignore file.
+        templates: Vec<String>,
+        /// Add the templates to the .gitignore file.
+        #[arg(long)]
+        create_file: bool,
+        /// Fetch the templates even if they are cached.
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Add templates to a .gitignore file, or update them if they were added
+    /// before.
+    ///
+    /// Each template is kept between markers so that it can be updated or
+    /// removed later. Patterns that are already in the file are left out.
+    /// Lines outside of the markers are never changed.
+    Add {
+        /// Templates to add, looked up as with `git ignore fetch`.
+        #[arg(required = true)]
+        templates: Vec<String>,
+        /// The .gitignore file to edit.
+        #[arg(short = 'f', long, default_value = ".gitignore")]
+        file: PathBuf,
+        /// Fetch the templates even if they are cached.
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Remove templates added with `git ignore add` from a .gitignore file.
+    Remove {
+        /// Templates to remove.
+        #[arg(required = true)]
+        templates: Vec<String>,
+        /// The .gitignore file to edit.
+        #[arg(short = 'f', long, default_value = ".gitignore")]
+        file: PathBuf,
+    },
+    /// Suggest templates from the marker files of a working tree, such as
+    /// `Cargo.toml` or `.vscode/`.
+    Detect {
+        /// Directory to scan.
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+        /// Add the detected templates to the directory's .gitignore file.
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Cache every template so that they can be used offline.
+    ///
+    /// Templates are fetched from the API, or read from a local clone of
+    /// github/gitignore if the API cannot be reached.
+    Sync {
+        /// Read the templates from this clone of github/gitignore instead of
+        /// the API. Defaults to the `git_ignore_repo` setting when the API
+        /// cannot be reached.
+        #[arg(long)]
+        repo: Option<PathBuf>,
+    },
+}
+
+impl CommandHandler for GitIgnoreCommands {
+    fn handle(&self) -> crate::Result<()> {
+        match self {
+            GitIgnoreCommands::List {
+                name,
+                provider,
+                refresh,
+            } => {
+                let ignore_list = list_templates(*provider, *refresh)?;
+                for ignore in ignore_list {
+                    if name
+                        .as_ref()
+                        .is_none_or(|name| ignore.contains(name.as_str()))
+                    {
+                        println!("{}", ignore);
+                    }
+                }
+                Ok(())
+            }
+            GitIgnoreCommands::Fetch {
+                templates,
+                create_file,
+                refresh,
+            } => {
+                if *create_file {
+                    return add_templates(Path::new(".gitignore"), templates, *refresh);
+                }
+                let ignore_txt = fetch_ignores(templates, *refresh)?;
+                println!("{}", ignore_txt);
+                Ok(())
+            }
+            GitIgnoreCommands::Add {
+                templates,
+                file,
+                refresh,
+            } => add_templates(file, templates, *refresh),
+            GitIgnoreCommands::Remove { templates, file } => remove_templates(file, templates),
+            GitIgnoreCommands::Detect { dir, apply } => {
+                if !dir.is_dir() {
+                    return Err(format!("{} is not a directory.", dir.display()).into());
+                }
+                let templates = print_detected(dir);
+                if templates.is_empty() {
+                    return Ok(());
+                }
+                if *apply {
+                    add_templates(&dir.join(".gitignore"), &templates, false)
+                } else {
+                    println!(
+                        "Add them with `git ignore add {}` or `git ignore detect --apply`.",
+                        templates.join(" ")
+                    );
+                    Ok(())
+                }
+            }
+            GitIgnoreCommands::Sync { repo } => {
+                let mut api = ApiProvider::new();
+                let count = match repo {
+                    Some(repo) => api.sync_repo(&expand_sanitized_home(repo))?,
+                    None => api.sync()?,
+                };
+                println!(
+                    "Cached {} templates in {}",
+                    count,
+                    api.dir.display().to_string().green()
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Get the .gitignore text of the templates from any provider.
+pub fn fetch_ignores(ignores: &[String], refresh: bool) -> crate::Result<String> {
+    if ignores.is_empty() {
+        println!("No ignore files provided.");
+        return Ok(String::new());
+    }
+    render_templates(ignores, refresh)
+}
+
+/// The names of the templates of one provider, or of every provider.
+///
+/// Providers that cannot list their templates are skipped with a warning.
+/// It is an error if none of them can.
+fn list_templates(provider: Option<ProviderKind>, refresh: bool) -> crate::Result<Vec<String>> {
+    let kinds = match provider {
+        Some(kind) => vec![kind],
+        None => ProviderKind::ALL.to_vec(),
+    };
+    let mut names = BTreeSet::new();
+    let mut errors = vec![];
+    for kind in &kinds {
+        let mut provider = kind.provider(refresh);
+        match provider.list() {
+            Ok(list) => names.extend(list),
+            Err(e) => errors.push(format!("{}: {}", provider.name(), e)),
+        }
+    }
+    if errors.len() == kinds.len() {
+        return Err(errors.join("\n").into());
+    }
+    //  Missing custom or github templates are only worth mentioning when
+    //  they were asked for.
+    if provider.is_some() || names.is_empty() {
+        for error in errors {
+            eprintln!("{}", error.yellow());
+        }
+    }
+    Ok(names.into_iter().collect())
+}
+
+/// The .gitignore text of several templates, which may come from different
+/// providers.
+///
+/// Templates that cannot be found are skipped with a warning. It is an
+/// error if none of them are found.
+fn render_templates(names: &[String], refresh: bool) -> crate::Result<String> {
+    let mut lookup = TemplateLookup::new(refresh);
+    let mut sections: Vec<String> = vec![];
+    let mut missing = vec![];
+
+    for name in names {
+        match lookup.get(name)? {
+            Some(text) => {
+                let text = text.trim().to_string();
+                if !sections.contains(&text) {
+                    sections.push(text);
+                }
+            }
+            None => missing.push(name.as_str()),
+        }
+    }
+
+    warn_missing(&missing);
+    if sections.is_empty() {
+        return Err("None of the templates were found. Run `git ignore sync` \
+            while online to use them offline."
+            .into());
+    }
+    Ok(format!("{}\n", sections.join("\n\n")))
+}
+
+/// Print the templates detected in a directory and why, returning their
+/// names.
+pub fn print_detected(dir: &Path) -> Vec<String> {
+    let detections = detect_templates(dir);
+    if detections.is_empty() {
+        println!("No templates detected in {}", dir.display());
+        return vec![];
+    }
+    println!("Detected templates:");
+    let width = detections
+        .iter()
+        .map(|d| d.template.len())
+        .max()
+        .unwrap_or(0);
+    for detection in &detections {
+        println!(
+            "  {}  {}",
+            format!("{:<width$}", detection.template).cyan(),
+            detection.evidence.display()
+        );
+    }
+    detections.iter().map(|d| d.template.to_string()).collect()
+}
+
+/// Add templates to a .gitignore file, creating it if needed.
+///
+/// Templates already in the file are updated. Patterns of the added
+/// templates that are already in hand-written lines are left out. Other
+/// templates are not changed.
+pub fn add_templates(path: &Path, names: &[String], refresh: bool) -> crate::Result<()> {
+    let mut file = read_ignore_file(path)?;
+    let mut lookup = TemplateLookup::new(refresh);
+    let mut added = vec![];
+    let mut missing = vec![];
+
+    for name in names {
+        let key = name.trim().to_lowercase();
+        match lookup.get(&key)? {
+            Some(text) => {
+                file.set_section(&key, &text);
+                file.dedupe_section(&key);
+                added.push(key);
+            }
+            None => missing.push(name.as_str()),
+        }
+    }
+    warn_missing(&missing);
+    if added.is_empty() {
+        return Err("None of the templates were found. Run `git ignore sync` \
+            while online to use them offline."
+            .into());
+    }
+    fs::write(path, file.render())?;
+    println!(
+        "Added {} to {}",
+        added.join(", ").cyan(),
+        path.display().to_string().green()
+    );
+    Ok(())
+}
+
+/// Remove managed templates from a .gitignore file.
+fn remove_templates(path: &Path, names: &[String]) -> crate::Result<()> {
+    if !path.exists() {
+        return Err(format!("{} does not exist.", path.display()).into());
+    }
+    let mut file = read_ignore_file(path)?;
+    let mut removed = vec![];
+    let mut missing = vec![];
+    for name in names {
+        let key = name.trim().to_lowercase();
+        //  Templates added from a given provider can be named without it.
+        let section = file.sections().into_iter().find(|section| {
+            *section == key || section.split_once(':').is_some_and(|(_, t)| t == key)
+        });
+        match section {
+            Some(section) if file.remove_section(&section) => removed.push(section),
+            _ => missing.push(name.as_str()),
+        }
+    }
+    if removed.is_empty() {
+        let sections = file.sections();
+        let added = if sections.is_empty() {
+            "none".to_string()
+        } else {
+            sections.join(", ")
+        };
+        return Err(format!(
+            "Not added with `git ignore add`: {}. Added templates: {}",
+            missing.join(", "),
+            added
+        )
+        .into());
+    }
+    if !missing.is_empty() {
+        eprintln!(
+            "{}",
+            format!("Templates not in the file: {}", missing.join(", ")).yellow()
+        );
+    }
+    fs::write(path, file.render())?;
+    println!(
+        "Removed {} from {}",
+        removed.join(", ").cyan(),
+        path.display().to_string().green()
+    );
+    Ok(())
+}
+
+fn read_ignore_file(path: &Path) -> crate::Result<IgnoreFile> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(IgnoreFile::parse(&text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(IgnoreFile::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn warn_missing(missing: &[&str]) {
+    if !missing.is_empty() {
+        eprintln!(
+            "{}",
+            format!("Templates not found: {}", missing.join(", ")).yellow()
+        );
+    }
+}
+
+/// Looks templates up in every provider.
+struct TemplateLookup {
+    providers: Vec<(ProviderKind, Box<dyn IgnoreProvider>)>,
+}
+
+impl TemplateLookup {
+    fn new(refresh: bool) -> Self {
+        TemplateLookup {
+            providers: ProviderKind::ALL
+                .iter()
+                .map(|kind| (*kind, kind.provider(refresh)))
+                .collect(),
+        }
+    }
+
+    /// The text of a template, from the first provider that has it.
+    ///
+    /// A name prefixed with a provider, such as `custom:acme`, is only looked
+    /// up in that provider.
+    fn get(&mut self, name: &str) -> crate::Result<Option<String>> {
+        let (kind, template) = match name.split_once(':') {
+            Some((prefix, template)) => {
+                let kind = ProviderKind::from_str(prefix, true)
+                    .map_err(|_| format!("Unknown template provider: {}", prefix))?;
+                (Some(kind), template)
+            }
+            None => (None, name),
+        };
+        Ok(self
+            .providers
+            .iter_mut()
+            .filter(|(k, _)| kind.is_none_or(|kind| kind == *k))
+            .find_map(|(_, provider)| provider.template(template)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::git::providers::{ApiProvider, DirectoryProvider},
+        testing::{StubServer, TestEnv, response},
+    };
+
+    /// A test environment with custom `rust` and `python` templates.
+    fn with_templates() -> TestEnv {
+        let env = TestEnv::new();
+        let dir = env.dir.join("templates");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("rust.gitignore"), "/target\n*.log\n").unwrap();
+        fs::write(dir.join("python.gitignore"), "__pycache__/\n*.log\n").unwrap();
+        crate::ENV.lock().unwrap().git_ignore_dir = dir;
+        env
+    }
+
+    #[test]
+    fn add_leaves_other_templates_alone() {
+        let env = with_templates();
+        let path = env.dir.join(".gitignore");
+        //  Edited by hand since it was added.
+        let python = "# >>> python (managed by ShellCommander) >>>\n\
+                      __pycache__/\n\
+                      *.log\n\
+                      .venv/\n\
+                      # <<< python <<<\n";
+        fs::write(&path, format!(".env\n/target\n\n{}", python)).unwrap();
+
+        add_templates(&path, &["Rust".to_string()], false).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            text,
+            format!(
+                ".env\n/target\n\n{}\n\
+                 # >>> rust (managed by ShellCommander) >>>\n\
+                 ### rust ###\n\
+                 *.log\n\
+                 # <<< rust <<<\n",
+                python
+            )
+        );
+    }
+
+    #[test]
+    fn remove_leaves_other_templates_alone() {
+        let env = with_templates();
+        let path = env.dir.join(".gitignore");
+        add_templates(&path, &["rust".to_string(), "python".to_string()], false).unwrap();
+
+        //  Both templates ignore `*.log`, so python still does.
+        remove_templates(&path, &["rust".to_string()]).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# >>> python (managed by ShellCommander) >>>\n\
+             ### python ###\n\
+             __pycache__/\n\
+             *.log\n\
+             # <<< python <<<\n"
+        );
+        assert!(remove_templates(&path, &["rust".to_string()]).is_err());
+    }
+
+    #[test]
+    fn lookup_order_and_prefixes() {
+        let env = with_templates();
+        let repo = env.dir.join("gitignore");
+        fs::create_dir_all(&repo).unwrap();
+        for name in ["Rust", "Go", "Zig"] {
+            fs::write(repo.join(format!("{}.gitignore", name)), "github\n").unwrap();
+        }
+        crate::ENV.lock().unwrap().git_ignore_repo = repo;
+        let server = StubServer::new(vec![
+            response("200 OK", &[], "### Go ###\napi\n"),
+            response("404 Not Found", &[], ""),
+            response("200 OK", &[], "### Rust ###\napi\n"),
+        ]);
+        crate::ENV.lock().unwrap().git_ignore_url = server.url.clone();
+        let mut api = ApiProvider::new();
+        api.dir = env.dir.join("cache");
+
+        let mut lookup = TemplateLookup {
+            providers: vec![
+                (ProviderKind::Custom, Box::new(DirectoryProvider::custom())),
+                (ProviderKind::Api, Box::new(api)),
+                (ProviderKind::Github, Box::new(DirectoryProvider::github())),
+            ],
+        };
+        let kinds: Vec<_> = lookup.providers.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, ProviderKind::ALL);
+
+        let mut get = |name: &str| lookup.get(name).unwrap();
+        assert_eq!(get("rust").unwrap(), "### rust ###\n/target\n*.log");
+        assert_eq!(get("go").unwrap(), "### Go ###\napi");
+        assert_eq!(get("zig").unwrap(), "### Zig ###\ngithub");
+        assert_eq!(get("GitHub:go").unwrap(), "### Go ###\ngithub");
+        assert_eq!(get("custom:go"), None);
+        assert_eq!(get("api:rust").unwrap(), "### Rust ###\napi");
+        assert_eq!(server.requests().len(), 3);
+
+        let error = lookup.get("gitlab:rust").unwrap_err();
+        assert_eq!(error.to_string(), "Unknown template provider: gitlab");
+    }
+}
@@ -0,0 +1,271 @@
+//! `.gitignore` files with sections managed by `git ignore add`.
+//!
+//! Each template is written between markers so that it can be updated or
+//! removed later:
+//!
+//! ```text
+//! # >>> rust (managed by ShellCommander) >>>
+//! /target
+//! # <<< rust <<<
+//! ```
+//!
+//! Lines outside of the markers are written by hand and never changed.
+
+use std::collections::HashSet;
+
+const BEGIN_PREFIX: &str = "# >>> ";
+const BEGIN_SUFFIX: &str = " (managed by ShellCommander) >>>";
+const END_PREFIX: &str = "# <<< ";
+const END_SUFFIX: &str = " <<<";
+
+/// Part of a `.gitignore` file.
+#[derive(Debug, Clone)]
+enum Block {
+    /// Lines written by hand.
+    Text(Vec<String>),
+    /// Lines of a template, managed by `git ignore add` and `remove`.
+    Section { name: String, lines: Vec<String> },
+}
+
+/// A `.gitignore` file split into hand-written lines and managed sections.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFile {
+    blocks: Vec<Block>,
+}
+
+impl IgnoreFile {
+    /// Split the text of a `.gitignore` file into blocks.
+    ///
+    /// A section without an end marker runs to the end of the file.
+    pub fn parse(text: &str) -> Self {
+        let mut blocks = vec![];
+        let mut text_lines: Vec<String> = vec![];
+        let mut section: Option<(String, Vec<String>)> = None;
+
+        for line in text.lines() {
+            if let Some((name, lines)) = &mut section {
+                if end_marker(line).is_some_and(|end| end == name) {
+                    blocks.push(Block::Section {
+                        name: name.clone(),
+                        lines: std::mem::take(lines),
+                    });
+                    section = None;
+                } else {
+                    lines.push(line.to_string());
+                }
+            } else if let Some(name) = begin_marker(line) {
+                if !text_lines.is_empty() {
+                    blocks.push(Block::Text(std::mem::take(&mut text_lines)));
+                }
+                section = Some((name.to_string(), vec![]));
+            } else {
+                text_lines.push(line.to_string());
+            }
+        }
+        if let Some((name, lines)) = section {
+            blocks.push(Block::Section { name, lines });
+        }
+        if !text_lines.is_empty() {
+            blocks.push(Block::Text(text_lines));
+        }
+        IgnoreFile { blocks }
+    }
+
+    /// Names of the managed sections, in file order.
+    pub fn sections(&self) -> Vec<String> {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Section { name, .. } => Some(name.clone()),
+                Block::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Replace the lines of a section, or add it at the end of the file.
+    pub fn set_section(&mut self, name: &str, text: &str) {
+        let new_lines: Vec<String> = text.trim().lines().map(str::to_string).collect();
+        for block in &mut self.blocks {
+            if let Block::Section {
+                name: section,
+                lines,
+            } = block
+                && section == name
+            {
+                *lines = new_lines;
+                return;
+            }
+        }
+        self.blocks.push(Block::Section {
+            name: name.to_string(),
+            lines: new_lines,
+        });
+    }
+
+    /// Remove a section. Returns whether it was found.
+    pub fn remove_section(&mut self, name: &str) -> bool {
+        let before = self.blocks.len();
+        self.blocks.retain(
+            |block| !matches!(block, Block::Section { name: section, .. } if section == name),
+        );
+        self.blocks.len() < before
+    }
+
+    /// Remove patterns from a section that are already ignored by a
+    /// hand-written line.
+    ///
+    /// Patterns shared with other sections are kept, so that removing one
+    /// section never un-ignores files another one needs. Other blocks are
+    /// not changed. Comments and empty lines are kept, but runs of empty
+    /// lines are collapsed.
+    pub fn dedupe_section(&mut self, name: &str) {
+        let seen: HashSet<String> = self
+            .blocks
+            .iter()
+            .flat_map(|block| match block {
+                Block::Text(lines) => lines.as_slice(),
+                Block::Section { .. } => &[],
+            })
+            .filter_map(|line| pattern(line))
+            .collect();
+        for block in &mut self.blocks {
+            let Block::Section {
+                name: section,
+                lines,
+            } = block
+            else {
+                continue;
+            };
+            if section != name {
+                continue;
+            }
+            let mut kept: Vec<String> = vec![];
+            for line in lines.drain(..) {
+                if pattern(&line).is_some_and(|p| seen.contains(&p)) {
+                    continue;
+                }
+                if line.trim().is_empty() && kept.last().is_none_or(|l| l.trim().is_empty()) {
+                    continue;
+                }
+                kept.push(line);
+            }
+            while kept.last().is_some_and(|l| l.trim().is_empty()) {
+                kept.pop();
+            }
+            *lines = kept;
+        }
+    }
+
+    /// The text of the file, ending in a newline.
+    pub fn render(&self) -> String {
+        let mut out: Vec<String> = vec![];
+        for block in &self.blocks {
+            match block {
+                Block::Text(lines) => {
+                    //  Blocks around a removed section leave runs of empty
+                    //  lines.
+                    let start = lines
+                        .iter()
+                        .position(|l| !l.trim().is_empty())
+                        .unwrap_or(lines.len());
+                    let blank_before = out.last().is_none_or(|l| l.trim().is_empty());
+                    let from = if blank_before {
+                        start
+                    } else {
+                        start.saturating_sub(1)
+                    };
+                    out.extend(lines[from..].iter().cloned());
+                }
+                Block::Section { name, lines } => {
+                    //  Keep sections apart from the lines before them.
+                    if out.last().is_some_and(|l| !l.trim().is_empty()) {
+                        out.push(String::new());
+                    }
+                    out.push(format!("{}{}{}", BEGIN_PREFIX, name, BEGIN_SUFFIX));
+                    out.extend(lines.iter().cloned());
+                    out.push(format!("{}{}{}", END_PREFIX, name, END_SUFFIX));
+                }
+            }
+        }
+        while out.last().is_some_and(|l| l.trim().is_empty()) {
+            out.pop();
+        }
+        if out.is_empty() {
+            return String::new();
+        }
+        format!("{}\n", out.join("\n"))
+    }
+}
+
+fn begin_marker(line: &str) -> Option<&str> {
+    line.trim_end()
+        .strip_prefix(BEGIN_PREFIX)?
+        .strip_suffix(BEGIN_SUFFIX)
+}
+
+fn end_marker(line: &str) -> Option<&str> {
+    line.trim_end()
+        .strip_prefix(END_PREFIX)?
+        .strip_suffix(END_SUFFIX)
+}
+
+/// The pattern of a line, or `None` for comments and empty lines.
+fn pattern(line: &str) -> Option<String> {
+    let line = line.trim();
+    (!line.is_empty() && !line.starts_with('#')).then(|| line.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+.env
+
+# >>> rust (managed by ShellCommander) >>>
+/target
+*.log
+# <<< rust <<<
+";
+
+    #[test]
+    fn parse_and_render() {
+        let file = IgnoreFile::parse(FILE);
+        assert_eq!(file.sections(), ["rust"]);
+        assert_eq!(file.render(), FILE);
+    }
+
+    #[test]
+    fn dedupe_against_hand_written_lines() {
+        let mut file = IgnoreFile::parse(FILE);
+        file.set_section("python", "__pycache__/\n.env\n\n\n# Logs\n*.log\n");
+        file.dedupe_section("python");
+        assert_eq!(
+            file.render(),
+            format!(
+                "{}\n{}",
+                FILE,
+                "# >>> python (managed by ShellCommander) >>>\n\
+                 __pycache__/\n\
+                 \n\
+                 # Logs\n\
+                 *.log\n\
+                 # <<< python <<<\n"
+            )
+        );
+
+        //  Other sections are not changed.
+        file.set_section("rust", "/target\n*.log\n.env");
+        file.dedupe_section("rust");
+        assert!(file.render().contains("/target\n*.log\n# <<< rust <<<"));
+        assert!(file.render().contains("# Logs\n*.log\n# <<< python <<<"));
+    }
+
+    #[test]
+    fn remove_section() {
+        let mut file = IgnoreFile::parse(FILE);
+        assert!(file.remove_section("rust"));
+        assert!(!file.remove_section("rust"));
+        assert_eq!(file.render(), ".env\n");
+    }
+}
@@ -1,17 +1,25 @@
 pub mod core;
+pub mod detect;
+pub mod ignore;
+pub mod ignore_file;
+pub mod providers;
+pub mod templates;
 
 use core::{clone_repo, create_commit};
+use ignore::{GitIgnoreCommands, add_templates, print_detected};
 use std::{
     fs,
-    io::{Error, Write},
+    io::{BufRead, Error, IsTerminal, Write, stdin, stdout},
     path::{Path, PathBuf},
 };
+use templates::{License, Variables, render_template};
 
+use chrono::Datelike;
 use clap::Subcommand;
 use git2::{Repository, Status, StatusOptions};
 
 use super::CommandHandler;
-use crate::{ENV, commands, expand_sanitized_home, sanitize_path};
+use crate::{Colorize, ENV, commands, expand_sanitized_home, sanitize_path};
 
 use super::time_now;
 
@@ -21,7 +29,21 @@ pub(crate) enum GitCommands {
     /// Initialize a new git repository.
     New {
         name: String,
+        /// Templates to add to the .gitignore file.
+        ///
+        /// If none are given, templates are detected from the files in the
+        /// repo and offered instead.
         ignores: Option<Vec<String>>,
+        /// Project template to render into the repo, such as rust, python,
+        /// zig or cmake.
+        ///
+        /// The template comes with a README and a LICENSE, and the repo is
+        /// given an initial commit.
+        #[arg(short, long)]
+        template: Option<String>,
+        /// License of the project. Defaults to `git_license`.
+        #[arg(short, long, requires = "template")]
+        license: Option<License>,
     },
     /// Clone a repository.
     Clone {
@@ -58,7 +80,12 @@ pub(crate) enum GitCommands {
 impl CommandHandler for GitCommands {
     fn handle(&self) -> crate::Result<()> {
         match self {
-            GitCommands::New { name, ignores } => new_repo(name, ignores.clone()),
+            GitCommands::New {
+                name,
+                ignores,
+                template,
+                license,
+            } => new_repo(name, ignores.clone(), template.as_deref(), *license),
             GitCommands::Clone { repo, dir } => clone_repo(repo, dir),
             GitCommands::List { json } => git_list(json.unwrap_or(false)),
             GitCommands::AddCommit { paths, changes } => {
@@ -75,8 +102,14 @@ impl CommandHandler for GitCommands {
     }
 }
 
-fn new_repo(name: &str, ignores: Option<Vec<String>>) -> crate::Result<()> {
+fn new_repo(
+    name: &str,
+    ignores: Option<Vec<String>>,
+    template: Option<&str>,
+    license: Option<License>,
+) -> crate::Result<()> {
     let author = ENV.lock().unwrap().git_name.clone();
+    let email = ENV.lock().unwrap().git_email.clone();
     let path = ENV
         .lock()
         .unwrap()
@@ -84,13 +117,38 @@ fn new_repo(name: &str, ignores: Option<Vec<String>>) -> crate::Result<()> {
         .clone()
         .join(Path::new(&author))
         .join(name);
+
+    //  Render the template first so that nothing is created if it fails.
+    let files = match template {
+        Some(template) => {
+            let vars = Variables {
+                name: path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or(name.to_string()),
+                author,
+                email,
+                year: time_now().year(),
+                license: match license {
+                    Some(license) => license,
+                    None => License::from_env()?,
+                },
+            };
+            let files = render_template(template, &vars)?;
+            if let Some(existing) = files.keys().find(|file| path.join(file).exists()) {
+                return Err(format!("{} already exists.", path.join(existing).display()).into());
+            }
+            files
+        }
+        None => Default::default(),
+    };
+
     println!("Creating new repo: {:?}", path);
     //  Create the directory.
     if !path.exists() {
         fs::create_dir_all(&path).unwrap();
     }
     let ignore_path = path.join(".gitignore");
-    let ignore_txt = fetch_ignores(ignores.unwrap().as_slice());
 
     let repo = Repository::init(&path);
 
@@ -101,18 +159,89 @@ fn new_repo(name: &str, ignores: Option<Vec<String>>) -> crate::Result<()> {
     }
 
     //  Get the repo's path from the object.
-    let path = repo.unwrap().workdir().unwrap().to_path_buf();
+    let repo = repo.unwrap();
+    let path = repo.workdir().unwrap().to_path_buf();
 
     println!("Repo created at: {:#?}", path);
 
-    let mut ignore_file = fs::File::create(&ignore_path).unwrap();
-    let write_res = ignore_file.write(ignore_txt.as_bytes());
-    if write_res.is_err() {
-        return Err(Box::new(write_res.err().unwrap()));
+    for (file, bytes) in &files {
+        let file = path.join(file);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file, bytes)?;
+    }
+    if let Some(template) = template {
+        println!("Rendered the {} template.", template.cyan());
+    }
+
+    match ignores {
+        Some(ignores) if !ignores.is_empty() => add_templates(&ignore_path, &ignores, false)?,
+        Some(_) => println!("No ignore files provided."),
+        None => offer_detected(
+            &path,
+            &ignore_path,
+            stdin().is_terminal().then(|| stdin().lock()),
+        )?,
     }
+
+    if template.is_none() {
+        return Ok(());
+    }
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let oid = create_commit(&repo, "Initial commit".to_string())?;
+    println!(
+        "Created the initial commit {}",
+        (&oid.to_string()[..7]).yellow()
+    );
     Ok(())
 }
 
+/// Offer to add the templates detected in a new repo to its .gitignore file.
+///
+/// A repo created without a template in a new directory has no files to
+/// detect templates from, so only a hint is printed. Without `input` to read
+/// the answer from, the command to add the templates is printed instead.
+fn offer_detected(
+    path: &Path,
+    ignore_path: &Path,
+    input: Option<impl BufRead>,
+) -> crate::Result<()> {
+    let is_empty = fs::read_dir(path)?
+        .flatten()
+        .all(|entry| entry.file_name() == ".git");
+    if is_empty {
+        println!(
+            "The repo is empty, so no templates were detected. Add them with \
+            `git ignore add <TEMPLATES> -f {}`.",
+            ignore_path.display()
+        );
+        return Ok(());
+    }
+    let templates = print_detected(path);
+    if templates.is_empty() {
+        return Ok(());
+    }
+    let Some(mut input) = input else {
+        println!(
+            "Add them with `git ignore add {} -f {}`.",
+            templates.join(" "),
+            ignore_path.display()
+        );
+        return Ok(());
+    };
+    print!("Add these templates to .gitignore? (y/n) ");
+    stdout().flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    if answer.trim().to_lowercase() != "y" {
+        return Ok(());
+    }
+    add_templates(ignore_path, &templates, false)
+}
+
 /// Add and commit files to the repository.
 ///
 /// If paths is None then all files in the repository will be staged.
@@ -239,6 +368,18 @@ fn traverse_git_dirs(dir: &Path, root: &PathBuf, paths: &mut Vec<Box<Path>>) ->
     Ok(())
 }
 
+/// Find the repos cloned to `git_dir`.
+pub(crate) fn find_repos() -> crate::Result<Vec<Box<Path>>> {
+    let git_dir = crate::ENV.lock().unwrap().git_dir.clone();
+    let dir = expand_sanitized_home(&git_dir);
+    if !dir.exists() {
+        return Err(format!("Directory does not exist: {}", dir.display()).into());
+    }
+    let mut paths: Vec<Box<Path>> = Vec::new();
+    traverse_git_dirs(&dir, &dir, &mut paths)?;
+    Ok(paths)
+}
+
 fn git_list(json: bool) -> crate::Result<()> {
     let env = crate::ENV.lock().unwrap();
     let dir = expand_sanitized_home(env.git_dir.clone().as_path());
@@ -264,12 +405,8 @@ fn git_list(json: bool) -> crate::Result<()> {
                     println!("{:?}", sanitize_path(&path));
                 }
             } else {
-                let json = serde_json::to_string_pretty(&paths);
-                if json.is_err() {
-                    return Err(Box::new(json.err().unwrap()));
-                } else {
-                    println!("{}", json.unwrap());
-                }
+                let json = serde_json::to_string_pretty(&paths)?;
+                println!("{}", json);
             }
             Ok(())
         }
@@ -277,81 +414,53 @@ fn git_list(json: bool) -> crate::Result<()> {
     }
 }
 
-/// A set of .gitignore utilities.
-#[derive(Debug, Subcommand)]
-pub(crate) enum GitIgnoreCommands {
-    /// List the available .gitignore templates.
-    List {
-        /// Filter the list of templates.
-        name: Option<String>,
-    },
-    /// Generate a new .gitignore file using the templates provided.
-    ///
-    /// If the `create_file` flag is set then the file will be created in the
-    /// current directory. Otherwise, the output will be printed to the console.
-    Fetch {
-        /// Templates to use in the .gitignore file.
-        templates: Vec<String>,
-        /// Create a .gitignore file.
-        #[arg(long)]
-        create_file: bool,
-    },
-}
-
-impl CommandHandler for GitIgnoreCommands {
-    fn handle(&self) -> crate::Result<()> {
-        match self {
-            GitIgnoreCommands::List { name } => {
-                let ignore_list = get_ignore_list(name);
-                for ignore in ignore_list {
-                    println!("{}", ignore);
-                }
-                Ok(())
-            }
-            GitIgnoreCommands::Fetch {
-                templates,
-                create_file,
-            } => {
-                let ignore_txt = fetch_ignores(templates);
-                if *create_file {
-                    let mut ignore_file = fs::File::create(".gitignore").unwrap();
-                    let write_res = ignore_file.write(ignore_txt.as_bytes());
-                    if write_res.is_err() {
-                        return Err(Box::new(write_res.err().unwrap()));
-                    }
-                } else {
-                    println!("{}", ignore_txt);
-                }
-                Ok(())
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    /// A test environment with a custom `rust` template and a new repo.
+    fn new_repo() -> (TestEnv, PathBuf, PathBuf) {
+        let env = TestEnv::new();
+        let templates = env.dir.join("templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(templates.join("rust.gitignore"), "/target\n").unwrap();
+        ENV.lock().unwrap().git_ignore_dir = templates;
+
+        let path = env.dir.join("repo");
+        Repository::init(&path).unwrap();
+        let ignore_path = path.join(".gitignore");
+        (env, path, ignore_path)
     }
-}
 
-fn fetch_ignores(ignores: &[String]) -> String {
-    if ignores.is_empty() {
-        println!("No ignore files provided.");
-        return String::new();
+    #[test]
+    fn offer_detected_in_empty_repo() {
+        let (_env, path, ignore_path) = new_repo();
+        offer_detected(&path, &ignore_path, Some("y\n".as_bytes())).unwrap();
+        assert!(!ignore_path.exists());
     }
-    let url = "https://www.toptal.com/developers/gitignore/api/";
-    let full_url = format!("{}{}", url, ignores.join(","));
-    let res = reqwest::blocking::get(full_url).unwrap();
 
-    res.text().unwrap()
-}
+    #[test]
+    fn offer_detected_adds_accepted_templates() {
+        let (_env, path, ignore_path) = new_repo();
+        fs::write(path.join("Cargo.toml"), "").unwrap();
+        offer_detected(&path, &ignore_path, Some("Y\n".as_bytes())).unwrap();
+        assert_eq!(
+            fs::read_to_string(&ignore_path).unwrap(),
+            "# >>> rust (managed by ShellCommander) >>>\n\
+             ### rust ###\n\
+             /target\n\
+             # <<< rust <<<\n"
+        );
+    }
 
-fn get_ignore_list(name: &Option<String>) -> Vec<String> {
-    let url = "https://www.toptal.com/developers/gitignore/api/list?format=lines";
-    // println!("Url: {}", url);
-    let res = reqwest::blocking::get(url).unwrap();
-    // println!("{:#?}", res);
-    let body = res.text().unwrap();
-    if name.is_none() {
-        body.lines().map(|x| x.to_string()).collect()
-    } else {
-        body.lines()
-            .filter(|x| x.contains(name.clone().unwrap().as_str()))
-            .map(|x| x.to_string())
-            .collect()
+    #[test]
+    fn offer_detected_leaves_declined_templates_out() {
+        let (_env, path, ignore_path) = new_repo();
+        fs::write(path.join("Cargo.toml"), "").unwrap();
+        offer_detected(&path, &ignore_path, Some("n\n".as_bytes())).unwrap();
+        assert!(!ignore_path.exists());
+        offer_detected(&path, &ignore_path, None::<&[u8]>).unwrap();
+        assert!(!ignore_path.exists());
     }
 }
@@ -0,0 +1,575 @@
+//! Providers of `.gitignore` templates.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::{Colorize, expand_sanitized_home, http};
+
+/// Name of the cached list of templates.
+const LIST_FILE: &str = "list.txt";
+
+/// A provider of .gitignore templates.
+pub trait IgnoreProvider {
+    /// A short description of where the templates come from.
+    fn name(&self) -> String;
+    /// The lowercase names of the templates the provider offers, sorted.
+    fn list(&mut self) -> crate::Result<Vec<String>>;
+    /// The text of a template, or `None` if the provider does not have it.
+    fn template(&mut self, name: &str) -> Option<String>;
+}
+
+/// The kinds of template providers, in the order templates are looked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProviderKind {
+    /// A directory of custom templates. Defaults to `git_ignore_dir`.
+    Custom,
+    /// The gitignore.io API at `git_ignore_url`, cached for offline use.
+    Api,
+    /// A local clone of github/gitignore. Defaults to `git_ignore_repo`.
+    Github,
+}
+
+impl ProviderKind {
+    /// Every provider, in the order templates are looked up.
+    pub const ALL: [ProviderKind; 3] = [
+        ProviderKind::Custom,
+        ProviderKind::Api,
+        ProviderKind::Github,
+    ];
+
+    /// Create the provider. `refresh` makes the API ignore its cache.
+    pub fn provider(&self, refresh: bool) -> Box<dyn IgnoreProvider> {
+        match self {
+            ProviderKind::Custom => Box::new(DirectoryProvider::custom()),
+            ProviderKind::Api => Box::new(ApiProvider::new().refresh(refresh)),
+            ProviderKind::Github => Box::new(DirectoryProvider::github()),
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.to_possible_value().unwrap();
+        write!(f, "{}", value.get_name())
+    }
+}
+
+/// A template of the API's full list.
+#[derive(Debug, Deserialize)]
+struct ApiTemplate {
+    contents: String,
+}
+
+/// Templates from the gitignore.io API.
+///
+/// Templates are cached for `git_ignore_cache_ttl` seconds, under
+/// `CACHE_DIR/gitignore` in a directory named by a hash of the API URL, so
+/// that changing `git_ignore_url` does not use templates of another API.
+/// When the API cannot be reached, cached templates are used even if they
+/// are stale.
+pub struct ApiProvider {
+    /// Base URL of the API, ending in a slash.
+    url: String,
+    /// Directory of the cache.
+    pub dir: PathBuf,
+    /// How long cached templates are used before they are fetched again.
+    ttl: Duration,
+    /// Local clone of github/gitignore to sync from when the API cannot be
+    /// reached.
+    repo: PathBuf,
+    /// Fetch from the API even if the cache is fresh.
+    refresh: bool,
+    /// Set once the API could not be reached, so that it is not tried for
+    /// every template.
+    offline: bool,
+}
+
+impl ApiProvider {
+    pub fn new() -> Self {
+        let env = crate::ENV.lock().unwrap();
+        let url = format!("{}/", env.git_ignore_url.trim().trim_end_matches('/'));
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        ApiProvider {
+            dir: crate::CACHE_DIR
+                .join("gitignore")
+                .join(format!("{:016x}", hasher.finish())),
+            url,
+            ttl: Duration::from_secs(env.git_ignore_cache_ttl),
+            repo: expand_sanitized_home(&env.git_ignore_repo),
+            refresh: false,
+            offline: false,
+        }
+    }
+
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Cache every template from the API, or from the local clone of
+    /// github/gitignore if the API cannot be reached.
+    pub fn sync(&mut self) -> crate::Result<usize> {
+        let body = match self.get(&format!("{}list?format=json", self.url)) {
+            Ok(body) => body,
+            Err(e) if self.repo.is_dir() => {
+                eprintln!(
+                    "{}",
+                    format!("{}\nReading templates from {}", e, self.repo.display()).yellow()
+                );
+                let repo = self.repo.clone();
+                return self.sync_repo(&repo);
+            }
+            Err(e) => return Err(e),
+        };
+        let templates: BTreeMap<String, ApiTemplate> = serde_json::from_str(&body)?;
+        for (key, template) in &templates {
+            write_cache(
+                &self.dir.join(format!("{}.gitignore", key)),
+                template.contents.trim(),
+            )?;
+        }
+        let list: Vec<&str> = templates.keys().map(String::as_str).collect();
+        write_cache(&self.dir.join(LIST_FILE), &list.join("\n"))?;
+        Ok(templates.len())
+    }
+
+    /// Cache every template of a clone of github/gitignore.
+    pub fn sync_repo(&mut self, repo: &Path) -> crate::Result<usize> {
+        let templates = directory_templates(repo);
+        if templates.is_empty() {
+            return Err(format!("No .gitignore templates found in {}", repo.display()).into());
+        }
+        for (key, file) in &templates {
+            if let Some(text) = read_template_file(file) {
+                write_cache(&self.dir.join(format!("{}.gitignore", key)), &text)?;
+            }
+        }
+        let list: Vec<&str> = templates.keys().map(String::as_str).collect();
+        write_cache(&self.dir.join(LIST_FILE), &list.join("\n"))?;
+        Ok(templates.len())
+    }
+
+    /// Read a cached file if it is younger than the TTL and a refresh was
+    /// not asked for.
+    fn read_fresh(&self, path: &Path) -> Option<String> {
+        if self.refresh {
+            return None;
+        }
+        let modified = fs::metadata(path).ok()?.modified().ok()?;
+        let age = SystemTime::now().duration_since(modified).ok()?;
+        if age > self.ttl {
+            return None;
+        }
+        fs::read_to_string(path).ok()
+    }
+
+    /// Get a page from the API, unless it could not be reached before.
+    fn get(&mut self, url: &str) -> crate::Result<String> {
+        if self.offline {
+            return Err(format!("{} cannot be reached.", self.url).into());
+        }
+        let text = http::get_text(url).inspect_err(|e| {
+            //  Unknown templates are not a sign of being offline.
+            if !matches!(e, http::HttpError::Status { code: 404, .. }) {
+                self.offline = true;
+            }
+        })?;
+        Ok(text)
+    }
+}
+
+impl IgnoreProvider for ApiProvider {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn list(&mut self) -> crate::Result<Vec<String>> {
+        let path = self.dir.join(LIST_FILE);
+        if let Some(body) = self.read_fresh(&path) {
+            return Ok(parse_list(&body));
+        }
+        match self.get(&format!("{}list?format=lines", self.url)) {
+            Ok(body) => {
+                //  Failing to cache should not fail the command.
+                let _ = write_cache(&path, &body);
+                Ok(parse_list(&body))
+            }
+            Err(e) => match fs::read_to_string(&path) {
+                Ok(body) => Ok(parse_list(&body)),
+                Err(_) => Err(e),
+            },
+        }
+    }
+
+    fn template(&mut self, name: &str) -> Option<String> {
+        let key = name.trim().to_lowercase();
+        //  Names become file names in the cache.
+        if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\']) {
+            return None;
+        }
+        let path = self.dir.join(format!("{}.gitignore", key));
+        if let Some(text) = self.read_fresh(&path) {
+            return Some(text);
+        }
+        if let Ok(body) = self.get(&format!("{}{}", self.url, key)) {
+            let text = strip_api_comments(&body);
+            let _ = write_cache(&path, &text);
+            return Some(text);
+        }
+        fs::read_to_string(&path).ok()
+    }
+}
+
+/// Templates stored as `.gitignore` files in a directory, such as a clone of
+/// github/gitignore or a directory of custom templates.
+///
+/// Files in subdirectories are included, but templates at the top of the
+/// directory take precedence. The directory is read once, when a template is
+/// first asked for.
+pub struct DirectoryProvider {
+    kind: ProviderKind,
+    path: PathBuf,
+    templates: Option<BTreeMap<String, PathBuf>>,
+}
+
+impl DirectoryProvider {
+    /// The directory of custom templates.
+    pub fn custom() -> Self {
+        let path = expand_sanitized_home(&crate::ENV.lock().unwrap().git_ignore_dir);
+        DirectoryProvider::new(ProviderKind::Custom, path)
+    }
+
+    /// The local clone of github/gitignore.
+    pub fn github() -> Self {
+        let path = expand_sanitized_home(&crate::ENV.lock().unwrap().git_ignore_repo);
+        DirectoryProvider::new(ProviderKind::Github, path)
+    }
+
+    fn new(kind: ProviderKind, path: PathBuf) -> Self {
+        DirectoryProvider {
+            kind,
+            path,
+            templates: None,
+        }
+    }
+
+    /// The template files of the directory, keyed by their lowercase name.
+    fn templates(&mut self) -> &BTreeMap<String, PathBuf> {
+        self.templates
+            .get_or_insert_with(|| directory_templates(&self.path))
+    }
+}
+
+impl IgnoreProvider for DirectoryProvider {
+    fn name(&self) -> String {
+        format!("{} ({})", self.kind, self.path.display())
+    }
+
+    fn list(&mut self) -> crate::Result<Vec<String>> {
+        if !self.path.is_dir() {
+            return Err(format!("{} is not a directory.", self.path.display()).into());
+        }
+        Ok(self.templates().keys().cloned().collect())
+    }
+
+    fn template(&mut self, name: &str) -> Option<String> {
+        let file = self.templates().get(&name.trim().to_lowercase())?;
+        read_template_file(file)
+    }
+}
+
+fn parse_list(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Remove the comments the API adds around the templates it returns.
+fn strip_api_comments(body: &str) -> String {
+    body.lines()
+        .filter(|line| {
+            !line.starts_with("# Created by ")
+                && !line.starts_with("# Edit at ")
+                && !line.starts_with("# End of ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// The `.gitignore` files of a directory, keyed by their lowercase name.
+///
+/// Files closer to the top of the directory take precedence, so a clone of
+/// github/gitignore prefers its main templates over those in `Global` and
+/// `community`.
+fn directory_templates(dir: &Path) -> BTreeMap<String, PathBuf> {
+    let mut found: HashMap<String, (usize, PathBuf)> = HashMap::new();
+    let mut dirs = vec![(dir.to_path_buf(), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if !entry.file_name().to_string_lossy().starts_with('.') {
+                    dirs.push((path, depth + 1));
+                }
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "gitignore") {
+                continue;
+            }
+            let Some(stem) = path.file_stem() else {
+                continue;
+            };
+            let key = stem.to_string_lossy().to_lowercase();
+            if found.get(&key).is_none_or(|(d, _)| depth < *d) {
+                found.insert(key, (depth, path));
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(key, (_, path))| (key, path))
+        .collect()
+}
+
+/// Read a template file, headed like the templates of the API.
+fn read_template_file(path: &Path) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    let name = path.file_stem()?.to_string_lossy();
+    Some(format!("### {} ###\n{}", name, text.trim()))
+}
+
+fn write_cache(path: &Path, body: &str) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, body)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{StubServer, TestEnv, response};
+
+    const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+    /// A provider for the API of `server`, caching under the test directory.
+    fn api(env: &TestEnv, server: &StubServer) -> ApiProvider {
+        crate::ENV.lock().unwrap().git_ignore_url = server.url.clone();
+        let mut provider = ApiProvider::new();
+        provider.dir = env.dir.join("cache");
+        provider.ttl = DAY;
+        provider
+    }
+
+    /// Cache a template that was fetched `age` ago.
+    fn cache(provider: &ApiProvider, name: &str, text: &str, age: Duration) {
+        let path = provider.dir.join(name);
+        write_cache(&path, text).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    }
+
+    fn ok(body: &str) -> crate::testing::Reply {
+        response("200 OK", &[], body)
+    }
+
+    #[test]
+    fn fresh_cache_is_used_until_the_ttl() {
+        let env = TestEnv::new();
+        let server = StubServer::new(vec![ok("# Created by https://example.com\n\
+             # Edit at https://example.com\n\n\
+             ### Rust ###\n/target\n\n\
+             # End of https://example.com\n")]);
+        let mut provider = api(&env, &server);
+
+        cache(&provider, "rust.gitignore", "cached", Duration::ZERO);
+        assert_eq!(provider.template("Rust").as_deref(), Some("cached"));
+        assert!(server.requests().is_empty());
+
+        cache(&provider, "rust.gitignore", "cached", 2 * DAY);
+        assert_eq!(
+            provider.template("rust").as_deref(),
+            Some("### Rust ###\n/target")
+        );
+        assert!(server.requests()[0].starts_with("GET /rust "));
+        assert_eq!(
+            fs::read_to_string(provider.dir.join("rust.gitignore")).unwrap(),
+            "### Rust ###\n/target"
+        );
+    }
+
+    #[test]
+    fn refresh_ignores_a_fresh_cache() {
+        let env = TestEnv::new();
+        let server = StubServer::new(vec![ok("go\nrust\n"), ok("### Rust ###\n/target\n")]);
+        let mut provider = api(&env, &server).refresh(true);
+        cache(&provider, LIST_FILE, "rust", Duration::ZERO);
+        cache(&provider, "rust.gitignore", "cached", Duration::ZERO);
+
+        assert_eq!(provider.list().unwrap(), ["go", "rust"]);
+        assert_eq!(
+            provider.template("rust").as_deref(),
+            Some("### Rust ###\n/target")
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn stale_cache_is_used_when_offline() {
+        let env = TestEnv::new();
+        //  Nothing listens at the URL once the server is done.
+        let server = StubServer::new(vec![]);
+        let mut provider = api(&env, &server);
+        cache(&provider, LIST_FILE, "rust\ngo", 2 * DAY);
+        cache(&provider, "rust.gitignore", "stale", 2 * DAY);
+
+        assert_eq!(provider.template("rust").as_deref(), Some("stale"));
+        assert!(provider.offline);
+        assert_eq!(provider.list().unwrap(), ["go", "rust"]);
+        assert_eq!(provider.template("go"), None);
+
+        fs::remove_file(provider.dir.join(LIST_FILE)).unwrap();
+        assert!(provider.list().is_err());
+    }
+
+    #[test]
+    fn unknown_templates_are_not_a_sign_of_being_offline() {
+        let env = TestEnv::new();
+        let server = StubServer::new(vec![
+            response("404 Not Found", &[], "not found"),
+            ok("### Go ###\n/bin\n"),
+        ]);
+        let mut provider = api(&env, &server);
+        assert_eq!(provider.template("nope"), None);
+        assert!(!provider.offline);
+        assert_eq!(provider.template("go").as_deref(), Some("### Go ###\n/bin"));
+        //  Names are never used as paths.
+        assert_eq!(provider.template("../go"), None);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    /// A clone of github/gitignore.
+    fn github_repo(env: &TestEnv) -> PathBuf {
+        let repo = env.dir.join("gitignore");
+        for (path, text) in [
+            ("Rust.gitignore", "/target\n"),
+            ("Global/Rust.gitignore", "global\n"),
+            ("Global/macOS.gitignore", ".DS_Store\n"),
+            ("community/Zig.gitignore", "zig-out/\n"),
+            (".github/Ignored.gitignore", "ignored\n"),
+            ("README.md", "# gitignore\n"),
+        ] {
+            write_cache(&repo.join(path), text).unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn sync_repo_caches_every_template() {
+        let env = TestEnv::new();
+        let server = StubServer::new(vec![]);
+        let mut provider = api(&env, &server);
+        let repo = github_repo(&env);
+
+        assert_eq!(provider.sync_repo(&repo).unwrap(), 3);
+        assert_eq!(provider.list().unwrap(), ["macos", "rust", "zig"]);
+        //  Templates at the top of the clone take precedence.
+        assert_eq!(
+            provider.template("rust").as_deref(),
+            Some("### Rust ###\n/target")
+        );
+        assert!(server.requests().is_empty());
+
+        assert!(provider.sync_repo(&env.dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn sync_falls_back_to_the_clone_when_offline() {
+        let env = TestEnv::new();
+        let server = StubServer::new(vec![]);
+        crate::ENV.lock().unwrap().git_ignore_repo = github_repo(&env);
+        let mut provider = api(&env, &server);
+
+        assert_eq!(provider.sync().unwrap(), 3);
+        assert_eq!(
+            provider.template("zig").as_deref(),
+            Some("### Zig ###\nzig-out/")
+        );
+    }
+
+    #[test]
+    fn sync_caches_the_full_list_of_the_api() {
+        let env = TestEnv::new();
+        let server = StubServer::new(vec![ok(
+            r#"{"go": {"contents": "\n### Go ###\n/bin\n"}, "rust": {"contents": "/target"}}"#,
+        )]);
+        let mut provider = api(&env, &server);
+
+        assert_eq!(provider.sync().unwrap(), 2);
+        assert!(server.requests()[0].starts_with("GET /list?format=json "));
+        assert_eq!(provider.list().unwrap(), ["go", "rust"]);
+        assert_eq!(provider.template("go").as_deref(), Some("### Go ###\n/bin"));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn each_api_has_its_own_cache() {
+        let _env = TestEnv::new();
+        crate::ENV.lock().unwrap().git_ignore_url = "https://one.example/api/".to_string();
+        let one = ApiProvider::new();
+        crate::ENV.lock().unwrap().git_ignore_url = " https://one.example/api ".to_string();
+        let same = ApiProvider::new();
+        crate::ENV.lock().unwrap().git_ignore_url = "https://two.example/api".to_string();
+        let two = ApiProvider::new();
+
+        assert_eq!(one.url, "https://one.example/api/");
+        assert_eq!(one.dir, same.dir);
+        assert_ne!(one.dir, two.dir);
+        assert!(one.dir.starts_with(crate::CACHE_DIR.join("gitignore")));
+    }
+
+    #[test]
+    fn directory_is_read_once() {
+        let env = TestEnv::new();
+        let repo = github_repo(&env);
+        let mut provider = DirectoryProvider::new(ProviderKind::Github, repo.clone());
+        assert_eq!(provider.list().unwrap(), ["macos", "rust", "zig"]);
+
+        write_cache(&repo.join("Go.gitignore"), "/bin\n").unwrap();
+        assert_eq!(provider.template("go"), None);
+        assert_eq!(
+            provider.template(" ZIG ").as_deref(),
+            Some("### Zig ###\nzig-out/")
+        );
+        assert_eq!(
+            DirectoryProvider::new(ProviderKind::Github, repo)
+                .template("go")
+                .as_deref(),
+            Some("### Go ###\n/bin")
+        );
+    }
+}
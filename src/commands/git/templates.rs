@@ -0,0 +1,348 @@
+//! Project templates rendered into new repos by `git new --template`.
+//!
+//! A template is a directory of files. Templates in `git_template_dir` take
+//! precedence over the built-in ones. The text of each file, and its path,
+//! can use these variables:
+//!
+//! - `{{name}}`: name of the repo.
+//! - `{{author}}` and `{{email}}`: the `git_name` and `git_email` settings.
+//! - `{{year}}`: the current year.
+//! - `{{license}}`: the chosen license, such as `MIT`.
+//! - `{{license_notice}}`: a sentence about the license for the README.
+//!
+//! A `.tmpl` extension is removed from file names, so that templates can
+//! hold files such as `Cargo.toml` without being mistaken for them.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+
+use crate::expand_sanitized_home;
+
+/// The README added to every template that does not have its own.
+const README: &str = include_str!("../../../rsrc/markdown-template.md");
+
+/// The built-in templates, as paths and the text of their files.
+const BUILTIN: [(&str, &[(&str, &str)]); 4] = [
+    (
+        "rust",
+        &[
+            (
+                "Cargo.toml.tmpl",
+                include_str!("../../../rsrc/templates/rust/Cargo.toml.tmpl"),
+            ),
+            (
+                "src/main.rs",
+                include_str!("../../../rsrc/templates/rust/src/main.rs"),
+            ),
+        ],
+    ),
+    (
+        "python",
+        &[
+            (
+                "pyproject.toml",
+                include_str!("../../../rsrc/templates/python/pyproject.toml"),
+            ),
+            (
+                "main.py",
+                include_str!("../../../rsrc/templates/python/main.py"),
+            ),
+        ],
+    ),
+    (
+        "zig",
+        &[
+            (
+                "build.zig",
+                include_str!("../../../rsrc/templates/zig/build.zig"),
+            ),
+            (
+                "src/main.zig",
+                include_str!("../../../rsrc/templates/zig/src/main.zig"),
+            ),
+        ],
+    ),
+    (
+        "cmake",
+        &[
+            (
+                "CMakeLists.txt",
+                include_str!("../../../rsrc/templates/cmake/CMakeLists.txt"),
+            ),
+            (
+                "src/main.cpp",
+                include_str!("../../../rsrc/templates/cmake/src/main.cpp"),
+            ),
+        ],
+    ),
+];
+
+/// Licenses a new repo can be created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum License {
+    Mit,
+    #[value(name = "apache-2.0")]
+    Apache2,
+    Unlicense,
+    /// Do not add a LICENSE file.
+    None,
+}
+
+impl License {
+    /// The license set by `git_license`.
+    pub fn from_env() -> crate::Result<Self> {
+        let value = crate::ENV.lock().unwrap().git_license.clone();
+        License::from_str(value.trim(), true)
+            .map_err(|_| format!("Invalid git_license setting: {}", value).into())
+    }
+
+    /// The SPDX identifier of the license.
+    pub fn id(&self) -> &'static str {
+        match self {
+            License::Mit => "MIT",
+            License::Apache2 => "Apache-2.0",
+            License::Unlicense => "Unlicense",
+            License::None => "None",
+        }
+    }
+
+    /// The text of the LICENSE file, if any.
+    fn text(&self) -> Option<&'static str> {
+        match self {
+            License::Mit => Some(include_str!("../../../rsrc/licenses/MIT.txt")),
+            License::Apache2 => Some(include_str!("../../../rsrc/licenses/Apache-2.0.txt")),
+            License::Unlicense => Some(include_str!("../../../rsrc/licenses/Unlicense.txt")),
+            License::None => None,
+        }
+    }
+
+    /// The paragraph of the README about the license.
+    fn notice(&self) -> String {
+        match self {
+            License::None => "This project is not licensed yet.".to_string(),
+            license => format!(
+                "Licensed under the {} license. See [LICENSE](LICENSE).",
+                license.id()
+            ),
+        }
+    }
+}
+
+/// Values of the variables in template files.
+#[derive(Debug, Clone)]
+pub struct Variables {
+    pub name: String,
+    pub author: String,
+    pub email: String,
+    pub year: i32,
+    pub license: License,
+}
+
+impl Variables {
+    /// Replace the variables in a text.
+    fn render(&self, text: &str) -> String {
+        text.replace("{{name}}", &self.name)
+            .replace("{{author}}", &self.author)
+            .replace("{{email}}", &self.email)
+            .replace("{{year}}", &self.year.to_string())
+            .replace("{{license}}", self.license.id())
+            .replace("{{license_notice}}", &self.license.notice())
+    }
+}
+
+/// The names of the available templates, sorted.
+pub fn template_names() -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN.iter().map(|(name, _)| name.to_string()).collect();
+    if let Ok(entries) = fs::read_dir(template_dir()) {
+        names.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string()),
+        );
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Render a template, returning the files of the new repo keyed by their
+/// path relative to it.
+///
+/// A README and the LICENSE are added unless the template has its own.
+pub fn render_template(
+    template: &str,
+    vars: &Variables,
+) -> crate::Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let dir = template_dir().join(template);
+    let sources: Vec<(String, Vec<u8>)> = if !template.contains(['/', '\\']) && dir.is_dir() {
+        read_template_dir(&dir)?
+    } else if let Some((_, files)) = BUILTIN.iter().find(|(name, _)| *name == template) {
+        files
+            .iter()
+            .map(|(path, text)| (path.to_string(), text.as_bytes().to_vec()))
+            .collect()
+    } else {
+        return Err(format!(
+            "Unknown template: {}. Available templates: {}",
+            template,
+            template_names().join(", ")
+        )
+        .into());
+    };
+
+    let mut files = BTreeMap::new();
+    for (path, bytes) in sources {
+        let path = vars.render(path.strip_suffix(".tmpl").unwrap_or(&path));
+        //  Files that are not text, such as images, are copied as they are.
+        let bytes = match String::from_utf8(bytes) {
+            Ok(text) => vars.render(&text).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        files.insert(PathBuf::from(path), bytes);
+    }
+    files
+        .entry(PathBuf::from("README.md"))
+        .or_insert_with(|| vars.render(README).into_bytes());
+    if let Some(text) = vars.license.text() {
+        files
+            .entry(PathBuf::from("LICENSE"))
+            .or_insert_with(|| vars.render(text).into_bytes());
+    }
+    Ok(files)
+}
+
+fn template_dir() -> PathBuf {
+    expand_sanitized_home(&crate::ENV.lock().unwrap().git_template_dir)
+}
+
+/// The files of a template directory, with their paths relative to it.
+fn read_template_dir(root: &Path) -> crate::Result<Vec<(String, Vec<u8>)>> {
+    let mut files = vec![];
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if entry.file_name() != ".git" {
+                    dirs.push(path);
+                }
+                continue;
+            }
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            //  Templates use forward slashes on every platform.
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, fs::read(&path)?));
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    fn vars(license: License) -> Variables {
+        Variables {
+            name: "demo".to_string(),
+            author: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            year: 2025,
+            license,
+        }
+    }
+
+    /// A test environment whose `git_template_dir` is a new directory.
+    fn with_template_dir() -> (TestEnv, PathBuf) {
+        let env = TestEnv::new();
+        let dir = env.dir.join("templates");
+        fs::create_dir_all(&dir).unwrap();
+        crate::ENV.lock().unwrap().git_template_dir = dir.clone();
+        (env, dir)
+    }
+
+    fn text(files: &BTreeMap<PathBuf, Vec<u8>>, path: &str) -> String {
+        String::from_utf8(files[Path::new(path)].clone()).unwrap()
+    }
+
+    #[test]
+    fn builtin_template() {
+        let (_env, _dir) = with_template_dir();
+        let files = render_template("rust", &vars(License::Mit)).unwrap();
+        let paths: Vec<_> = files.keys().map(|p| p.display().to_string()).collect();
+        assert_eq!(paths, ["Cargo.toml", "LICENSE", "README.md", "src/main.rs"]);
+        assert!(text(&files, "Cargo.toml").contains("name = \"demo\""));
+        assert!(text(&files, "LICENSE").contains("Copyright (c) 2025 Ada"));
+
+        let readme = text(&files, "README.md");
+        assert!(readme.starts_with("# demo\n"));
+        assert!(readme.contains("Licensed under the MIT license."));
+        assert!(!readme.contains("{{"));
+    }
+
+    #[test]
+    fn variables_in_paths_and_text() {
+        let (_env, dir) = with_template_dir();
+        let template = dir.join("custom");
+        fs::create_dir_all(template.join("{{name}}")).unwrap();
+        fs::write(
+            template.join("{{name}}/info.txt.tmpl"),
+            "{{name}} by {{author}} <{{email}}>, {{year}}, {{license}}",
+        )
+        .unwrap();
+        fs::write(template.join("logo.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let files = render_template("custom", &vars(License::Apache2)).unwrap();
+        assert_eq!(
+            text(&files, "demo/info.txt"),
+            "demo by Ada <ada@example.com>, 2025, Apache-2.0"
+        );
+        assert_eq!(files[Path::new("logo.bin")], [0xff, 0xfe, 0x00]);
+        assert!(!files.contains_key(Path::new("demo/info.txt.tmpl")));
+    }
+
+    #[test]
+    fn custom_template_overrides_builtin() {
+        let (_env, dir) = with_template_dir();
+        fs::create_dir_all(dir.join("rust")).unwrap();
+        fs::write(dir.join("rust/main.rs"), "fn main() {}\n").unwrap();
+
+        let files = render_template("rust", &vars(License::None)).unwrap();
+        let paths: Vec<_> = files.keys().map(|p| p.display().to_string()).collect();
+        assert_eq!(paths, ["README.md", "main.rs"]);
+        assert!(text(&files, "README.md").contains("This project is not licensed yet."));
+        assert!(template_names().contains(&"rust".to_string()));
+    }
+
+    #[test]
+    fn template_readme_and_license_take_precedence() {
+        let (_env, dir) = with_template_dir();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs/README.md"), "# {{name}} docs\n").unwrap();
+        fs::write(dir.join("docs/LICENSE"), "All rights reserved.\n").unwrap();
+
+        let files = render_template("docs", &vars(License::Unlicense)).unwrap();
+        assert_eq!(text(&files, "README.md"), "# demo docs\n");
+        assert_eq!(text(&files, "LICENSE"), "All rights reserved.\n");
+    }
+
+    #[test]
+    fn unknown_template() {
+        let (_env, dir) = with_template_dir();
+        fs::create_dir_all(dir.join("mine")).unwrap();
+        let error = render_template("missing", &vars(License::Mit)).unwrap_err();
+        assert!(error.to_string().contains("Unknown template: missing"));
+        assert!(error.to_string().contains("cmake, mine, python, rust, zig"));
+        assert!(render_template("../templates", &vars(License::Mit)).is_err());
+    }
+}
@@ -1,17 +1,22 @@
 pub mod completions;
 pub mod environment;
+pub mod find;
 pub mod git;
 pub mod greeting;
+pub mod prompt;
 pub mod quotes;
 pub mod tasks;
 pub mod web;
+pub mod welcome;
 
 use clap::{Parser, Subcommand};
 
 use completions::CompletionCommands;
 use environment::EnvCommands;
+use find::FindArgs;
 use git::GitCommands;
-use quotes::{QuoteCommands, core::get_daily};
+use prompt::PromptArgs;
+use quotes::QuoteCommands;
 use tasks::TaskCommands;
 use web::WebCommands;
 
@@ -45,11 +50,24 @@ pub enum Commands {
         command: CompletionCommands,
     },
     /// Message of the day.
+    ///
+    /// The sections shown are set in the `welcome` table of the config file.
     Welcome,
     Tasks {
         #[command(subcommand)]
         command: TaskCommands,
     },
+    /// Print a compact status segment for a shell prompt.
+    ///
+    /// The segment shows the number of overdue tasks, the task in progress
+    /// and a marker if the current git repo has uncommitted changes. Results
+    /// are cached on disk so the prompt stays fast.
+    Prompt(PromptArgs),
+    /// Find tasks, quotes and past searches containing some text.
+    ///
+    /// Matches are ranked by relevance and the matching words are
+    /// highlighted.
+    Find(FindArgs),
 }
 
 /// A trait that indicates a struct encapsulates a command.
@@ -73,12 +91,10 @@ impl CommandHandler for ClapParser {
             Commands::Env { command } => command.handle(),
             Commands::Quote { command } => command.handle(),
             Commands::Completions { command } => command.handle(),
-            Commands::Welcome => {
-                println!("{}", greeting::welcome_msg());
-                println!("{}", get_daily().unwrap());
-                Ok(())
-            }
+            Commands::Welcome => welcome::show_dashboard(),
             Commands::Tasks { command } => command.handle(),
+            Commands::Prompt(args) => args.handle(),
+            Commands::Find(args) => args.handle(),
         }
     }
 }
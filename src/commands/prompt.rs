@@ -0,0 +1,243 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use clap::{Args, ValueEnum};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::Colorize;
+use crate::commands::git::core::is_dirty;
+use crate::database::models::task::TaskStatus;
+use crate::database::sqlite::{get_tasks_by_due_date, get_tasks_by_status};
+
+use super::CommandHandler;
+
+/// Maximum number of characters of the active task name to show.
+const TIMER_WIDTH: usize = 24;
+
+/// Options for the `prompt` command.
+#[derive(Debug, Args)]
+pub struct PromptArgs {
+    /// The shell or prompt tool the segment is printed for.
+    #[arg(short = 'f', long, value_enum, default_value = "plain")]
+    format: PromptFormat,
+    /// Number of seconds cached results are used for.
+    #[arg(short = 't', long, default_value_t = 60)]
+    ttl: i64,
+    /// Ignore the cache and reload everything.
+    #[arg(short = 'r', long)]
+    refresh: bool,
+}
+
+/// Output formats for the prompt segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PromptFormat {
+    /// Text with ANSI colors.
+    Plain,
+    /// For `PS1` in bash: `PS1='$(ShellCommander prompt -f bash) \$ '`.
+    Bash,
+    /// For `PROMPT` in zsh with `setopt prompt_subst`:
+    /// `PROMPT='$(ShellCommander prompt -f zsh) %# '`.
+    Zsh,
+    /// Text without colors for a starship custom module, which applies its
+    /// own style.
+    Starship,
+    /// For `PROMPT_COMMAND_RIGHT` in nushell:
+    /// `{|| ShellCommander prompt -f nushell }`.
+    Nushell,
+}
+
+/// A cached value and when it was loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Cached<T> {
+    /// Unix timestamp of when the value was loaded.
+    updated: i64,
+    value: T,
+}
+
+impl<T> Cached<T> {
+    fn new(value: T, now: i64) -> Self {
+        Self {
+            updated: now,
+            value,
+        }
+    }
+
+    fn is_fresh(&self, now: i64, ttl: i64) -> bool {
+        now - self.updated < ttl
+    }
+}
+
+/// The task information shown in the prompt.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TaskSummary {
+    /// Number of incomplete tasks past their due date.
+    overdue: usize,
+    /// Name of the first task in progress.
+    active: Option<String>,
+    /// Number of tasks in progress.
+    active_count: usize,
+}
+
+/// The on-disk prompt cache.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptCache {
+    tasks: Option<Cached<TaskSummary>>,
+    /// Whether each repo has uncommitted changes, keyed by its path.
+    #[serde(default)]
+    repos: HashMap<String, Cached<bool>>,
+}
+
+impl PromptCache {
+    fn path() -> PathBuf {
+        crate::CACHE_DIR.join("prompt.json")
+    }
+
+    /// Load the cache. A missing or corrupt cache is treated as empty.
+    fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        fs::create_dir_all(crate::CACHE_DIR.as_path())?;
+        fs::write(Self::path(), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+impl CommandHandler for PromptArgs {
+    fn handle(&self) -> crate::Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let mut cache = if self.refresh {
+            PromptCache::default()
+        } else {
+            PromptCache::load()
+        };
+        let mut changed = false;
+
+        let tasks = match &cache.tasks {
+            Some(cached) if cached.is_fresh(now, self.ttl) => cached.value.clone(),
+            _ => {
+                let tasks = load_tasks();
+                cache.tasks = Some(Cached::new(tasks.clone(), now));
+                changed = true;
+                tasks
+            }
+        };
+
+        let mut dirty = false;
+        if let Some(root) = repo_root() {
+            let key = root.to_string_lossy().to_string();
+            dirty = match cache.repos.get(&key) {
+                Some(cached) if cached.is_fresh(now, self.ttl) => cached.value,
+                _ => {
+                    let dirty = Repository::open(&root)
+                        .and_then(|repo| is_dirty(&repo))
+                        .unwrap_or(false);
+                    cache.repos.insert(key, Cached::new(dirty, now));
+                    changed = true;
+                    dirty
+                }
+            };
+        }
+
+        if changed {
+            //  Drop stale repos so the cache does not grow forever.
+            cache.repos.retain(|_, c| c.is_fresh(now, self.ttl));
+            //  The prompt must never fail because the cache cannot be written.
+            let _ = cache.save();
+        }
+
+        print!("{}", render(&tasks, dirty, self.format));
+        Ok(())
+    }
+}
+
+/// Load the task summary from the database.
+///
+/// Errors are ignored so that the prompt still renders without a database.
+fn load_tasks() -> TaskSummary {
+    let now = chrono::Local::now().naive_local();
+    let overdue = get_tasks_by_due_date(now)
+        .map(|tasks| {
+            tasks
+                .iter()
+                .filter(|t| !matches!(TaskStatus::from(t.status), TaskStatus::Complete))
+                .count()
+        })
+        .unwrap_or(0);
+    let active = get_tasks_by_status(TaskStatus::InProgress).unwrap_or_default();
+
+    TaskSummary {
+        overdue,
+        active: active.first().map(|t| t.task.clone()),
+        active_count: active.len(),
+    }
+}
+
+/// Find the root of the git repo containing the current directory.
+///
+/// This only checks for a `.git` entry, since opening the repo with libgit2
+/// is too slow to do on every prompt.
+fn repo_root() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|dir| dir.to_path_buf())
+}
+
+/// Render the prompt segment in the given format.
+fn render(tasks: &TaskSummary, dirty: bool, format: PromptFormat) -> String {
+    let mut parts = vec![];
+    if tasks.overdue > 0 {
+        parts.push(format!("!{}", tasks.overdue).red());
+    }
+    if let Some(active) = &tasks.active {
+        let mut name: String = active.chars().take(TIMER_WIDTH).collect();
+        if active.chars().count() > TIMER_WIDTH {
+            name.push('…');
+        }
+        if tasks.active_count > 1 {
+            name.push_str(&format!(" +{}", tasks.active_count - 1));
+        }
+        parts.push(format!("⏳ {}", name).cyan());
+    }
+    if dirty {
+        parts.push("●".yellow());
+    }
+    let segment = parts.join(" ");
+
+    match format {
+        PromptFormat::Plain | PromptFormat::Nushell => segment,
+        //  Readline needs non-printing characters wrapped in \001 and \002.
+        PromptFormat::Bash => wrap_escapes(&segment, "\x01", "\x02"),
+        //  zsh expands `%` sequences in the output of prompt substitutions.
+        PromptFormat::Zsh => wrap_escapes(&segment.replace('%', "%%"), "%{", "%}"),
+        PromptFormat::Starship => crate::layout::strip_ansi(&segment),
+    }
+}
+
+/// Wrap every ANSI escape code so the shell does not count it as visible
+/// characters.
+fn wrap_escapes(text: &str, open: &str, close: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push_str(open);
+            out.push(c);
+            for c in chars.by_ref() {
+                out.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            out.push_str(close);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
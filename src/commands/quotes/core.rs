@@ -1,17 +1,22 @@
 use crate::Result;
-use crate::database::sqlite::{get_daily_quote, get_quote, get_quotes, insert_quote};
-use crate::database::{NewQuote, Quote};
+use crate::database::sqlite::{
+    add_author_alias, delete_quote, get_author_stats, get_daily_history, get_daily_quote,
+    get_quote, get_quote_random, get_quote_tags, get_quotes, get_tag_counts, insert_quote,
+    search_quotes, suggest_author, tag_quote, untag_quote, update_quote,
+};
+use crate::database::{Author, DailyQuote, NewQuote, Quote};
 
-pub fn add_quote(quote: &str, author: &str) -> Result<()> {
+/// Add a quote and apply the given tags to it.
+///
+/// Returns the ID of the new quote.
+pub fn add_quote(quote: &str, author: &str, tags: &[String]) -> Result<i32> {
     let new_quote = NewQuote {
         quote: quote.to_string(),
         author: author.to_string(),
     };
-    let res = insert_quote(new_quote);
-    match res {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e.to_string().into()),
-    }
+    let id = insert_quote(new_quote).map_err(|e| e.to_string())?;
+    add_tags(id, tags)?;
+    Ok(id)
 }
 
 pub fn get_quote_by_id(id: i32) -> Result<Quote> {
@@ -27,16 +32,81 @@ pub fn get_quotes_all() -> Result<Vec<Quote>> {
     let res = get_quotes();
 
     match res {
+        Ok(q) if q.is_empty() => Err("No quotes found".into()),
         Ok(q) => Ok(q),
         Err(e) => Err(e.to_string().into()),
     }
 }
 
-pub fn get_daily() -> Result<Quote> {
-    let res = get_daily_quote();
+/// Get the daily quote, picking a new one with the configured rotation policy
+/// if needed.
+pub fn get_daily(tag: Option<&str>) -> Result<Quote> {
+    let (rotation, days) = {
+        let env = crate::ENV.lock().unwrap();
+        (env.quote_rotation, env.quote_rotation_days)
+    };
+    let res = get_daily_quote(tag, rotation, days);
 
     match res {
         Ok(q) => Ok(q),
         Err(e) => Err(e.to_string().into()),
     }
 }
+
+pub fn get_history(days: Option<u32>) -> Result<Vec<(DailyQuote, Quote)>> {
+    get_daily_history(days).map_err(|e| e.to_string().into())
+}
+
+pub fn get_random(tag: Option<&str>) -> Result<Quote> {
+    get_quote_random(tag).map_err(|e| e.to_string().into())
+}
+
+pub fn remove_quote(id: i32) -> Result<()> {
+    delete_quote(id).map_err(|e| e.to_string().into())
+}
+
+pub fn edit_quote(id: i32, quote: Option<String>, author: Option<String>) -> Result<()> {
+    if quote.is_none() && author.is_none() {
+        return Err("Nothing to edit. Provide --quote and/or --author.".into());
+    }
+    update_quote(id, quote, author).map_err(|e| e.to_string().into())
+}
+
+pub fn find_quotes(text: &str) -> Result<Vec<Quote>> {
+    search_quotes(text).map_err(|e| e.to_string().into())
+}
+
+pub fn add_tags(id: i32, tags: &[String]) -> Result<()> {
+    for tag in tags.iter().filter(|t| !t.trim().is_empty()) {
+        tag_quote(id, tag).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn remove_tags(id: i32, tags: &[String]) -> Result<()> {
+    for tag in tags {
+        untag_quote(id, tag).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn get_tags_of(id: i32) -> Result<Vec<String>> {
+    get_quote_tags(id).map_err(|e| e.to_string().into())
+}
+
+pub fn get_tags_all() -> Result<Vec<(String, i64)>> {
+    get_tag_counts().map_err(|e| e.to_string().into())
+}
+
+pub fn get_authors_all() -> Result<Vec<(Author, i64, Vec<String>)>> {
+    get_author_stats().map_err(|e| e.to_string().into())
+}
+
+pub fn get_author_suggestion(author: &str) -> Result<Option<String>> {
+    suggest_author(author).map_err(|e| e.to_string().into())
+}
+
+/// Record an alias for an author, returning the number of quotes merged.
+pub fn alias_author(alias: &str, author: &str) -> Result<usize> {
+    add_author_alias(alias, author).map_err(|e| e.to_string().into())
+}
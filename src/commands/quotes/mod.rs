@@ -1,11 +1,19 @@
 pub mod core;
+pub mod sources;
+pub mod transfer;
 
-use clap::{Subcommand, arg};
+use clap::Subcommand;
 
-use core::{add_quote, get_daily, get_quote_by_id, get_quotes_all};
-use std::io::stdin;
+use core::{
+    add_quote, add_tags, alias_author, edit_quote, find_quotes, get_author_suggestion,
+    get_authors_all, get_daily, get_history, get_quote_by_id, get_quotes_all, get_random,
+    get_tags_all, get_tags_of, remove_quote, remove_tags,
+};
+use sources::SourceKind;
+use std::{fs, io::stdin, path::PathBuf};
+use transfer::{QuoteFormat, export_quotes, import_file, import_records};
 
-use crate::database::sqlite::get_quote_random;
+use crate::{Colorize, database::Quote};
 
 use super::CommandHandler;
 
@@ -20,80 +28,171 @@ pub(crate) enum QuoteCommands {
         author: Option<String>,
         #[arg(short = 'q', long)]
         quote: Option<String>,
+        /// Tags to apply to the quote.
+        #[arg(short = 't', long, num_args(1..), value_delimiter = ',')]
+        tags: Vec<String>,
     },
+    /// Record an alternative spelling of an author.
+    ///
+    /// Quotes attributed to the alias are moved to the author.
+    Alias {
+        /// The alternative spelling.
+        alias: String,
+        /// The existing author the alias refers to.
+        author: String,
+    },
+    /// List authors with their number of quotes.
+    Authors,
     /// Returns the daily quote.
     ///
     /// If the daily quote does not exist, a new one is generated.
-    Daily,
+    Daily {
+        /// Only pick the daily quote from quotes with this tag.
+        #[arg(short = 't', long)]
+        tag: Option<String>,
+    },
+    /// Delete a quote by its ID.
+    Delete {
+        /// The ID of the quote to delete.
+        id: i32,
+    },
+    /// Edit the text or author of a quote.
+    Edit {
+        /// The ID of the quote to edit.
+        id: i32,
+        /// The new quote text.
+        #[arg(short = 'q', long)]
+        quote: Option<String>,
+        /// The new author.
+        #[arg(short = 'a', long)]
+        author: Option<String>,
+    },
+    /// Export all quotes to a file or the console.
+    Export {
+        /// Format to write the quotes in.
+        #[arg(short = 'f', long, value_enum, default_value = "json")]
+        format: QuoteFormat,
+        /// File to write to. If not provided, the quotes are printed.
+        #[arg(short = 'o', long)]
+        out: Option<PathBuf>,
+    },
+    /// Fetch quotes from an HTTP API, an RSS/Atom feed or a local directory.
+    ///
+    /// Quotes that already exist with the same text and author are skipped.
+    Fetch {
+        /// The kind of source to fetch from.
+        #[arg(short = 's', long, value_enum)]
+        source: SourceKind,
+        /// URL or path of the source. If not provided, the configured one is used.
+        #[arg(short = 'l', long)]
+        location: Option<String>,
+    },
     /// Get a single quote by its ID or all quotes as a vector.
     Get {
         /// The ID of the quote to get.
         #[arg(short = None, long)]
         id: Option<i32>,
     },
+    /// List past daily quotes, most recent first.
+    History {
+        /// Only show daily quotes from the last N days.
+        #[arg(short = 'd', long)]
+        days: Option<u32>,
+    },
+    /// Import quotes from a JSON, CSV or fortune file.
+    ///
+    /// Quotes that already exist with the same text and author are skipped.
+    Import {
+        /// The file to import.
+        file: PathBuf,
+        /// Format of the file. If not provided, it is guessed from the extension.
+        #[arg(short = 'f', long, value_enum)]
+        format: Option<QuoteFormat>,
+    },
     /// Get a random quote from the database.
-    Random,
+    Random {
+        /// Only pick from quotes with this tag.
+        #[arg(short = 't', long)]
+        tag: Option<String>,
+    },
+    /// Search quotes by text or author.
+    ///
+    /// The search is case-insensitive.
+    Search {
+        /// Text to search for in the quote and author.
+        text: String,
+    },
+    /// Add or remove tags on a quote.
+    Tag {
+        /// The ID of the quote to tag.
+        id: i32,
+        /// Tags to add or remove.
+        #[arg(required = true, num_args(1..), value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Remove the tags instead of adding them.
+        #[arg(short = 'r', long)]
+        remove: bool,
+    },
+    /// List all tags and the number of quotes with each tag.
+    Tags,
 }
 
 impl CommandHandler for QuoteCommands {
     fn handle(&self) -> crate::Result<()> {
         match self {
-            QuoteCommands::Add { author, quote } => {
-                //  If both the author and quote are None, prompt the user for input.
-                if author.is_none() && quote.is_none() {
-                    let mut quote_input = String::new();
-                    let mut author_input = String::new();
-                    println!("Enter the quote: \nPress Enter to submit.");
-                    while quote_input.is_empty() {
-                        stdin().read_line(&mut quote_input).unwrap();
-                    }
-                    println!("Enter the author: \nPress Enter to submit.");
-                    while author_input.is_empty() {
-                        stdin().read_line(&mut author_input).unwrap();
-                    }
-                    let res = add_quote(quote_input.trim(), author_input.trim());
-                    match res {
-                        Ok(_) => Ok(()),
-                        Err(e) => Err(e.to_string().into()),
-                    }
+            QuoteCommands::Add {
+                author,
+                quote,
+                tags,
+            } => {
+                //  Prompt the user for any input that was not provided.
+                let quote = match quote {
+                    Some(q) => q.clone(),
+                    None => prompt_line("Enter the quote: \nPress Enter to submit."),
+                };
+                let author = match author {
+                    Some(a) => a.clone(),
+                    None => prompt_line("Enter the author: \nPress Enter to submit."),
+                };
+                if quote.is_empty() || author.is_empty() {
+                    return Err("A quote and an author are required.".into());
                 }
-                //  If either the author or quote is None, prompt the user for the missing input.
-                else if author.is_none() || quote.is_none() {
-                    if author.is_none() {
-                        let mut author_input = String::new();
-                        println!("Enter the author: \nPress Enter to submit.");
-                        while author_input.is_empty() {
-                            stdin().read_line(&mut author_input).unwrap();
-                        }
-                        let res = add_quote(quote.as_ref().unwrap(), author_input.trim());
-                        match res {
-                            Ok(_) => Ok(()),
-                            Err(e) => Err(e.to_string().into()),
-                        }
-                    } else {
-                        let mut quote_input = String::new();
-                        println!("Enter the quote: \nPress Enter to submit.");
-                        while quote_input.is_empty() {
-                            stdin().read_line(&mut quote_input).unwrap();
-                        }
-                        let res = add_quote(quote_input.trim(), author.as_ref().unwrap());
-                        match res {
-                            Ok(_) => Ok(()),
-                            Err(e) => Err(e.to_string().into()),
-                        }
-                    }
+                let author = confirm_author(&author)?;
+                let id = add_quote(&quote, &author, tags)?;
+                println!("Added quote ({:02}).", id);
+                Ok(())
+            }
+            QuoteCommands::Alias { alias, author } => {
+                let merged = alias_author(alias, author)?;
+                println!(
+                    "{} is now an alias of {}. Updated {} quotes.",
+                    alias.green(),
+                    author.green_bright(),
+                    merged
+                );
+                Ok(())
+            }
+            QuoteCommands::Authors => {
+                let authors = get_authors_all()?;
+                if authors.is_empty() {
+                    println!("No authors to display.");
                 }
-                //  If both the author and quote are provided, add the quote to the database.
-                else {
-                    let res = add_quote(quote.as_ref().unwrap(), author.as_ref().unwrap());
-                    match res {
-                        Ok(_) => Ok(()),
-                        Err(e) => Err(e.to_string().into()),
+                for (author, count, aliases) in authors {
+                    if aliases.is_empty() {
+                        println!("{} ({})", author.name.green_bright(), count);
+                    } else {
+                        println!(
+                            "{} ({}) aka {}",
+                            author.name.green_bright(),
+                            count,
+                            aliases.join(", ")
+                        );
                     }
                 }
+                Ok(())
             }
-            QuoteCommands::Daily => {
-                let daily = get_daily();
+            QuoteCommands::Daily { tag } => {
+                let daily = get_daily(tag.as_deref());
                 match daily {
                     Ok(q) => {
                         println!("{}", q);
@@ -102,32 +201,71 @@ impl CommandHandler for QuoteCommands {
                     Err(e) => Err(e.to_string().into()),
                 }
             }
-            //  TODO: Implement getting a single and all quotes in the database.
-            QuoteCommands::Get { id } => {
-                if id.is_none() {
-                    let quotes = get_quotes_all();
-                    match quotes {
-                        Ok(q) => {
-                            for quote in q {
-                                println!("{}", quote);
-                            }
-                            Ok(())
-                        }
-                        Err(e) => Err(e.to_string().into()),
+            QuoteCommands::Delete { id } => {
+                remove_quote(*id)?;
+                println!("Deleted quote ({:02}).", id);
+                Ok(())
+            }
+            QuoteCommands::Edit { id, quote, author } => {
+                let author = author.as_deref().map(confirm_author).transpose()?;
+                edit_quote(*id, quote.clone(), author)?;
+                print_listing(&get_quote_by_id(*id)?)
+            }
+            QuoteCommands::Export { format, out } => {
+                let text = export_quotes(*format)?;
+                match out {
+                    Some(path) => {
+                        fs::write(path, text)?;
+                        println!("Exported quotes to: {}", path.display());
                     }
-                } else {
-                    let quote = get_quote_by_id(id.unwrap());
-                    match quote {
-                        Ok(q) => {
-                            println!("{}", q);
-                            Ok(())
-                        }
-                        Err(e) => Err(e.to_string().into()),
+                    None => print!("{}", text),
+                }
+                Ok(())
+            }
+            QuoteCommands::Fetch { source, location } => {
+                let source = source.source(location.clone());
+                println!("Fetching quotes from: {}", source.name().cyan());
+                let summary = import_records(source.fetch()?)?;
+                println!(
+                    "Imported {} quotes, skipped {} duplicates.",
+                    summary.added, summary.skipped
+                );
+                Ok(())
+            }
+            QuoteCommands::Get { id } => match id {
+                None => {
+                    for quote in get_quotes_all()? {
+                        print_listing(&quote)?;
                     }
+                    Ok(())
                 }
+                Some(id) => print_listing(&get_quote_by_id(*id)?),
+            },
+            QuoteCommands::History { days } => {
+                let history = get_history(*days)?;
+                if history.is_empty() {
+                    println!("No daily quotes to display.");
+                }
+                for (daily, quote) in history {
+                    println!(
+                        "{} ({:02})",
+                        daily.time_stamp.format("%A, %B %d, %Y").to_string().green(),
+                        quote.id
+                    );
+                    println!("{}\n", quote);
+                }
+                Ok(())
+            }
+            QuoteCommands::Import { file, format } => {
+                let summary = import_file(file, *format)?;
+                println!(
+                    "Imported {} quotes, skipped {} duplicates.",
+                    summary.added, summary.skipped
+                );
+                Ok(())
             }
-            QuoteCommands::Random => {
-                let quote_res = get_quote_random();
+            QuoteCommands::Random { tag } => {
+                let quote_res = get_random(tag.as_deref());
 
                 match quote_res {
                     Ok(q) => {
@@ -140,6 +278,84 @@ impl CommandHandler for QuoteCommands {
                     }
                 }
             }
+            QuoteCommands::Search { text } => {
+                let quotes = find_quotes(text)?;
+                if quotes.is_empty() {
+                    println!("No quotes found matching: {}", text);
+                }
+                for quote in quotes {
+                    print_listing(&quote)?;
+                }
+                Ok(())
+            }
+            QuoteCommands::Tag { id, tags, remove } => {
+                if *remove {
+                    remove_tags(*id, tags)?;
+                } else {
+                    add_tags(*id, tags)?;
+                }
+                print_listing(&get_quote_by_id(*id)?)
+            }
+            QuoteCommands::Tags => {
+                let tags = get_tags_all()?;
+                if tags.is_empty() {
+                    println!("No tags to display.");
+                }
+                for (name, count) in tags {
+                    println!("{} ({})", name.cyan(), count);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Prompt the user until a non-empty line is entered.
+///
+/// Returns an empty string if the input is closed.
+fn prompt_line(prompt: &str) -> String {
+    let mut input = String::new();
+    println!("{prompt}");
+    while input.trim().is_empty() {
+        input.clear();
+        if stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            break;
+        }
+    }
+    input.trim().to_string()
+}
+
+/// Offer an existing author with a similar name before a new one is created.
+///
+/// If the user accepts, the entered name is recorded as an alias.
+fn confirm_author(author: &str) -> crate::Result<String> {
+    let Some(suggestion) = get_author_suggestion(author)? else {
+        return Ok(author.to_string());
+    };
+    loop {
+        let answer = prompt_line(&format!(
+            "Did you mean {}? (y/n)",
+            suggestion.green_bright()
+        ));
+        match answer.to_lowercase().as_str() {
+            "y" => {
+                alias_author(author, &suggestion)?;
+                return Ok(suggestion);
+            }
+            "n" | "" => return Ok(author.to_string()),
+            _ => continue,
         }
     }
 }
+
+/// Print a quote with its ID and tags.
+fn print_listing(quote: &Quote) -> crate::Result<()> {
+    let tags = get_tags_of(quote.id)?;
+    if tags.is_empty() {
+        println!("Quote ({:02}):", quote.id);
+    } else {
+        println!("Quote ({:02}): [{}]", quote.id, tags.join(", ").cyan());
+    }
+    println!("{}\n", quote);
+    Ok(())
+}
@@ -0,0 +1,454 @@
+use std::{fs, path::PathBuf};
+
+use clap::ValueEnum;
+use quick_xml::{Reader, events::Event};
+use serde_json::Value;
+
+use super::transfer::{QuoteFormat, QuoteRecord, parse_quotes};
+
+/// A provider of quotes from outside the database.
+pub trait QuoteSource {
+    /// A short description of where the quotes come from.
+    fn name(&self) -> String;
+    /// Fetch every quote the source currently offers.
+    fn fetch(&self) -> crate::Result<Vec<QuoteRecord>>;
+}
+
+/// The kinds of quote sources that can be fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SourceKind {
+    /// A JSON-over-HTTP endpoint. Defaults to `quote_api_url`.
+    Http,
+    /// An RSS or Atom feed. Defaults to `quote_feed_url`.
+    Feed,
+    /// A local directory of quote files. Defaults to `quote_dir`.
+    Dir,
+}
+
+impl SourceKind {
+    /// Create the source, using `location` instead of the configured default
+    /// if it is provided.
+    pub fn source(&self, location: Option<String>) -> Box<dyn QuoteSource> {
+        let env = crate::ENV.lock().unwrap();
+        match self {
+            SourceKind::Http => Box::new(HttpJsonSource {
+                url: location.unwrap_or_else(|| env.quote_api_url.clone()),
+            }),
+            SourceKind::Feed => Box::new(FeedSource {
+                url: location.unwrap_or_else(|| env.quote_feed_url.clone()),
+            }),
+            SourceKind::Dir => Box::new(DirectorySource {
+                path: location
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| env.quote_dir.clone()),
+            }),
+        }
+    }
+}
+
+/// Quotes served as JSON over HTTP.
+///
+/// The response can be an array of quotes, a single quote, or an object with
+/// the quotes under a `results`, `quotes` or `data` key. Quote text is read
+/// from `quote`, `q`, `content` or `text`, and the author from `author` or `a`.
+pub struct HttpJsonSource {
+    pub url: String,
+}
+
+impl HttpJsonSource {
+    /// Extract quote records from a JSON document.
+    fn parse(body: &str) -> crate::Result<Vec<QuoteRecord>> {
+        let value: Value = serde_json::from_str(body)?;
+        let items = match value {
+            Value::Array(items) => items,
+            Value::Object(ref map) => ["results", "quotes", "data"]
+                .iter()
+                .find_map(|k| map.get(*k).and_then(|v| v.as_array()).cloned())
+                .unwrap_or_else(|| vec![value.clone()]),
+            _ => return Err("Unexpected JSON in quote response.".into()),
+        };
+
+        let field = |item: &Value, keys: &[&str]| -> Option<String> {
+            keys.iter()
+                .find_map(|k| item.get(*k).and_then(|v| v.as_str()))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        Ok(items
+            .iter()
+            .filter_map(|item| {
+                let quote = field(item, &["quote", "q", "content", "text"])?;
+                let author =
+                    field(item, &["author", "a"]).unwrap_or_else(|| String::from("Unknown"));
+                let tags = item
+                    .get("tags")
+                    .and_then(|t| t.as_array())
+                    .map(|t| {
+                        t.iter()
+                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(QuoteRecord {
+                    quote,
+                    author,
+                    tags,
+                })
+            })
+            .collect())
+    }
+}
+
+impl QuoteSource for HttpJsonSource {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn fetch(&self) -> crate::Result<Vec<QuoteRecord>> {
+        let body = crate::http::get_text(&self.url)?;
+        Self::parse(&body)
+    }
+}
+
+/// Quotes published as items of an RSS or Atom feed.
+///
+/// The item description (or Atom summary/content) is used as the quote and
+/// the title as the author. Items without a description are read from a
+/// title in the form `"Quote" - Author`.
+pub struct FeedSource {
+    pub url: String,
+}
+
+impl FeedSource {
+    /// Extract quote records from an RSS or Atom document.
+    fn parse(body: &str) -> crate::Result<Vec<QuoteRecord>> {
+        let mut reader = Reader::from_str(body);
+        reader.config_mut().trim_text(true);
+
+        let mut records = vec![];
+        let mut in_item = false;
+        let mut field: Option<String> = None;
+        //  Only the first of description, summary and content is used.
+        let mut has_body = false;
+        let mut title = String::new();
+        let mut text = String::new();
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => {
+                    let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                    match name.as_str() {
+                        "item" | "entry" => {
+                            in_item = true;
+                            has_body = false;
+                            title.clear();
+                            text.clear();
+                        }
+                        "title" if in_item => field = Some(name),
+                        "description" | "summary" | "content" if in_item && !has_body => {
+                            has_body = true;
+                            field = Some(name)
+                        }
+                        //  Text is trimmed, so words in nested elements such
+                        //  as paragraphs are kept apart by hand.
+                        _ if field.as_deref().is_some_and(|f| f != "title") => text.push(' '),
+                        _ => (),
+                    }
+                }
+                Event::Text(t) => match field.as_deref() {
+                    Some("title") => title.push_str(&t.unescape()?),
+                    Some(_) => text.push_str(&t.unescape()?),
+                    None => (),
+                },
+                Event::CData(c) => {
+                    let data = String::from_utf8_lossy(&c.into_inner()).to_string();
+                    match field.as_deref() {
+                        Some("title") => title.push_str(&data),
+                        Some(_) => text.push_str(&data),
+                        None => (),
+                    }
+                }
+                Event::End(e) => {
+                    let name = e.local_name();
+                    match name.as_ref() {
+                        b"item" | b"entry" => {
+                            in_item = false;
+                            if let Some(record) = Self::record(&title, &text) {
+                                records.push(record);
+                            }
+                        }
+                        //  Elements nested in the field, such as the XHTML
+                        //  of Atom content, do not end it.
+                        name if field.as_deref().is_some_and(|f| f.as_bytes() == name) => {
+                            field = None
+                        }
+                        _ if field.as_deref().is_some_and(|f| f != "title") => text.push(' '),
+                        _ => (),
+                    }
+                }
+                Event::Eof => break,
+                _ => (),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Build a quote record from the title and body of a feed item.
+    fn record(title: &str, text: &str) -> Option<QuoteRecord> {
+        let text = clean_quote(&strip_tags(text));
+        let title = strip_tags(title).trim().to_string();
+
+        if !text.is_empty() {
+            let author = if title.is_empty() {
+                String::from("Unknown")
+            } else {
+                title
+            };
+            return Some(QuoteRecord {
+                quote: text,
+                author,
+                tags: vec![],
+            });
+        }
+
+        let (quote, author) = title
+            .rsplit_once(" - ")
+            .or_else(|| title.rsplit_once(" — "))?;
+        Some(QuoteRecord {
+            quote: clean_quote(quote),
+            author: author.trim().to_string(),
+            tags: vec![],
+        })
+    }
+}
+
+impl QuoteSource for FeedSource {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn fetch(&self) -> crate::Result<Vec<QuoteRecord>> {
+        let body = crate::http::get_text(&self.url)?;
+        Self::parse(&body)
+    }
+}
+
+/// Quotes stored in a local directory.
+///
+/// Every `.json`, `.csv` and fortune file in the directory is read.
+pub struct DirectorySource {
+    pub path: PathBuf,
+}
+
+impl QuoteSource for DirectorySource {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn fetch(&self) -> crate::Result<Vec<QuoteRecord>> {
+        let path = if self.path.starts_with("~") {
+            crate::expand_sanitized_home(&self.path)
+        } else {
+            self.path.clone()
+        };
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect();
+        files.sort();
+
+        let mut records = vec![];
+        for file in files {
+            let text = fs::read_to_string(&file)?;
+            records.extend(parse_quotes(&text, QuoteFormat::from_path(&file))?);
+        }
+        Ok(records)
+    }
+}
+
+/// Remove HTML tags from feed text.
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => (),
+        }
+    }
+    out
+}
+
+/// Trim whitespace and surrounding quotation marks from quote text.
+fn clean_quote(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .trim_matches(|c| matches!(c, '"' | '“' | '”'))
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::quotes::transfer::import_records;
+    use crate::testing::{StubServer, TestEnv, response};
+
+    const BODY: &str = r#"{
+        "results": [
+            { "content": " Simplicity is prerequisite for reliability. ", "author": "Edsger Dijkstra", "tags": ["programming"] },
+            { "q": "Talk is cheap. Show me the code.", "a": "Linus Torvalds" },
+            { "text": "Anonymous wisdom." },
+            { "author": "Nobody" },
+            { "content": "Simplicity is prerequisite for reliability.", "author": "Edsger Dijkstra" }
+        ]
+    }"#;
+
+    #[test]
+    fn http_source_reads_quotes_from_a_server() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![response("200 OK", &[], BODY)]);
+        let source = HttpJsonSource {
+            url: format!("{}quotes", server.url),
+        };
+
+        let records = source.fetch().unwrap();
+
+        assert!(server.requests()[0].starts_with("GET /quotes HTTP/1.1"));
+        let quotes: Vec<(&str, &str)> = records
+            .iter()
+            .map(|r| (r.quote.as_str(), r.author.as_str()))
+            .collect();
+        assert_eq!(
+            quotes,
+            [
+                (
+                    "Simplicity is prerequisite for reliability.",
+                    "Edsger Dijkstra"
+                ),
+                ("Talk is cheap. Show me the code.", "Linus Torvalds"),
+                ("Anonymous wisdom.", "Unknown"),
+                (
+                    "Simplicity is prerequisite for reliability.",
+                    "Edsger Dijkstra"
+                ),
+            ]
+        );
+        assert_eq!(records[0].tags, ["programming"]);
+    }
+
+    #[test]
+    fn fetched_quotes_are_imported_once() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![
+            response("200 OK", &[], BODY),
+            response("200 OK", &[], BODY),
+        ]);
+        let source = HttpJsonSource {
+            url: server.url.clone(),
+        };
+
+        let first = import_records(source.fetch().unwrap()).unwrap();
+        assert_eq!((first.added, first.skipped), (3, 1));
+
+        let second = import_records(source.fetch().unwrap()).unwrap();
+        assert_eq!((second.added, second.skipped), (0, 4));
+    }
+
+    #[test]
+    fn http_source_reports_server_errors() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![response("500 Internal Server Error", &[], "")]);
+        let source = HttpJsonSource {
+            url: server.url.clone(),
+        };
+
+        assert!(source.fetch().is_err());
+    }
+
+    fn pairs(records: &[QuoteRecord]) -> Vec<(&str, &str)> {
+        records
+            .iter()
+            .map(|r| (r.quote.as_str(), r.author.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn rss_items() {
+        let body = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+                <title>Quotes of the day</title>
+                <description>Not a quote.</description>
+                <item>
+                    <title>Edsger Dijkstra</title>
+                    <description>&lt;p&gt;&#8220;Simplicity is prerequisite
+                        for reliability.&#8221;&lt;/p&gt;</description>
+                    <link>https://example.com/1</link>
+                </item>
+                <item>
+                    <description><![CDATA[<em>Talk</em> is cheap.]]></description>
+                    <title><![CDATA[Linus Torvalds]]></title>
+                </item>
+                <item>
+                    <title>"Stay hungry, stay foolish." - Steve Jobs</title>
+                </item>
+                <item><title>No author here</title></item>
+            </channel></rss>"#;
+        assert_eq!(
+            pairs(&FeedSource::parse(body).unwrap()),
+            [
+                (
+                    "Simplicity is prerequisite for reliability.",
+                    "Edsger Dijkstra"
+                ),
+                ("Talk is cheap.", "Linus Torvalds"),
+                ("Stay hungry, stay foolish.", "Steve Jobs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn atom_entries() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <title>Quotes</title>
+                <entry>
+                    <content type="xhtml">
+                        <div xmlns="http://www.w3.org/1999/xhtml">
+                            <p>Premature optimization</p>
+                            <p>is the <em>root</em> of all evil.</p>
+                        </div>
+                    </content>
+                    <title type="html">Donald &lt;b&gt;Knuth&lt;/b&gt;</title>
+                </entry>
+                <entry>
+                    <title>Grace Hopper</title>
+                    <summary>It is easier to ask forgiveness than permission.</summary>
+                    <content>Not used.</content>
+                </entry>
+                <entry>
+                    <summary>A quote without a title.</summary>
+                </entry>
+            </feed>"#;
+        assert_eq!(
+            pairs(&FeedSource::parse(body).unwrap()),
+            [
+                (
+                    "Premature optimization is the root of all evil.",
+                    "Donald Knuth"
+                ),
+                (
+                    "It is easier to ask forgiveness than permission.",
+                    "Grace Hopper"
+                ),
+                ("A quote without a title.", "Unknown"),
+            ]
+        );
+        assert!(FeedSource::parse("<feed><entry>").unwrap().is_empty());
+    }
+}
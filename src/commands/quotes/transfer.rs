@@ -0,0 +1,384 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{
+    NewQuote,
+    sqlite::{get_all_quote_tags, get_quotes, insert_quotes},
+};
+
+/// File formats that quotes can be imported from and exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QuoteFormat {
+    /// A JSON array of `{ "quote", "author", "tags" }` objects.
+    Json,
+    /// A CSV file with `quote,author,tags` columns. Tags are separated by `;`.
+    Csv,
+    /// A classic `fortune` file with quotes delimited by `%` lines.
+    Fortune,
+}
+
+impl QuoteFormat {
+    /// Guess the format of a file from its extension.
+    ///
+    /// Files without a known extension are treated as fortune files.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("json") => QuoteFormat::Json,
+            Some("csv") => QuoteFormat::Csv,
+            _ => QuoteFormat::Fortune,
+        }
+    }
+}
+
+/// A quote as it is stored in an import or export file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteRecord {
+    pub quote: String,
+    pub author: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A row in a CSV quote file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRecord {
+    quote: String,
+    author: String,
+    #[serde(default)]
+    tags: String,
+}
+
+/// The result of importing a set of quotes.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+/// Normalize a quote and author into a key used to detect duplicates.
+///
+/// Case, punctuation and whitespace differences are ignored.
+pub fn normalize_key(quote: &str, author: &str) -> String {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+    };
+    format!("{}|{}", normalize(quote), normalize(author))
+}
+
+/// Parse quote records from text in the given format.
+pub fn parse_quotes(text: &str, format: QuoteFormat) -> crate::Result<Vec<QuoteRecord>> {
+    match format {
+        QuoteFormat::Json => Ok(serde_json::from_str(text)?),
+        QuoteFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(text.as_bytes());
+            let mut records = vec![];
+            for row in reader.deserialize::<CsvRecord>() {
+                let row = row?;
+                records.push(QuoteRecord {
+                    quote: row.quote,
+                    author: row.author,
+                    tags: split_tags(&row.tags),
+                });
+            }
+            Ok(records)
+        }
+        QuoteFormat::Fortune => Ok(parse_fortune(text)),
+    }
+}
+
+/// Render quote records as text in the given format.
+pub fn render_quotes(records: &[QuoteRecord], format: QuoteFormat) -> crate::Result<String> {
+    match format {
+        QuoteFormat::Json => Ok(serde_json::to_string_pretty(records)?),
+        QuoteFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            //  The header is written with the first record, so an empty
+            //  collection needs it written by hand.
+            if records.is_empty() {
+                writer.write_record(["quote", "author", "tags"])?;
+            }
+            for record in records {
+                writer.serialize(CsvRecord {
+                    quote: record.quote.clone(),
+                    author: record.author.clone(),
+                    tags: record.tags.join(";"),
+                })?;
+            }
+            Ok(String::from_utf8(writer.into_inner()?)?)
+        }
+        QuoteFormat::Fortune => {
+            let mut out = String::new();
+            for record in records {
+                out.push_str(&format!("{}\n\t-- {}\n%\n", record.quote, record.author));
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Split a `;` separated list of tags.
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(';')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Parse a `fortune` file.
+///
+/// Entries are separated by lines containing only `%`. The author is taken
+/// from a trailing line starting with `--` or `—`. Entries without an author
+/// are attributed to "Unknown".
+pub fn parse_fortune(text: &str) -> Vec<QuoteRecord> {
+    let mut records = vec![];
+    let mut lines: Vec<&str> = vec![];
+
+    for line in text.lines().chain(std::iter::once("%")) {
+        if line.trim() != "%" {
+            lines.push(line);
+            continue;
+        }
+        //  Drop blank lines around the entry.
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        let mut author = String::from("Unknown");
+        if let Some(last) = lines.last() {
+            let trimmed = last.trim();
+            if let Some(name) = trimmed
+                .strip_prefix("--")
+                .or_else(|| trimmed.strip_prefix('—'))
+            {
+                author = name.trim().to_string();
+                lines.pop();
+            }
+        }
+        let quote = lines
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if !quote.is_empty() {
+            records.push(QuoteRecord {
+                quote,
+                author,
+                tags: vec![],
+            });
+        }
+        lines.clear();
+    }
+    records
+}
+
+/// Add quote records to the database, skipping duplicates.
+///
+/// A record is a duplicate if a quote with the same normalized text and
+/// author already exists in the database or earlier in `records`. The
+/// records are added in one transaction, so a failed import adds nothing.
+pub fn import_records(records: Vec<QuoteRecord>) -> crate::Result<ImportSummary> {
+    let mut seen: HashSet<String> = get_quotes()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|q| normalize_key(&q.quote, &q.author))
+        .collect();
+    let total = records.len();
+    let new_quotes = records
+        .into_iter()
+        .map(|record| {
+            let new_quote = NewQuote {
+                quote: record.quote,
+                author: record.author,
+            };
+            (new_quote, record.tags)
+        })
+        .collect();
+
+    //  Compare against the canonical spelling of known authors.
+    let added = insert_quotes(new_quotes, |quote, author| {
+        seen.insert(normalize_key(quote, author))
+    })
+    .map_err(|e| e.to_string())?
+    .len();
+    Ok(ImportSummary {
+        added,
+        skipped: total - added,
+    })
+}
+
+/// Import quotes from a file.
+///
+/// If `format` is `None`, it is guessed from the file extension.
+pub fn import_file(path: &Path, format: Option<QuoteFormat>) -> crate::Result<ImportSummary> {
+    let text = fs::read_to_string(path)?;
+    let format = format.unwrap_or_else(|| QuoteFormat::from_path(path));
+    import_records(parse_quotes(&text, format)?)
+}
+
+/// Export every quote and its tags in the given format.
+///
+/// An empty collection is exported as an empty list.
+pub fn export_quotes(format: QuoteFormat) -> crate::Result<String> {
+    let quotes = get_quotes().map_err(|e| e.to_string())?;
+    let mut tags = get_all_quote_tags().map_err(|e| e.to_string())?;
+    let records: Vec<QuoteRecord> = quotes
+        .into_iter()
+        .map(|q| QuoteRecord {
+            tags: tags.remove(&q.id).unwrap_or_default(),
+            quote: q.quote,
+            author: q.author,
+        })
+        .collect();
+    render_quotes(&records, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::sqlite::{add_author_alias, delete_quote};
+    use crate::testing::TestEnv;
+
+    fn record(quote: &str, author: &str, tags: &[&str]) -> QuoteRecord {
+        QuoteRecord {
+            quote: quote.to_string(),
+            author: author.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    fn pairs(records: &[QuoteRecord]) -> Vec<(&str, &str)> {
+        records
+            .iter()
+            .map(|r| (r.quote.as_str(), r.author.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn fortune_entries_and_authors() {
+        let text = "\
+Simplicity is prerequisite
+  for reliability.
+\t-- Edsger Dijkstra
+%
+%
+
+Talk is cheap.
+— Linus Torvalds
+
+%
+Anonymous wisdom.
+   %
+Dashes -- in the middle are kept.";
+        assert_eq!(
+            pairs(&parse_fortune(text)),
+            [
+                (
+                    "Simplicity is prerequisite for reliability.",
+                    "Edsger Dijkstra"
+                ),
+                ("Talk is cheap.", "Linus Torvalds"),
+                ("Anonymous wisdom.", "Unknown"),
+                ("Dashes -- in the middle are kept.", "Unknown"),
+            ]
+        );
+        assert!(parse_fortune("%\n\n%\n-- Nobody\n").is_empty());
+    }
+
+    #[test]
+    fn csv_quotes_special_characters() {
+        let records = [
+            record("He said \"hi\", then left.", "Ada, Countess", &["a", "b c"]),
+            record("Two\nlines", "Unknown", &[]),
+        ];
+        let text = render_quotes(&records, QuoteFormat::Csv).unwrap();
+        assert_eq!(
+            text,
+            "quote,author,tags\n\
+             \"He said \"\"hi\"\", then left.\",\"Ada, Countess\",a;b c\n\
+             \"Two\nlines\",Unknown,\n"
+        );
+
+        let parsed = parse_quotes(&text, QuoteFormat::Csv).unwrap();
+        assert_eq!(pairs(&parsed), pairs(&records));
+        assert_eq!(parsed[0].tags, ["a", "b c"]);
+        assert!(parsed[1].tags.is_empty());
+
+        let empty = render_quotes(&[], QuoteFormat::Csv).unwrap();
+        assert_eq!(empty, "quote,author,tags\n");
+        assert!(parse_quotes(&empty, QuoteFormat::Csv).unwrap().is_empty());
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let _env = TestEnv::new();
+        crate::database::sqlite::tag_quote(1, "Truth").unwrap();
+        crate::database::sqlite::tag_quote(1, "science").unwrap();
+
+        for format in [QuoteFormat::Json, QuoteFormat::Csv, QuoteFormat::Fortune] {
+            let text = export_quotes(format).unwrap();
+            let before = parse_quotes(&text, format).unwrap();
+            assert_eq!(before.len(), 12);
+
+            //  Nothing is added twice.
+            let summary = import_records(before.clone()).unwrap();
+            assert_eq!((summary.added, summary.skipped), (0, 12));
+
+            for quote in get_quotes().unwrap() {
+                delete_quote(quote.id).unwrap();
+            }
+            let summary = import_records(before.clone()).unwrap();
+            assert_eq!((summary.added, summary.skipped), (12, 0));
+
+            let after = parse_quotes(&export_quotes(format).unwrap(), format).unwrap();
+            assert_eq!(pairs(&after), pairs(&before));
+            if format != QuoteFormat::Fortune {
+                assert_eq!(after[1].tags, ["science", "truth"]);
+            }
+        }
+    }
+
+    #[test]
+    fn import_skips_duplicates() {
+        let _env = TestEnv::new();
+        add_author_alias("Feynman", "Richard Feynman").unwrap();
+        let summary = import_records(vec![
+            //  Seeded, with other case, punctuation and an alias.
+            record(
+                "a very great deal more truth can become known than can be proven",
+                "feynman",
+                &[],
+            ),
+            record("New quote.", "Grace Hopper", &["computing"]),
+            record("new   quote", "GRACE HOPPER", &[]),
+            record("New quote.", "Someone Else", &[]),
+        ])
+        .unwrap();
+        assert_eq!((summary.added, summary.skipped), (2, 2));
+
+        let quotes = get_quotes().unwrap();
+        let added: Vec<_> = quotes.iter().filter(|q| q.id >= 12).collect();
+        assert_eq!(added.len(), 2);
+        assert_eq!(
+            (added[0].id, added[0].author.as_str()),
+            (12, "Grace Hopper")
+        );
+        assert_eq!(
+            (added[1].id, added[1].author.as_str()),
+            (13, "Someone Else")
+        );
+        assert_eq!(get_all_quote_tags().unwrap()[&12], ["computing"]);
+    }
+}
@@ -28,8 +28,8 @@ pub fn create_task(parent_task: Option<i32>) -> crate::Result<i32> {
     }
 
     let due_date: Option<DateTime<Local>> = parse_date_time(
-        "Enter due date (YYYY-MM-DD HH:MM:SS): ",
-        "Invalid date format. Use YYYY-MM-DD HH:MM:SS.",
+        "Enter due date (such as YYYY-MM-DD HH:MM, tomorrow or 3d): ",
+        "Invalid date format. Use YYYY-MM-DD HH:MM, tomorrow or 3d.",
     );
 
     if let Some(occurence) = parse_input(
@@ -95,14 +95,14 @@ fn parse_date_time(prompt: &str, error_msg: &str) -> Option<DateTime<Local>> {
         if input.trim().is_empty() {
             return None;
         }
-        let temp = DateTime::parse_from_str(input.trim(), "%Y-%m-%d %H:%M:%S");
-        match temp {
+        match crate::dates::parse_due(&input) {
             Ok(date) => {
-                output = Some(date.with_timezone(&Local));
+                output = Some(date);
                 break;
             }
             Err(_) => {
                 println!("{error_msg}");
+                input.clear();
                 continue;
             }
         }
@@ -3,8 +3,9 @@ mod core;
 pub use core::*;
 
 use chrono::{DateTime, Local};
-use clap::{Subcommand, arg};
+use clap::Subcommand;
 
+use crate::layout::{labeled, pad_right, terminal_width, wrap_str};
 use crate::database::{
     models::task::{task_status_utf8, NewTask, NewTaskRelation, Task, TaskStatus},
     sqlite::{
@@ -26,7 +27,8 @@ pub enum TaskCommands {
         /// A short description of the task.
         #[arg(short = 'd', long, value_parser = task_string_validator)]
         task_desc: Option<String>,
-        /// The due date of the task.
+        /// The due date of the task, such as `2025-06-01 17:00`, `tomorrow`
+        /// or `3d`.
         #[arg(long, short = 'D', value_parser = parse_due_date)]
         due: Option<DateTime<Local>>,
         /// The time it takes for the task to reoccur.
@@ -88,11 +90,7 @@ fn task_duration_validator(val: &str) -> Result<i32, String> {
 }
 
 fn parse_due_date(due: &str) -> Result<DateTime<Local>, String> {
-    let due_date = DateTime::parse_from_str(due, "%Y-%m-%d %H:%M:%S");
-    match due_date {
-        Ok(date) => Ok(date.with_timezone(&Local)),
-        Err(_) => Err("Invalid date format. Use %Y-%m-%d %H:%M:%S".into()),
-    }
+    crate::dates::parse_due(due)
 }
 
 fn parent_validator(val: &str) -> Result<i32, String> {
@@ -163,10 +161,9 @@ impl CommandHandler for TaskCommands {
 }
 
 fn get_all_task_view(detailed: bool, filter: Option<TaskStatus>) -> crate::Result<()> {
-    let tasks_res = if filter.is_none() {
-        get_all_tasks()
-    } else {
-        get_tasks_by_status(filter.unwrap())
+    let tasks_res = match filter {
+        Some(status) => get_tasks_by_status(status),
+        None => get_all_tasks(),
     };
     let tasks = tasks_res.map_err(|e| e.to_string())?;
     if tasks.is_empty() {
@@ -176,10 +173,10 @@ fn get_all_task_view(detailed: bool, filter: Option<TaskStatus>) -> crate::Resul
         if !detailed {
             println!("Task ({:02}): {:10}", task.id, task.task);
             println!("Due: {}", due_date_display(task.due_date));
-            println!("Status: {}", (TaskStatus::from(task.status)).to_string());
+            println!("Status: {}", TaskStatus::from(task.status));
         } else {
             println!("Task: {}", task.task);
-            println!("Description: {}", task.desc.unwrap_or("None".to_string()));
+            println!("{}", description_display(&task.desc));
         }
     }
     Ok(())
@@ -197,26 +194,28 @@ fn task_menu() -> crate::Result<()> {
 }
 
 fn get_task_view(detailed: bool, task_id: Option<i32>) -> crate::Result<()> {
-    if task_id.is_none() {
-        let tasks = get_all_root_tasks().map_err(|e| e.to_string())?;
-        if tasks.is_empty() {
-            println!("No tasks to display.");
-        }
+    match task_id {
+        None => {
+            let tasks = get_all_root_tasks().map_err(|e| e.to_string())?;
+            if tasks.is_empty() {
+                println!("No tasks to display.");
+            }
 
-        for task in tasks {
-            if detailed {
-                print_detailed(&task);
-            } else {
-                print_task(&task);
+            for task in tasks {
+                if detailed {
+                    print_detailed(&task);
+                } else {
+                    print_task(&task);
+                }
             }
         }
-    } else {
-        let id = task_id.unwrap();
-        let task = get_task_by_id(id).map_err(|e| e.to_string())?;
-        if !detailed {
-            print_task(&task);
-        } else {
-            print_detailed(&task);
+        Some(id) => {
+            let task = get_task_by_id(id).map_err(|e| e.to_string())?;
+            if !detailed {
+                print_task(&task);
+            } else {
+                print_detailed(&task);
+            }
         }
     }
     Ok(())
@@ -229,27 +228,69 @@ fn due_date_display(due_date: Option<chrono::NaiveDateTime>) -> String {
     }
 }
 
-fn print_task(task: &Task) -> () {
-    println!("Task ({:02}): {:<80} Due: {}", task.id, task.task, due_date_display(task.due_date));
+/// Render a task description wrapped to the terminal width.
+fn description_display(desc: &Option<String>) -> String {
+    labeled(
+        "Description: ",
+        desc.as_deref().unwrap_or("None"),
+        terminal_width(),
+    )
 }
 
-fn print_detailed(task: &Task) -> () {
-    print!("Task ({:02}): {:<80} ", task.id, task.task);
+/// Width of the `Task (NN): ` prefix.
+const TASK_PREFIX: usize = 11;
+
+/// Width of ` Due: ` followed by a date and time.
+const DUE_WIDTH: usize = 25;
+
+/// Width of the task column, so that due dates line up at the end of the
+/// terminal.
+///
+/// The rest of the line is the `Task (NN): ` prefix and the due date.
+fn task_width() -> usize {
+    terminal_width().saturating_sub(TASK_PREFIX + DUE_WIDTH).max(20)
+}
+
+fn print_task(task: &Task) {
+    println!(
+        "Task ({:02}): {} Due: {}",
+        task.id,
+        pad_right(&task.task, task_width()),
+        due_date_display(task.due_date)
+    );
+}
+
+fn print_detailed(task: &Task) {
+    let width = task_width();
+    print!("Task ({:02}): {} ", task.id, pad_right(&task.task, width));
     println!("Due: {}", due_date_display(task.due_date));
-    if let Some(dur) = task.renewal_duration {
-        if dur > 0 {
+    match task.renewal_duration {
+        Some(dur) if dur > 0 => {
             let renew_str = format!("Renews every: {} days", dur);
-            print!("{:<92}", renew_str);
+            print!("{}", pad_right(&renew_str, TASK_PREFIX + width + 1));
             println!("Status: {}", task_status_utf8(&TaskStatus::from(task.status)));
         }
+        _ => println!("Status: {}", task_status_utf8(&TaskStatus::from(task.status))),
     }
-    else {
-        println!("Status: {}", task_status_utf8(&TaskStatus::from(task.status)));
-    }
-    println!("Description: {}", task.desc.clone().unwrap_or("None".to_string()));
+    println!("{}", description_display(&task.desc));
     println!("Subtasks:");
     let children = get_child_tasks(task.id).unwrap();
+    //  Subtask descriptions hang under the description column.
+    let hanging = " ".repeat(4 + 3 + 40);
     for child in children {
-        println!("\t{} {:<40}{:<80}", task_status_utf8(&child.status.into()), child.task, child.desc.unwrap_or("None".to_string()));
+        let prefix = format!(
+            "    {} {}",
+            task_status_utf8(&child.status.into()),
+            pad_right(&child.task, 40)
+        );
+        println!(
+            "{}",
+            wrap_str(
+                child.desc.as_deref().unwrap_or("None"),
+                terminal_width(),
+                &prefix,
+                &hanging
+            )
+        );
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use clap::Subcommand;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::{Colorize, ENV};
+
+use crate::commands::CommandHandler;
+
+/// Placeholder in a bang's URL template that is replaced by the query.
+pub const QUERY_PLACEHOLDER: &str = "{query}";
+
+/// Manage search bangs such as `!rs`.
+#[derive(Debug, Subcommand)]
+pub(crate) enum BangCommands {
+    /// List the available bangs.
+    List,
+    /// Add a bang or replace an existing one.
+    Add {
+        /// Name of the bang, with or without the leading `!`.
+        bang: String,
+        /// URL template. `{query}` is replaced by the search query.
+        template: String,
+    },
+    /// Remove a bang.
+    Remove {
+        /// Name of the bang, with or without the leading `!`.
+        bang: String,
+    },
+}
+
+impl CommandHandler for BangCommands {
+    fn handle(&self) -> crate::Result<()> {
+        match self {
+            BangCommands::List => {
+                let env = ENV.lock().unwrap();
+                let width = env.bangs.keys().map(|b| b.len() + 1).max().unwrap_or(0);
+                for (bang, template) in &env.bangs {
+                    let name = format!("{:<width$}", format!("!{}", bang));
+                    println!("{}  {}", name.cyan(), template);
+                }
+                Ok(())
+            }
+            BangCommands::Add { bang, template } => {
+                if !template.contains(QUERY_PLACEHOLDER) {
+                    return Err(format!(
+                        "The template must contain a {} placeholder.",
+                        QUERY_PLACEHOLDER
+                    )
+                    .into());
+                }
+                let bang = normalize_bang(bang);
+                if bang.is_empty() || bang.contains(char::is_whitespace) {
+                    return Err("A bang must be a single word.".into());
+                }
+                let env = &mut ENV.lock().unwrap();
+                env.bangs.insert(bang.clone(), template.clone());
+                env.save();
+                println!("Added {}: {}", format!("!{}", bang).cyan(), template);
+                Ok(())
+            }
+            BangCommands::Remove { bang } => {
+                let bang = normalize_bang(bang);
+                let env = &mut ENV.lock().unwrap();
+                if env.bangs.remove(&bang).is_none() {
+                    return Err(format!("No bang found with name: !{}", bang).into());
+                }
+                env.save();
+                println!("Removed {}", format!("!{}", bang).cyan());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Strip the leading `!` from a bang name and lowercase it.
+fn normalize_bang(bang: &str) -> String {
+    bang.trim().trim_start_matches('!').to_lowercase()
+}
+
+/// Expand the first known bang in a query.
+///
+/// The bang can appear anywhere in the query, for example `!rs serde` or
+/// `serde !rs`. Returns the bang and the expanded URL, or `None` if the query
+/// has no known bang.
+pub fn expand_bang(query: &str, bangs: &BTreeMap<String, String>) -> Option<(String, String)> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let (index, bang, template) = words.iter().enumerate().find_map(|(i, word)| {
+        let name = word.strip_prefix('!')?.to_lowercase();
+        bangs.get(&name).map(|template| (i, name, template))
+    })?;
+
+    let rest = words
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, w)| *w)
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let encoded = utf8_percent_encode(&rest, NON_ALPHANUMERIC).to_string();
+    Some((bang, template.replace(QUERY_PLACEHOLDER, &encoded)))
+}
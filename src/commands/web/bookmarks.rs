@@ -0,0 +1,373 @@
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use clap::Subcommand;
+
+use crate::Colorize;
+use crate::commands::CommandHandler;
+use crate::database::{self, Bookmark};
+use crate::http::HttpClient;
+
+use super::core::open_url;
+use super::html::{decode_entities, encode_entities, html_title};
+
+/// How long to wait for a page when fetching its title.
+const TITLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Manage bookmarked URLs.
+#[derive(Debug, Subcommand)]
+pub(crate) enum BookmarkCommands {
+    /// Bookmark a URL.
+    ///
+    /// The title is fetched from the page when it is not given.
+    Add {
+        url: String,
+        /// Title of the bookmark.
+        #[arg(short = None, long)]
+        title: Option<String>,
+        /// Tags to apply to the bookmark.
+        #[arg(short = 't', long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Do not fetch the title from the page.
+        #[arg(short = None, long)]
+        no_fetch: bool,
+    },
+    /// List bookmarks, newest first.
+    List {
+        /// Only list bookmarks with this tag.
+        #[arg(short = 't', long)]
+        tag: Option<String>,
+    },
+    /// Find bookmarks whose URL, title or tags contain some text.
+    Search { text: String },
+    /// Open a bookmark in the browser.
+    Open { id: i32 },
+    /// Delete a bookmark.
+    Delete { id: i32 },
+    /// Import bookmarks from a browser's bookmarks HTML file.
+    ///
+    /// Folders are imported as tags. URLs that are already bookmarked are
+    /// skipped.
+    Import { file: PathBuf },
+    /// Export bookmarks as a bookmarks HTML file that browsers can import.
+    Export {
+        /// File to write to. Prints to stdout if not given.
+        file: Option<PathBuf>,
+    },
+}
+
+impl CommandHandler for BookmarkCommands {
+    fn handle(&self) -> crate::Result<()> {
+        match self {
+            BookmarkCommands::Add {
+                url,
+                title,
+                tags,
+                no_fetch,
+            } => {
+                let title = match title {
+                    Some(title) => Some(title.clone()),
+                    None if !no_fetch => fetch_title(url),
+                    None => None,
+                };
+                let now = chrono::Local::now().naive_local();
+                let bookmark = database::sqlite::insert_bookmark(url, title, now, tags)
+                    .map_err(|e| e.to_string())?;
+                let tags =
+                    database::sqlite::get_bookmark_tags(bookmark.id).map_err(|e| e.to_string())?;
+                println!("Added bookmark {}", bookmark.id);
+                print_bookmark(&bookmark, &tags);
+                Ok(())
+            }
+            BookmarkCommands::List { tag } => {
+                let bookmarks = match tag {
+                    Some(tag) => database::sqlite::get_bookmarks_by_tag(tag),
+                    None => database::sqlite::get_bookmarks(),
+                };
+                print_bookmarks(bookmarks.map_err(|e| e.to_string())?)
+            }
+            BookmarkCommands::Search { text } => {
+                let bookmarks =
+                    database::sqlite::search_bookmarks(text).map_err(|e| e.to_string())?;
+                print_bookmarks(bookmarks)
+            }
+            BookmarkCommands::Open { id } => {
+                let bookmark = database::sqlite::get_bookmark(*id).map_err(|e| e.to_string())?;
+                open_url(&bookmark.url)
+            }
+            BookmarkCommands::Delete { id } => {
+                database::sqlite::delete_bookmark(*id).map_err(|e| e.to_string())?;
+                println!("Deleted bookmark {}", id);
+                Ok(())
+            }
+            BookmarkCommands::Import { file } => import_bookmarks(file),
+            BookmarkCommands::Export { file } => {
+                let bookmarks = database::sqlite::get_bookmarks().map_err(|e| e.to_string())?;
+                let tags = database::sqlite::get_all_bookmark_tags().map_err(|e| e.to_string())?;
+                let html = to_netscape(&bookmarks, &tags);
+                match file {
+                    Some(file) => {
+                        fs::write(file, html)?;
+                        println!(
+                            "Exported {} bookmarks to {}",
+                            bookmarks.len(),
+                            file.display()
+                        );
+                    }
+                    None => print!("{}", html),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn print_bookmarks(bookmarks: Vec<Bookmark>) -> crate::Result<()> {
+    if bookmarks.is_empty() {
+        println!("No bookmarks found.");
+        return Ok(());
+    }
+    let tags = database::sqlite::get_all_bookmark_tags().map_err(|e| e.to_string())?;
+    for bookmark in &bookmarks {
+        print_bookmark(bookmark, tags.get(&bookmark.id).map_or(&[], |t| t));
+    }
+    Ok(())
+}
+
+fn print_bookmark(bookmark: &Bookmark, tags: &[String]) {
+    let title = bookmark.title.as_deref().unwrap_or(&bookmark.url);
+    if tags.is_empty() {
+        println!("({:02}) {}", bookmark.id, title.cyan_bright());
+    } else {
+        println!(
+            "({:02}) {} [{}]",
+            bookmark.id,
+            title.cyan_bright(),
+            tags.join(", ").cyan()
+        );
+    }
+    println!("     {}", bookmark.url.green());
+}
+
+/// Fetch the title of a page.
+///
+/// Returns `None` if the page cannot be fetched or has no title, so that
+/// bookmarks can be added offline.
+fn fetch_title(url: &str) -> Option<String> {
+    let client = HttpClient::new().ok()?.timeout(TITLE_TIMEOUT).retries(0);
+    let body = match client.get_text(url) {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("{}", format!("Could not fetch the title: {}", e).yellow());
+            return None;
+        }
+    };
+    html_title(&body)
+}
+
+fn import_bookmarks(file: &PathBuf) -> crate::Result<()> {
+    let html = fs::read_to_string(file)?;
+    let entries = parse_netscape(&html);
+    let (mut added, mut skipped) = (0, 0);
+    for entry in entries {
+        if database::sqlite::get_bookmark_by_url(&entry.url)
+            .map_err(|e| e.to_string())?
+            .is_some()
+        {
+            skipped += 1;
+            continue;
+        }
+        let added_at = entry
+            .added
+            .unwrap_or_else(|| chrono::Local::now().naive_local());
+        database::sqlite::insert_bookmark(&entry.url, entry.title, added_at, &entry.tags)
+            .map_err(|e| e.to_string())?;
+        added += 1;
+    }
+    println!(
+        "Imported {} bookmarks, skipped {} already bookmarked.",
+        added, skipped
+    );
+    Ok(())
+}
+
+/// A bookmark read from a bookmarks HTML file.
+#[derive(Debug, Default)]
+struct ImportedBookmark {
+    url: String,
+    title: Option<String>,
+    added: Option<NaiveDateTime>,
+    tags: Vec<String>,
+}
+
+/// Parse the Netscape bookmarks HTML format that browsers export.
+///
+/// The format is not well formed HTML, so it is scanned tag by tag. Links
+/// are `<A>` elements, and folders are `<H3>` headings followed by a `<DL>`
+/// list of their contents.
+fn parse_netscape(html: &str) -> Vec<ImportedBookmark> {
+    let mut bookmarks = vec![];
+    let mut folders: Vec<String> = vec![];
+    //  Folder named by the last heading, opened by the next list.
+    let mut pending: Option<String> = None;
+    //  Whether each open list is a folder.
+    let mut lists: Vec<bool> = vec![];
+    //  Lowercased once to find end tags. ASCII lowercasing keeps the
+    //  offsets the same as in `html`.
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+
+    while let Some(open) = html[pos..].find('<') {
+        let start = pos + open;
+        let Some(close) = html[start..].find('>').map(|c| start + c) else {
+            break;
+        };
+        let tag = &html[start + 1..close];
+        let name = lower[start + 1..close]
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        let after = close + 1;
+
+        match name {
+            "a" | "h3" => {
+                let end_tag = format!("</{}", name);
+                let end = lower[after..]
+                    .find(&end_tag)
+                    .map_or(html.len(), |e| after + e);
+                let text = decode_entities(html[after..end].trim());
+                if name == "h3" {
+                    pending = Some(text);
+                } else if let Some(url) = attribute(tag, "href") {
+                    let mut tags = folders.clone();
+                    if let Some(extra) = attribute(tag, "tags") {
+                        tags.extend(extra.split(',').map(|t| t.trim().to_string()));
+                    }
+                    bookmarks.push(ImportedBookmark {
+                        url,
+                        title: (!text.is_empty()).then_some(text),
+                        added: attribute(tag, "add_date")
+                            .and_then(|d| d.parse::<i64>().ok())
+                            .and_then(|d| DateTime::from_timestamp(d, 0))
+                            .map(|d| d.with_timezone(&Local).naive_local()),
+                        tags,
+                    });
+                }
+                pos = end;
+                continue;
+            }
+            "dl" => {
+                let folder = pending.take();
+                lists.push(folder.is_some());
+                folders.extend(folder);
+            }
+            "/dl" if lists.pop() == Some(true) => {
+                folders.pop();
+            }
+            _ => (),
+        }
+        pos = after;
+    }
+    bookmarks
+}
+
+/// Get the value of an attribute of a tag, matching its name in any case.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(name) {
+        let start = from + found;
+        from = start + name.len();
+        //  Skip matches inside other names, such as `last_href`.
+        let before = lower[..start].chars().last();
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = lower[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let offset = tag.len() - value.len();
+        let (quote, value) = match value.chars().next()? {
+            q @ ('"' | '\'') => (Some(q), &tag[offset + 1..]),
+            _ => (None, &tag[offset..]),
+        };
+        let end = match quote {
+            Some(q) => value.find(q)?,
+            None => value.find(char::is_whitespace).unwrap_or(value.len()),
+        };
+        return Some(decode_entities(&value[..end]));
+    }
+    None
+}
+
+/// Write bookmarks in the Netscape bookmarks HTML format.
+///
+/// Tags are written to the `TAGS` attribute, which Firefox reads.
+fn to_netscape(bookmarks: &[Bookmark], tags: &HashMap<i32, Vec<String>>) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+    for bookmark in bookmarks {
+        let title = bookmark.title.as_deref().unwrap_or(&bookmark.url);
+        let mut attributes = format!(
+            "HREF=\"{}\" ADD_DATE=\"{}\"",
+            encode_entities(&bookmark.url),
+            Local
+                .from_local_datetime(&bookmark.time_stamp)
+                .earliest()
+                .map_or(0, |d| d.timestamp())
+        );
+        if let Some(tags) = tags.get(&bookmark.id) {
+            attributes.push_str(&format!(" TAGS=\"{}\"", encode_entities(&tags.join(","))));
+        }
+        html.push_str(&format!(
+            "    <DT><A {}>{}</A>\n",
+            attributes,
+            encode_entities(title)
+        ));
+    }
+    html.push_str("</DL><p>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_netscape_reads_folders() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+    <DT><H3>Rust &amp; Co</H3>
+    <DL><p>
+        <DT><A HREF="https://doc.rust-lang.org/" ADD_DATE="0" TAGS="docs">The Book</a>
+    </DL><p>
+    <DT><A href='https://example.com'></A>
+</DL>"#;
+        let bookmarks = parse_netscape(html);
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].url, "https://doc.rust-lang.org/");
+        assert_eq!(bookmarks[0].title.as_deref(), Some("The Book"));
+        assert_eq!(bookmarks[0].tags, ["Rust & Co", "docs"]);
+        assert!(bookmarks[0].added.is_some());
+        assert_eq!(bookmarks[1].url, "https://example.com");
+        assert_eq!(bookmarks[1].title, None);
+        assert!(bookmarks[1].tags.is_empty());
+    }
+
+    #[test]
+    fn attribute_skips_names_without_values() {
+        let tag = r#"a tags data-href="x" HREF="https://example.com" tags=a,b"#;
+        assert_eq!(
+            attribute(tag, "href").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(attribute(tag, "tags").as_deref(), Some("a,b"));
+        assert_eq!(attribute(tag, "title"), None);
+    }
+}
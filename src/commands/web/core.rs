@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use reqwest::Url;
 
 use crate::ENV;
+use crate::commands::environment::core::{SafeSearch, SearchLocale};
 
 /// Search parameters for a google web search.
 #[derive(Debug)]
@@ -12,32 +13,57 @@ pub struct SearchParams {
 }
 
 impl SearchParams {
-    pub fn new(query: &String) -> Self {
+    pub fn new(query: &str, locale: &SearchLocale) -> Self {
         let mut s = Self::default();
-        s.args.insert("q".to_string(), query.clone());
+        s.args.insert("q".to_string(), query.to_string());
+        s.add_locale(locale);
         s
     }
 
     /// Creates a new set of search parameters that is compatible with the
     /// Google Custom Search JSON API.
-    pub fn new_json(query: &String) -> Self {
-        let mut s = Self::default();
-        s.args.insert("q".to_string(), query.clone());
+    ///
+    /// The API returns at most 10 results per request, so `num` is capped.
+    pub fn new_json(query: &str, locale: &SearchLocale) -> Self {
+        let mut s = Self::new(query, locale);
+        s.url = ENV.lock().unwrap().google_search_api_url.clone();
+        s.args
+            .insert("num".to_string(), locale.num.min(10).to_string());
         s.add_api_data();
         s
     }
 
+    /// Add the language, country, safe search and number of results.
+    pub fn add_locale(&mut self, locale: &SearchLocale) {
+        let lang = locale.lang.to_lowercase();
+        let country = locale.country.to_lowercase();
+        //  Language
+        self.args.insert("hl".to_string(), lang.clone());
+        //  Location/Country
+        self.args.insert("gl".to_string(), country.clone());
+        //  Language Restriction
+        self.args.insert("lr".to_string(), format!("lang_{}", lang));
+        //  Country Restriction
+        self.args.insert(
+            "cr".to_string(),
+            format!("country{}", country.to_uppercase()),
+        );
+        let safe = match locale.safe {
+            SafeSearch::Off => "off",
+            SafeSearch::Active => "active",
+        };
+        self.args.insert("safe".to_string(), safe.to_string());
+        self.args.insert("num".to_string(), locale.num.to_string());
+    }
+
     /// Add the API key and search engine ID to the search parameters.
     /// This is required for the Google Custom Search JSON API.
     pub fn add_api_data(&mut self) {
-        self.args.insert(
-            "key".to_string(),
-            ENV.lock().unwrap().google_search_api_key.clone(),
-        );
-        self.args.insert(
-            "cx".to_string(),
-            ENV.lock().unwrap().google_search_engine_id.clone(),
-        );
+        let env = ENV.lock().unwrap();
+        self.args
+            .insert("key".to_string(), env.google_search_api_key.clone());
+        self.args
+            .insert("cx".to_string(), env.google_search_engine_id.clone());
     }
 }
 
@@ -49,27 +75,15 @@ impl From<SearchParams> for Url {
 
 impl Default for SearchParams {
     fn default() -> Self {
-        let mut s = SearchParams {
+        SearchParams {
             url: String::from("https://www.google.com/search"),
             args: HashMap::new(),
-        };
-        //  Language
-        s.args.insert(String::from("hl"), String::from("en"));
-        //  Location/Country
-        s.args.insert("gl".to_string(), "us".to_string());
-        //  Language Restriction
-        s.args.insert("lr".to_string(), "lang_en".to_string());
-        //  Country Restriction
-        s.args.insert("cr".to_string(), "countryUS".to_string());
-        //  Query
-        // s.args
-        //     .insert("q".to_string(), "Spyder337 site:github.com".to_string());
-        s
+        }
     }
 }
 
 /// Open a URL in the default browser.
-fn open_url(url: &str) -> crate::Result<()> {
+pub fn open_url(url: &str) -> crate::Result<()> {
     let res = open::that_detached(url);
 
     if res.is_ok() {
@@ -80,45 +94,12 @@ fn open_url(url: &str) -> crate::Result<()> {
     }
 }
 
-/// Perform a basic search using the given search parameters.
+/// Open a search in the default browser, returning the URL that was opened.
 ///
-/// Optionally open the search results in the default browser.
-pub fn basic_search(mut options: SearchParams, json: &bool) -> crate::Result<()> {
-    options.add_api_data();
+/// The search is opened on the engine's website, so no API data is sent.
+pub fn basic_search(options: SearchParams) -> crate::Result<String> {
     let url: Url = options.into();
-    // println!("Url: {}", url);
-    if *json {
-        println!("Fetching web page: {}", url);
-        basic_search_json(url)
-    } else {
-        println!("Opening URL: {}", url);
-        basic_search_open(url)
-    }
-}
-
-fn basic_search_open(url: Url) -> crate::Result<()> {
-    let res = open_url(url.as_ref());
-    res
-}
-
-fn basic_search_json(url: Url) -> crate::Result<()> {
-    let req = reqwest::blocking::get(url.to_string())?;
-    let body = req.text()?;
-    println!("{}", body);
-    Ok(())
-}
-
-pub fn query_string_builder(
-    query: &str,
-    site: &Option<String>,
-    allintext: &Option<String>,
-) -> String {
-    let mut query_string = query.to_string();
-    if let Some(site) = site {
-        query_string.push_str(&format!(" site:{}", site));
-    }
-    if let Some(all_in_text) = allintext {
-        query_string.push_str(&format!(" allintext:{}", all_in_text));
-    }
-    query_string
+    println!("Opening URL: {}", url);
+    open_url(url.as_ref())?;
+    Ok(url.to_string())
 }
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::commands::environment::core::{SafeSearch, SearchEngine, SearchLocale};
+
+use super::core::SearchParams;
+use super::query::SearchQuery;
+
+/// How an engine writes each search operator.
+///
+/// `None` means the engine does not support the operator.
+struct Syntax {
+    site: Option<&'static str>,
+    exclude: Option<&'static str>,
+    filetype: Option<&'static str>,
+    intitle: Option<&'static str>,
+    inurl: Option<&'static str>,
+    allintext: Option<&'static str>,
+    dates: bool,
+    or: bool,
+}
+
+impl SearchEngine {
+    fn syntax(&self) -> Syntax {
+        let web = Syntax {
+            site: Some("site:"),
+            exclude: Some("-"),
+            filetype: Some("filetype:"),
+            intitle: Some("intitle:"),
+            inurl: Some("inurl:"),
+            allintext: None,
+            dates: false,
+            or: true,
+        };
+        match self {
+            SearchEngine::Google => Syntax {
+                allintext: Some("allintext:"),
+                dates: true,
+                ..web
+            },
+            SearchEngine::Bing => Syntax {
+                allintext: Some("inbody:"),
+                ..web
+            },
+            SearchEngine::DuckDuckGo | SearchEngine::Kagi | SearchEngine::SearXng => web,
+            SearchEngine::GitHub => Syntax {
+                //  GitHub restricts searches to repos rather than sites.
+                site: Some("repo:"),
+                exclude: Some("NOT "),
+                filetype: Some("path:*."),
+                intitle: None,
+                inurl: Some("path:"),
+                ..web
+            },
+            //  Site specific engines only search their own site.
+            SearchEngine::DocsRs | SearchEngine::CratesIo => Syntax {
+                site: None,
+                exclude: None,
+                filetype: None,
+                intitle: None,
+                inurl: None,
+                allintext: None,
+                dates: false,
+                or: false,
+            },
+        }
+    }
+
+    /// Translate a query and its operators into this engine's syntax.
+    ///
+    /// Text operators the engine does not support are added as plain search
+    /// terms. Sites, excluded terms and dates are left out.
+    pub fn query_string(&self, query: &SearchQuery) -> String {
+        let syntax = self.syntax();
+        let mut terms = vec![query.terms.clone()];
+
+        if let Some(phrase) = &query.exact {
+            terms.push(format!("\"{}\"", phrase));
+        }
+        if let Some(prefix) = syntax.site {
+            let sites: Vec<String> = query
+                .sites
+                .iter()
+                .filter_map(|s| match self {
+                    SearchEngine::GitHub => github_repo(s),
+                    _ => Some(s.clone()),
+                })
+                .map(|s| format!("{}{}", prefix, s))
+                .collect();
+            terms.push(or_group(&sites));
+        }
+        if let Some(prefix) = syntax.exclude {
+            for term in &query.exclude {
+                terms.push(format!("{}{}", prefix, quote(term)));
+            }
+        }
+        for (value, prefix) in [
+            (&query.filetype, syntax.filetype),
+            (&query.intitle, syntax.intitle),
+            (&query.inurl, syntax.inurl),
+            (&query.allintext, syntax.allintext),
+        ] {
+            match (value, prefix) {
+                (Some(value), Some(prefix)) => terms.push(format!("{}{}", prefix, quote(value))),
+                (Some(value), None) => terms.push(quote(value)),
+                _ => (),
+            }
+        }
+        if syntax.dates {
+            if let Some(after) = query.after {
+                terms.push(format!("after:{}", after.format("%Y-%m-%d")));
+            }
+            if let Some(before) = query.before {
+                terms.push(format!("before:{}", before.format("%Y-%m-%d")));
+            }
+        }
+        let any_of: Vec<String> = query.any_of.iter().map(|t| quote(t)).collect();
+        if syntax.or {
+            terms.push(or_group(&any_of));
+        } else {
+            terms.extend(any_of);
+        }
+
+        terms.retain(|t| !t.is_empty());
+        terms.join(" ")
+    }
+
+    /// Create the parameters for searching this engine for `query_string`.
+    ///
+    /// Locale settings the engine does not support are left out.
+    pub fn params(&self, query_string: &str, locale: &SearchLocale) -> SearchParams {
+        let (url, query_key) = match self {
+            SearchEngine::Google => return SearchParams::new(query_string, locale),
+            SearchEngine::DuckDuckGo => ("https://duckduckgo.com/".to_string(), "q"),
+            SearchEngine::Bing => ("https://www.bing.com/search".to_string(), "q"),
+            SearchEngine::Kagi => ("https://kagi.com/search".to_string(), "q"),
+            SearchEngine::SearXng => {
+                let base = crate::ENV.lock().unwrap().searxng_url.clone();
+                (format!("{}/search", base.trim_end_matches('/')), "q")
+            }
+            SearchEngine::DocsRs => ("https://docs.rs/releases/search".to_string(), "query"),
+            SearchEngine::CratesIo => ("https://crates.io/search".to_string(), "q"),
+            SearchEngine::GitHub => ("https://github.com/search".to_string(), "q"),
+        };
+
+        let mut args = HashMap::new();
+        args.insert(query_key.to_string(), query_string.to_string());
+        let lang = locale.lang.to_lowercase();
+        let country = locale.country.to_lowercase();
+        let safe = locale.safe == SafeSearch::Active;
+        match self {
+            SearchEngine::DuckDuckGo => {
+                args.insert("kl".to_string(), format!("{}-{}", country, lang));
+                args.insert("kp".to_string(), if safe { "1" } else { "-2" }.to_string());
+            }
+            SearchEngine::Bing => {
+                args.insert("setlang".to_string(), lang);
+                args.insert("cc".to_string(), country);
+                args.insert("count".to_string(), locale.num.to_string());
+                args.insert(
+                    "adlt".to_string(),
+                    if safe { "strict" } else { "off" }.to_string(),
+                );
+            }
+            SearchEngine::SearXng => {
+                args.insert(
+                    "language".to_string(),
+                    format!("{}-{}", lang, country.to_uppercase()),
+                );
+                args.insert(
+                    "safesearch".to_string(),
+                    if safe { "2" } else { "0" }.to_string(),
+                );
+            }
+            SearchEngine::GitHub => {
+                args.insert("type".to_string(), "code".to_string());
+            }
+            _ => (),
+        }
+        SearchParams { url, args }
+    }
+}
+
+/// Get the `owner/repo` part of a GitHub site, if it has one.
+fn github_repo(site: &str) -> Option<String> {
+    let path = site
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("github.com/")
+        .trim_end_matches('/');
+    let mut parts = path.split('/');
+    match (parts.next(), parts.next()) {
+        (Some(owner), Some(repo)) if !owner.is_empty() && !repo.is_empty() => {
+            Some(format!("{}/{}", owner, repo))
+        }
+        _ => None,
+    }
+}
+
+/// Quote a term if it has more than one word.
+fn quote(term: &str) -> String {
+    if term.contains(char::is_whitespace) {
+        format!("\"{}\"", term)
+    } else {
+        term.to_string()
+    }
+}
+
+/// Join terms so that any of them may match.
+fn or_group(terms: &[String]) -> String {
+    match terms.len() {
+        0 => String::new(),
+        1 => terms[0].clone(),
+        _ => format!("({})", terms.join(" OR ")),
+    }
+}
@@ -0,0 +1,404 @@
+//! Helpers for reading HTML pages without a full parser.
+
+/// Elements whose content is never readable text.
+const SKIPPED: [&str; 13] = [
+    "script", "style", "noscript", "svg", "template", "head", "nav", "header", "footer", "aside",
+    "form", "iframe", "button",
+];
+
+/// Elements that start a new line of text.
+const BLOCKS: [&str; 24] = [
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "ul",
+    "ol",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "tr",
+    "blockquote",
+    "pre",
+    "figure",
+    "figcaption",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "details",
+];
+
+/// Minimum length of the text of an `<article>` or `<main>` element for it
+/// to be taken as the content of the page.
+const MIN_CONTENT: usize = 200;
+
+/// Get the contents of the `<title>` element of an HTML page.
+pub fn html_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(
+        html[start..end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .as_str(),
+    );
+    (!title.is_empty()).then_some(title)
+}
+
+/// Extract the readable text of an HTML page.
+///
+/// Scripts, styles and page chrome such as navigation, headers and footers
+/// are dropped. If the page has an `<article>` or `<main>` element with
+/// enough text, only the longest one is kept. Block elements become
+/// paragraphs and list items become bullets.
+pub fn extract_text(html: &str) -> String {
+    let mut best: Option<String> = None;
+    for name in ["article", "main"] {
+        for element in elements(html, name) {
+            let text = render(element);
+            if text.len() >= MIN_CONTENT && best.as_ref().is_none_or(|b| text.len() > b.len()) {
+                best = Some(text);
+            }
+        }
+    }
+    best.unwrap_or_else(|| render(html))
+}
+
+/// The contents of every `name` element of `html`, outermost only.
+fn elements<'a>(html: &'a str, name: &str) -> Vec<&'a str> {
+    let lower = html.to_ascii_lowercase();
+    let (open, close) = (format!("<{}", name), format!("</{}", name));
+    let mut found = vec![];
+    let mut from = 0;
+    while let Some(start) = lower[from..].find(&open).map(|i| from + i) {
+        from = start + open.len();
+        //  Skip longer names with the same start, such as `<mainframe>`.
+        if !lower[from..].starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            continue;
+        }
+        let Some(body) = lower[from..].find('>').map(|i| from + i + 1) else {
+            break;
+        };
+        //  Find the matching end tag, counting nested elements.
+        let mut depth = 1;
+        let mut at = body;
+        let end = loop {
+            let next_open = lower[at..].find(&open).map(|i| at + i);
+            let Some(next_close) = lower[at..].find(&close).map(|i| at + i) else {
+                break lower.len();
+            };
+            match next_open {
+                Some(next) if next < next_close => {
+                    depth += 1;
+                    at = next + open.len();
+                }
+                _ => {
+                    depth -= 1;
+                    at = next_close + close.len();
+                    if depth == 0 {
+                        break next_close;
+                    }
+                }
+            }
+        };
+        found.push(&html[body..end]);
+        from = end;
+    }
+    found
+}
+
+/// Turn HTML into text, tag by tag.
+fn render(html: &str) -> String {
+    let mut out = String::new();
+    //  Skipped element the scan is inside of, and how deeply.
+    let mut skipping: Option<(String, usize)> = None;
+    let mut pre: usize = 0;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            if skipping.is_none() {
+                push_text(&mut out, rest, pre > 0);
+            }
+            break;
+        };
+        if skipping.is_none() {
+            push_text(&mut out, &rest[..open], pre > 0);
+        }
+        rest = &rest[open..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(close) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let self_closing = tag.ends_with('/');
+
+        if let Some((skipped, depth)) = &mut skipping {
+            if *skipped == name && !self_closing {
+                if closing {
+                    *depth -= 1;
+                } else {
+                    *depth += 1;
+                }
+                if *depth == 0 {
+                    skipping = None;
+                }
+            }
+            continue;
+        }
+        if SKIPPED.contains(&name.as_str()) {
+            if !closing && !self_closing {
+                skipping = Some((name, 1));
+            }
+            continue;
+        }
+
+        match name.as_str() {
+            "br" => out.push('\n'),
+            "li" if !closing => {
+                new_line(&mut out);
+                out.push_str("• ");
+            }
+            "pre" => {
+                new_paragraph(&mut out);
+                pre = if closing {
+                    pre.saturating_sub(1)
+                } else {
+                    pre + 1
+                };
+            }
+            "td" | "th" if closing => out.push(' '),
+            _ if BLOCKS.contains(&name.as_str()) => new_paragraph(&mut out),
+            _ => (),
+        }
+    }
+    tidy(&out)
+}
+
+/// Add text to the output, collapsing whitespace outside of `<pre>`.
+fn push_text(out: &mut String, text: &str, preformatted: bool) {
+    let text = decode_entities(text);
+    if preformatted {
+        out.push_str(&text);
+        return;
+    }
+    let mut words = text.split_whitespace().peekable();
+    if words.peek().is_none() {
+        if !text.is_empty() && !out.is_empty() && !out.ends_with(char::is_whitespace) {
+            out.push(' ');
+        }
+        return;
+    }
+    if text.starts_with(char::is_whitespace)
+        && !out.is_empty()
+        && !out.ends_with(char::is_whitespace)
+    {
+        out.push(' ');
+    }
+    out.push_str(&words.collect::<Vec<_>>().join(" "));
+    if text.ends_with(char::is_whitespace) {
+        out.push(' ');
+    }
+}
+
+fn new_line(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn new_paragraph(out: &mut String) {
+    new_line(out);
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+/// Trim the lines of the text and keep at most one empty line between
+/// paragraphs.
+fn tidy(text: &str) -> String {
+    let mut lines: Vec<&str> = vec![];
+    for line in text.lines().map(str::trim_end) {
+        let line = if line.trim().is_empty() { "" } else { line };
+        if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+pub fn encode_entities(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The character of a named or numeric entity, without its `&` and `;`.
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "middot" => '·',
+        "bull" => '•',
+        _ => return None,
+    };
+    Some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text long enough for an `<article>` to be taken as the content.
+    fn long_text() -> String {
+        "The borrow checker makes sure references are valid. ".repeat(5)
+    }
+
+    #[test]
+    fn page_chrome_is_skipped() {
+        let html = "<html><head><title>Page</title><style>p { color: red }</style></head>
+            <body>
+              <header><h1>Site name</h1></header>
+              <nav><ul><li><a href='/'>Home</a></li></ul></nav>
+              <p>First   paragraph
+                 of the page.</p>
+              <script>alert('hi')</script>
+              <ul><li>One</li><li>Two</li></ul>
+              <footer>Copyright</footer>
+            </body></html>";
+        assert_eq!(
+            extract_text(html),
+            "First paragraph of the page.\n\n• One\n• Two"
+        );
+    }
+
+    #[test]
+    fn nested_skipped_elements() {
+        let html = "<div><nav>Menu <nav>Inner</nav> still menu</nav>Text</div>";
+        assert_eq!(extract_text(html), "Text");
+    }
+
+    #[test]
+    fn article_is_preferred() {
+        let html = format!(
+            "<div>Sidebar text</div><main><p>Short</p></main><article><p>{}</p></article>",
+            long_text()
+        );
+        assert_eq!(extract_text(&html), long_text().trim_end());
+    }
+
+    #[test]
+    fn short_article_is_not_preferred() {
+        let html = "<p>Intro</p><article>Short</article>";
+        assert_eq!(extract_text(html), "Intro\n\nShort");
+    }
+
+    #[test]
+    fn pre_is_kept_as_is() {
+        let html =
+            "<p>Example:</p><pre>fn main() {\n    println!(\"&lt;hi&gt;\");\n}</pre><p>Done</p>";
+        assert_eq!(
+            extract_text(html),
+            "Example:\n\nfn main() {\n    println!(\"<hi>\");\n}\n\nDone"
+        );
+    }
+
+    #[test]
+    fn entities_are_decoded() {
+        assert_eq!(
+            decode_entities("a &amp; b &lt;c&gt; &quot;d&quot; &#65;&#x42; &unknown; & e"),
+            "a & b <c> \"d\" AB &unknown; & e"
+        );
+        assert_eq!(
+            extract_text("<p>Tom &amp; Jerry&nbsp;&#8212; cartoon</p>"),
+            "Tom & Jerry — cartoon"
+        );
+        assert_eq!(
+            decode_entities(&encode_entities("<a href=\"x\">&</a>")),
+            "<a href=\"x\">&</a>"
+        );
+    }
+
+    #[test]
+    fn title() {
+        let html = "<HEAD><Title>\n  Rust &amp;\n  Co </TITLE></HEAD>";
+        assert_eq!(html_title(html).as_deref(), Some("Rust & Co"));
+        assert_eq!(html_title("<title> </title>"), None);
+        assert_eq!(html_title("<p>No title</p>"), None);
+    }
+}
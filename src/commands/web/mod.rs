@@ -1,187 +1,512 @@
+pub mod bangs;
+pub mod bookmarks;
 pub mod core;
+pub mod engine;
+pub mod html;
+pub mod profiles;
+pub mod query;
+pub mod results;
+pub mod snapshots;
+pub mod stats;
+pub mod suggest;
 
-use clap::Subcommand;
+use std::io::{IsTerminal, Write, stdin, stdout};
 
-use core::{SearchParams, basic_search, query_string_builder};
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::{Args, Subcommand};
+use query::SearchQuery;
+use reqwest::Url;
+
+use bangs::{BangCommands, expand_bang};
+use bookmarks::BookmarkCommands;
+use core::{SearchParams, basic_search, open_url};
+use profiles::{LocaleArgs, ProfileCommands, resolve_locale};
+use results::{fetch_results, open_result, print_results, prompt_open};
+use snapshots::{ReadArgs, SaveArgs};
+use stats::StatsArgs;
+use suggest::SuggestArgs;
 
 use crate::{
-    commands::time_now,
-    database::{self, SearchEntry},
+    commands::environment::core::SearchEngine,
+    database::{self, SearchEntry, sqlite::SearchFilter},
 };
 
 use super::CommandHandler;
+use crate::{Colorize, ENV, dates, layout};
 
 /// A set of web utilities.
 #[derive(Debug, Subcommand)]
 pub(crate) enum WebCommands {
-    /// Search google for a query.
-    Search {
-        /// Search query.
-        query: String,
-        /// Site to restrict search to.
-        #[arg(short = None, long)]
-        site: Option<String>,
-        /// Search for text in the page.
-        #[arg(short = None, long)]
-        allintext: Option<String>,
-        /// Return results in JSON format.
-        #[arg(short = None, long)]
-        json: Option<bool>,
-    },
+    /// Search the web for a query.
+    ///
+    /// A query containing a bang such as `!rs serde` opens the bang's URL
+    /// instead.
+    Search(Box<SearchArgs>),
     /// View search history.
     History {
         #[command(subcommand)]
-        command: HistoryCommands,
+        command: Box<HistoryCommands>,
+    },
+    /// Manage search bangs.
+    Bangs {
+        #[command(subcommand)]
+        command: BangCommands,
     },
+    /// Keep useful URLs.
+    Bookmark {
+        #[command(subcommand)]
+        command: BookmarkCommands,
+    },
+    /// Manage search profiles.
+    Profiles {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+    /// Suggest past queries, ranked by how often and how recently they were
+    /// searched.
+    Suggest(SuggestArgs),
+    /// Save the readable text of a page to read it offline.
+    Save(SaveArgs),
+    /// Read a saved page, or list the saved pages.
+    Read(ReadArgs),
+}
+
+/// A web search and its operators.
+#[derive(Debug, Args)]
+pub(crate) struct SearchArgs {
+    /// Search query.
+    query: String,
+    /// Search engine to use. Defaults to the `search_engine` setting.
+    #[arg(short = 'e', long, value_enum)]
+    engine: Option<SearchEngine>,
+    /// Sites to restrict the search to. Results may come from any of them.
+    #[arg(short = None, long, value_delimiter = ',')]
+    site: Vec<String>,
+    /// Search for text in the page.
+    #[arg(short = None, long)]
+    allintext: Option<String>,
+    /// Phrase the results must contain exactly.
+    #[arg(short = None, long)]
+    exact: Option<String>,
+    /// Terms the results must not contain.
+    #[arg(short = None, long, value_delimiter = ',')]
+    exclude: Vec<String>,
+    /// File type of the results, such as `pdf`.
+    #[arg(short = None, long)]
+    filetype: Option<String>,
+    /// Text the result titles must contain.
+    #[arg(short = None, long)]
+    intitle: Option<String>,
+    /// Text the result URLs must contain.
+    #[arg(short = None, long)]
+    inurl: Option<String>,
+    /// Only find results published after this date, such as `2025-01-31`
+    /// or `4w`.
+    #[arg(short = None, long, value_parser = parse_after)]
+    after: Option<NaiveDate>,
+    /// Only find results published before this date.
+    #[arg(short = None, long, value_parser = parse_before)]
+    before: Option<NaiveDate>,
+    /// Terms of which the results must contain at least one.
+    #[arg(short = None, long, value_delimiter = ',')]
+    any_of: Vec<String>,
+    /// List the results from the Custom Search JSON API instead of
+    /// opening the search in the browser. Only supported for Google.
+    #[arg(short = 'r', long)]
+    results: bool,
+    /// Open result N from the list of results.
+    #[arg(short = 'o', long, value_name = "N")]
+    open: Option<usize>,
+    /// Search profile whose settings override the defaults.
+    #[arg(short = 'p', long)]
+    profile: Option<String>,
+    /// Settings that override the defaults and the profile.
+    #[command(flatten)]
+    locale: LocaleArgs,
+    /// Print the results in JSON format.
+    #[arg(short = None, long, requires = "results")]
+    json: bool,
+    /// Fetch the results even if they are cached.
+    #[arg(short = None, long, requires = "results")]
+    no_cache: bool,
+}
+
+impl SearchArgs {
+    /// The search query with its operators.
+    fn search_query(&self) -> SearchQuery {
+        SearchQuery::new(&self.query)
+            .sites(&self.site)
+            .allintext(self.allintext.clone())
+            .exact(self.exact.clone())
+            .exclude(&self.exclude)
+            .filetype(self.filetype.clone())
+            .intitle(self.intitle.clone())
+            .inurl(self.inurl.clone())
+            .after(self.after)
+            .before(self.before)
+            .any_of(&self.any_of)
+    }
+}
+
+fn parse_after(date: &str) -> Result<NaiveDate, String> {
+    dates::parse_from(date).map(|time| time.date())
+}
+
+fn parse_before(date: &str) -> Result<NaiveDate, String> {
+    dates::parse_to(date).map(|time| time.date())
 }
 
 impl CommandHandler for WebCommands {
     fn handle(&self) -> crate::Result<()> {
         match self {
-            WebCommands::Search {
-                query,
-                site,
-                allintext,
-                json,
-            } => {
-                // println!("Searching for: {}", query);
-                let query_string = query_string_builder(query, site, allintext);
-                let search = SearchParams::new(&query_string);
-
-                // println!("Search Params: {:?}", search);
-
-                let search_res = basic_search(search, &(json.unwrap_or(false)));
-
-                if search_res.is_err() {
-                    return Err(search_res.err().unwrap());
-                }
-
-                let new_search = SearchEntry {
-                    id: 0,
-                    query: query.clone(),
-                    website: site.clone(),
-                    allintext: allintext.clone(),
-                    time_stamp: time_now(),
-                };
+            WebCommands::Search(args) => web_search(args),
+            WebCommands::History { command } => command.handle(),
+            WebCommands::Bangs { command } => command.handle(),
+            WebCommands::Bookmark { command } => command.handle(),
+            WebCommands::Profiles { command } => command.handle(),
+            WebCommands::Suggest(args) => args.handle(),
+            WebCommands::Save(args) => args.handle(),
+            WebCommands::Read(args) => args.handle(),
+        }
+    }
+}
 
-                // println!("Search Object: {:?}", new_search);
+/// Run a web search and record it in the search history.
+fn web_search(args: &SearchArgs) -> crate::Result<()> {
+    let list_results = args.results || args.open.is_some();
+    let search = args.search_query();
+    let locale = resolve_locale(args.profile.as_deref(), &args.locale)?;
+    let bangs = ENV.lock().unwrap().bangs.clone();
 
-                let res = database::sqlite::insert_search(new_search);
+    let url = if let Some((bang, url)) = expand_bang(&args.query, &bangs) {
+        if list_results {
+            return Err(format!("Listing results is not supported for !{}.", bang).into());
+        }
+        open_url(&url)?;
+        url
+    } else {
+        let engine = args
+            .engine
+            .unwrap_or_else(|| ENV.lock().unwrap().search_engine);
+        let query_string = search.build(engine);
 
-                if res.is_err() {
-                    return Err(res.err().unwrap());
-                }
-                Ok(())
+        if list_results && engine != SearchEngine::Google {
+            return Err(format!("Listing results is not supported for {}.", engine).into());
+        }
+        if list_results {
+            let items = fetch_results(
+                SearchParams::new_json(&query_string, &locale),
+                args.no_cache,
+            )?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&items)?);
+            } else if let Some(n) = args.open {
+                open_result(&items, n)?;
+            } else {
+                print_results(&items);
+                prompt_open(&items)?;
             }
-            WebCommands::History { command } => command.handle(),
+            Url::from(engine.params(&query_string, &locale)).to_string()
+        } else {
+            basic_search(engine.params(&query_string, &locale))?
         }
-    }
+    };
+
+    let entry = SearchEntry {
+        profile: args.profile.clone(),
+        ..search.entry(Some(url))
+    };
+    database::sqlite::insert_search(entry).map_err(|e| e.to_string().into())
 }
 
 /// Commands for interacting with search history.
 #[derive(Debug, Subcommand)]
 pub(crate) enum HistoryCommands {
+    /// List searches, newest first.
     List {
-        /// End date to filter searches.
-        #[arg(short = None, long)]
-        to: Option<String>,
-        /// Start date to filter searches.
-        #[arg(short = None, long)]
-        from: Option<String>,
+        #[command(flatten)]
+        range: RangeArgs,
+        #[command(flatten)]
+        page: PageArgs,
     },
+    /// Delete searches.
+    ///
+    /// The number of matching searches is shown before anything is deleted.
+    /// Without filters, all searches are deleted.
     Clear {
-        /// End date to filter searches.
-        #[arg(short = None, long)]
-        to: Option<String>,
-        /// Start date to filter searches.
-        #[arg(short = None, long)]
-        from: Option<String>,
-        /// Site to filter searches.
-        #[arg(short = None, long)]
-        site: Option<String>,
+        #[command(flatten)]
+        range: RangeArgs,
+        /// Delete without asking for confirmation.
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
     /// If no arguments are provided, all searches are returned.
     ///
-    /// If a query is provided, searches with that query are returned.
-    ///
-    /// If a site is provided, searches with that site are returned.
-    ///
-    /// If allintext is provided, searches with that text are returned.
-    Search {
-        /// Search query.
-        #[arg(short = 'q', long)]
-        query: Option<String>,
-        /// Site to restrict search to.
-        #[arg(short = 's', long)]
-        site: Option<String>,
-        /// Search for text in the page.
-        #[arg(short = 'a', long)]
-        allintext: Option<String>,
+    /// Otherwise, only searches matching every filter are returned. Filters
+    /// match any part of the stored value.
+    Search(Box<HistorySearchArgs>),
+    /// Run a past search again.
+    Open {
+        /// ID of the search.
+        id: i32,
+    },
+    /// Show the most searched queries and sites and when searches are made.
+    Stats(StatsArgs),
+    /// Mark a search as a favorite.
+    Star {
+        /// ID of the search.
+        id: i32,
+        /// Remove the mark instead.
+        #[arg(short = 'r', long)]
+        remove: bool,
     },
 }
 
+/// Filters of `web history search`.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct HistorySearchArgs {
+    #[command(flatten)]
+    range: RangeArgs,
+    #[command(flatten)]
+    page: PageArgs,
+    /// Search for text in the page.
+    #[arg(short = 'a', long)]
+    allintext: Option<String>,
+    /// Exact phrase.
+    #[arg(short = None, long)]
+    exact: Option<String>,
+    /// Excluded term.
+    #[arg(short = None, long)]
+    exclude: Option<String>,
+    /// File type.
+    #[arg(short = None, long)]
+    filetype: Option<String>,
+    /// Text in the result titles.
+    #[arg(short = None, long)]
+    intitle: Option<String>,
+    /// Text in the result URLs.
+    #[arg(short = None, long)]
+    inurl: Option<String>,
+    /// Term of an OR group.
+    #[arg(short = None, long)]
+    any_of: Option<String>,
+    /// Only include searches for results published after this date or
+    /// a later one. Unlike `--from`, this is the date the search was
+    /// restricted to, not when it was made.
+    #[arg(short = None, long, value_parser = parse_after)]
+    after: Option<NaiveDate>,
+    /// Only include searches for results published before this date or
+    /// an earlier one.
+    #[arg(short = None, long, value_parser = parse_before)]
+    before: Option<NaiveDate>,
+    /// Search profile.
+    #[arg(short = 'p', long)]
+    profile: Option<String>,
+    /// Only show starred searches.
+    #[arg(short = None, long)]
+    starred: bool,
+}
+
+/// Filters shared by the history commands.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct RangeArgs {
+    /// Only include searches made at or after this time, such as
+    /// `2025-01-31`, `yesterday` or `7d`.
+    #[arg(short = None, long, value_parser = dates::parse_from)]
+    from: Option<NaiveDateTime>,
+    /// Only include searches made at or before this time. Dates include the
+    /// whole day.
+    #[arg(short = None, long, value_parser = dates::parse_to)]
+    to: Option<NaiveDateTime>,
+    /// Only include searches whose query contains this text.
+    #[arg(short = 'q', long)]
+    query: Option<String>,
+    /// Only include searches restricted to a site containing this text.
+    #[arg(short = 's', long)]
+    site: Option<String>,
+}
+
+impl From<&RangeArgs> for SearchFilter {
+    fn from(range: &RangeArgs) -> Self {
+        SearchFilter {
+            query: range.query.clone(),
+            site: range.site.clone(),
+            from: range.from,
+            to: range.to,
+            ..SearchFilter::default()
+        }
+    }
+}
+
+/// Pagination of the history commands.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct PageArgs {
+    /// Maximum number of searches to show.
+    #[arg(short = 'n', long)]
+    limit: Option<i64>,
+    /// Number of searches to skip.
+    #[arg(short = None, long)]
+    offset: Option<i64>,
+}
+
 impl CommandHandler for HistoryCommands {
     fn handle(&self) -> crate::Result<()> {
         match self {
-            HistoryCommands::List { to, from } => history_list(to.clone(), from.clone()),
-            HistoryCommands::Clear { to, from, site } => {
-                history_clear(to.clone(), from.clone(), site.clone())
+            HistoryCommands::List { range, page } => history_search(&SearchFilter {
+                limit: page.limit,
+                offset: page.offset,
+                ..SearchFilter::from(range)
+            }),
+            HistoryCommands::Clear { range, yes } => history_clear(&range.into(), *yes),
+            HistoryCommands::Search(args) => history_search(&SearchFilter {
+                allintext: args.allintext.clone(),
+                exact: args.exact.clone(),
+                exclude: args.exclude.clone(),
+                filetype: args.filetype.clone(),
+                intitle: args.intitle.clone(),
+                inurl: args.inurl.clone(),
+                any_of: args.any_of.clone(),
+                after: args.after,
+                before: args.before,
+                profile: args.profile.clone(),
+                starred: args.starred,
+                limit: args.page.limit,
+                offset: args.page.offset,
+                ..SearchFilter::from(&args.range)
+            }),
+            HistoryCommands::Open { id } => history_open(*id),
+            HistoryCommands::Stats(args) => args.handle(),
+            HistoryCommands::Star { id, remove } => {
+                database::sqlite::star_search(*id, !remove).map_err(|e| e.to_string())?;
+                if *remove {
+                    println!("Unstarred search {}", id);
+                } else {
+                    println!("Starred search {}", id);
+                }
+                Ok(())
             }
-            HistoryCommands::Search {
-                query,
-                site,
-                allintext,
-            } => history_search(query.clone(), site.clone(), allintext.clone()),
         }
     }
 }
 
-/// List search history.
-fn history_list(to: Option<String>, from: Option<String>) -> crate::Result<()> {
-    let res = database::sqlite::get_search_range(from.unwrap_or_default(), to.unwrap_or_default());
-    if res.is_err() {
-        return Err(res.err().unwrap());
-    }
-    for search in res.unwrap() {
-        println!("{}", search);
+/// Clear search history.
+///
+/// Asks for confirmation unless `yes` is set. Without a terminal to ask on,
+/// nothing is deleted.
+fn history_clear(filter: &SearchFilter, yes: bool) -> crate::Result<()> {
+    let count = database::sqlite::count_searches(filter).map_err(|e| e.to_string())?;
+    if count == 0 {
+        println!("No searches match.");
+        return Ok(());
     }
-    Ok(())
-}
+    println!(
+        "{} {} will be deleted.",
+        count.to_string().red(),
+        if count == 1 { "search" } else { "searches" }
+    );
 
-/// Clear search history.
-fn history_clear(
-    to: Option<String>,
-    from: Option<String>,
-    _site: Option<String>,
-) -> crate::Result<()> {
-    let res =
-        database::sqlite::delete_search_range(from.unwrap_or_default(), to.unwrap_or_default());
-    if res.is_err() {
-        return Err(res.err().unwrap());
+    if !yes {
+        if !stdin().is_terminal() {
+            return Err("Pass --yes to delete without confirmation.".into());
+        }
+        print!("Continue? (y/n) ");
+        stdout().flush()?;
+        let mut answer = String::new();
+        stdin().read_line(&mut answer)?;
+        if answer.trim().to_lowercase() != "y" {
+            println!("Nothing was deleted.");
+            return Ok(());
+        }
     }
+
+    let deleted = database::sqlite::delete_searches(filter).map_err(|e| e.to_string())?;
+    println!("Deleted {} searches.", deleted);
     Ok(())
 }
 
 /// Search history.
 ///
 /// # Arguments
-/// query: The search query to search for.
-/// site: The site to filter searches by.
-/// allintext: The required text to filter searches by.
-fn history_search(
-    query: Option<String>,
-    site: Option<String>,
-    allintext: Option<String>,
-) -> crate::Result<()> {
-    let res = database::sqlite::get_search_by(query, site, allintext);
+/// filter: The filters that the searches must match.
+fn history_search(filter: &SearchFilter) -> crate::Result<()> {
+    let res = database::sqlite::get_search_by(filter);
 
     match res {
         Ok(searches) => {
             for search in searches {
-                println!("{}", search);
+                print_search(&search);
             }
             Ok(())
         }
         Err(e) => Err(e),
     }
 }
+
+/// Print a search history item with its operators and query string.
+fn print_search(search: &SearchEntry) {
+    let width = layout::terminal_width();
+    if search.starred {
+        println!("ID: {} ★", search.id);
+    } else {
+        println!("ID: {}", search.id);
+    }
+    println!("{}", layout::labeled("Query: ", &search.query, width));
+    let operators = [
+        ("Website: ", search.website.clone()),
+        ("All in text: ", search.allintext.clone()),
+        ("Exact: ", search.exact.clone()),
+        ("Exclude: ", search.exclude.clone()),
+        ("File type: ", search.filetype.clone()),
+        ("In title: ", search.intitle.clone()),
+        ("In URL: ", search.inurl.clone()),
+        ("After: ", search.after.map(|d| d.to_string())),
+        ("Before: ", search.before.map(|d| d.to_string())),
+        ("Any of: ", search.any_of.clone()),
+        ("Profile: ", search.profile.clone()),
+    ];
+    for (label, value) in operators {
+        if let Some(value) = value {
+            println!("{}", layout::labeled(label, &value, width));
+        }
+    }
+    println!("Time: {}", search.time_stamp);
+    let query_string = SearchQuery::from(search).to_string();
+    println!(
+        "{}",
+        layout::labeled("Query String: ", &query_string, width)
+    );
+    if let Some(url) = &search.url {
+        println!("{}", layout::labeled("URL: ", url, width));
+    }
+    println!();
+}
+
+/// Run a past search again and record it in the search history.
+///
+/// The URL that was opened for the search is reused. Searches recorded
+/// before URLs were stored are rebuilt for the default engine.
+fn history_open(id: i32) -> crate::Result<()> {
+    let entry =
+        database::sqlite::get_search(id).map_err(|_| format!("No search found with ID: {}", id))?;
+
+    let url = match &entry.url {
+        Some(url) => url.clone(),
+        None => {
+            let engine = ENV.lock().unwrap().search_engine;
+            let defaults = LocaleArgs::default();
+            //  The profile may have been removed since.
+            let locale = resolve_locale(entry.profile.as_deref(), &defaults)
+                .or_else(|_| resolve_locale(None, &defaults))?;
+            let query_string = SearchQuery::from(&entry).build(engine);
+            Url::from(engine.params(&query_string, &locale)).to_string()
+        }
+    };
+    open_url(&url)?;
+
+    let entry = SearchEntry {
+        url: Some(url),
+        starred: false,
+        ..entry
+    };
+    database::sqlite::insert_search(entry).map_err(|e| e.to_string().into())
+}
@@ -0,0 +1,123 @@
+use clap::{Args, Subcommand};
+
+use crate::commands::environment::core::{SafeSearch, SearchLocale, SearchProfile};
+use crate::{Colorize, ENV};
+
+use crate::commands::CommandHandler;
+
+/// Manage named sets of search settings such as `work` or `de`.
+#[derive(Debug, Subcommand)]
+pub(crate) enum ProfileCommands {
+    /// List the search profiles.
+    List,
+    /// Add a profile or replace an existing one.
+    Add {
+        /// Name of the profile.
+        name: String,
+        #[command(flatten)]
+        locale: LocaleArgs,
+    },
+    /// Remove a profile.
+    Remove {
+        /// Name of the profile.
+        name: String,
+    },
+}
+
+/// Locale settings of a search. Unset settings keep their default.
+#[derive(Debug, Clone, Default, Args)]
+pub(crate) struct LocaleArgs {
+    /// Language code, such as `en` or `de`.
+    #[arg(short = None, long)]
+    pub lang: Option<String>,
+    /// Country code, such as `us` or `de`.
+    #[arg(short = None, long)]
+    pub country: Option<String>,
+    /// Safe search filtering.
+    #[arg(short = None, long, value_enum)]
+    pub safe: Option<SafeSearch>,
+    /// Number of results per page.
+    #[arg(short = None, long, value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub num: Option<u8>,
+}
+
+impl From<&LocaleArgs> for SearchProfile {
+    fn from(args: &LocaleArgs) -> Self {
+        SearchProfile {
+            lang: args.lang.clone(),
+            country: args.country.clone(),
+            safe: args.safe,
+            num: args.num,
+        }
+    }
+}
+
+impl CommandHandler for ProfileCommands {
+    fn handle(&self) -> crate::Result<()> {
+        match self {
+            ProfileCommands::List => {
+                let env = ENV.lock().unwrap();
+                if env.search_profiles.is_empty() {
+                    println!("No search profiles.");
+                    return Ok(());
+                }
+                let width = env
+                    .search_profiles
+                    .keys()
+                    .map(|p| p.len())
+                    .max()
+                    .unwrap_or(0);
+                for (name, profile) in &env.search_profiles {
+                    println!("{}  {}", format!("{:<width$}", name).cyan(), profile);
+                }
+                Ok(())
+            }
+            ProfileCommands::Add { name, locale } => {
+                let name = name.trim().to_string();
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err("A profile name must be a single word.".into());
+                }
+                let profile = SearchProfile::from(locale);
+                if profile == SearchProfile::default() {
+                    return Err(
+                        "A profile needs at least one of --lang, --country, --safe or --num."
+                            .into(),
+                    );
+                }
+                let env = &mut ENV.lock().unwrap();
+                println!("Added {}: {}", name.cyan(), profile);
+                env.search_profiles.insert(name, profile);
+                env.save();
+                Ok(())
+            }
+            ProfileCommands::Remove { name } => {
+                let env = &mut ENV.lock().unwrap();
+                if env.search_profiles.remove(name).is_none() {
+                    return Err(format!("No search profile found with name: {}", name).into());
+                }
+                env.save();
+                println!("Removed {}", name.cyan());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Resolve the locale of a search.
+///
+/// The environment settings are overridden by the profile, which is in turn
+/// overridden by `overrides`.
+pub fn resolve_locale(
+    profile: Option<&str>,
+    overrides: &LocaleArgs,
+) -> crate::Result<SearchLocale> {
+    let env = ENV.lock().unwrap();
+    let mut locale = env.search_locale();
+    if let Some(name) = profile {
+        match env.search_profiles.get(name) {
+            Some(profile) => locale = profile.apply(locale),
+            None => return Err(format!("No search profile found with name: {}", name).into()),
+        }
+    }
+    Ok(SearchProfile::from(overrides).apply(locale))
+}
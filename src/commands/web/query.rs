@@ -0,0 +1,368 @@
+use chrono::NaiveDate;
+
+use crate::commands::environment::core::SearchEngine;
+use crate::database::SearchEntry;
+
+/// A search query and its operators.
+///
+/// The query is engine independent. Use [`SearchQuery::build`] to get the
+/// query string for a specific engine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Plain search terms.
+    pub terms: String,
+    /// A phrase the results must contain exactly.
+    pub exact: Option<String>,
+    /// Terms the results must not contain.
+    pub exclude: Vec<String>,
+    /// Sites to restrict the search to. Any of them may match.
+    pub sites: Vec<String>,
+    /// File type of the results, such as `pdf`.
+    pub filetype: Option<String>,
+    /// Text the result titles must contain.
+    pub intitle: Option<String>,
+    /// Text the result URLs must contain.
+    pub inurl: Option<String>,
+    /// Text the result pages must contain.
+    pub allintext: Option<String>,
+    /// Results must be published after this date.
+    pub after: Option<NaiveDate>,
+    /// Results must be published before this date.
+    pub before: Option<NaiveDate>,
+    /// Terms of which the results must contain at least one.
+    pub any_of: Vec<String>,
+}
+
+impl SearchQuery {
+    /// Start a query from plain search terms.
+    pub fn new(terms: &str) -> Self {
+        Self {
+            terms: terms.trim().to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn exact(mut self, phrase: Option<String>) -> Self {
+        self.exact = non_empty(phrase);
+        self
+    }
+
+    pub fn exclude(mut self, terms: &[String]) -> Self {
+        self.exclude.extend(non_empty_all(terms));
+        self
+    }
+
+    pub fn sites(mut self, sites: &[String]) -> Self {
+        self.sites.extend(non_empty_all(sites));
+        self
+    }
+
+    /// Leading dots are removed, so `.pdf` and `pdf` are the same.
+    pub fn filetype(mut self, filetype: Option<String>) -> Self {
+        self.filetype = non_empty(filetype.map(|f| f.trim_start_matches('.').to_string()));
+        self
+    }
+
+    pub fn intitle(mut self, text: Option<String>) -> Self {
+        self.intitle = non_empty(text);
+        self
+    }
+
+    pub fn inurl(mut self, text: Option<String>) -> Self {
+        self.inurl = non_empty(text);
+        self
+    }
+
+    pub fn allintext(mut self, text: Option<String>) -> Self {
+        self.allintext = non_empty(text);
+        self
+    }
+
+    pub fn after(mut self, date: Option<NaiveDate>) -> Self {
+        self.after = date;
+        self
+    }
+
+    pub fn before(mut self, date: Option<NaiveDate>) -> Self {
+        self.before = date;
+        self
+    }
+
+    pub fn any_of(mut self, terms: &[String]) -> Self {
+        self.any_of.extend(non_empty_all(terms));
+        self
+    }
+
+    /// Build the query string for `engine`.
+    pub fn build(&self, engine: SearchEngine) -> String {
+        engine.query_string(self)
+    }
+
+    /// Create a search history item for the query.
+    pub fn entry(&self, url: Option<String>) -> SearchEntry {
+        SearchEntry {
+            id: 0,
+            query: self.terms.clone(),
+            website: join_list(&self.sites),
+            allintext: self.allintext.clone(),
+            time_stamp: crate::commands::time_now(),
+            url,
+            exact: self.exact.clone(),
+            exclude: join_list(&self.exclude),
+            filetype: self.filetype.clone(),
+            intitle: self.intitle.clone(),
+            inurl: self.inurl.clone(),
+            after: self.after,
+            before: self.before,
+            any_of: join_list(&self.any_of),
+            profile: None,
+            starred: false,
+        }
+    }
+}
+
+impl std::fmt::Display for SearchQuery {
+    /// Formats the query with Google's syntax.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.build(SearchEngine::Google))
+    }
+}
+
+impl From<&SearchEntry> for SearchQuery {
+    fn from(entry: &SearchEntry) -> Self {
+        SearchQuery::new(&entry.query)
+            .sites(&split_list(&entry.website))
+            .allintext(entry.allintext.clone())
+            .exact(entry.exact.clone())
+            .exclude(&split_list(&entry.exclude))
+            .filetype(entry.filetype.clone())
+            .intitle(entry.intitle.clone())
+            .inurl(entry.inurl.clone())
+            .after(entry.after)
+            .before(entry.before)
+            .any_of(&split_list(&entry.any_of))
+    }
+}
+
+/// Join a list of terms for storage. Empty lists are stored as `None`.
+pub fn join_list(items: &[String]) -> Option<String> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items.join(","))
+    }
+}
+
+/// Split a list of terms that was stored with [`join_list`].
+pub fn split_list(items: &Option<String>) -> Vec<String> {
+    items
+        .as_deref()
+        .map(|s| s.split(',').map(|i| i.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn non_empty_all(values: &[String]) -> impl Iterator<Item = String> + '_ {
+    values
+        .iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOOGLE: SearchEngine = SearchEngine::Google;
+    const BING: SearchEngine = SearchEngine::Bing;
+    const DUCKDUCKGO: SearchEngine = SearchEngine::DuckDuckGo;
+    const GITHUB: SearchEngine = SearchEngine::GitHub;
+    const DOCSRS: SearchEngine = SearchEngine::DocsRs;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn date(text: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+    }
+
+    #[test]
+    fn plain_terms() {
+        let query = SearchQuery::new("  rust  ");
+        for engine in [GOOGLE, BING, DUCKDUCKGO, GITHUB, DOCSRS] {
+            assert_eq!(query.build(engine), "rust");
+        }
+    }
+
+    #[test]
+    fn exact() {
+        let query = SearchQuery::new("rust").exact(Some("error handling".to_string()));
+        for engine in [GOOGLE, BING, DUCKDUCKGO, GITHUB, DOCSRS] {
+            assert_eq!(query.build(engine), "rust \"error handling\"");
+        }
+    }
+
+    #[test]
+    fn exclude() {
+        let query = SearchQuery::new("rust").exclude(&strings(&["java", "visual basic"]));
+        assert_eq!(query.build(GOOGLE), "rust -java -\"visual basic\"");
+        assert_eq!(query.build(BING), "rust -java -\"visual basic\"");
+        assert_eq!(query.build(DUCKDUCKGO), "rust -java -\"visual basic\"");
+        assert_eq!(query.build(GITHUB), "rust NOT java NOT \"visual basic\"");
+        assert_eq!(query.build(DOCSRS), "rust");
+    }
+
+    #[test]
+    fn filetype() {
+        let query = SearchQuery::new("rust").filetype(Some(".pdf".to_string()));
+        assert_eq!(query.filetype.as_deref(), Some("pdf"));
+        assert_eq!(query.build(GOOGLE), "rust filetype:pdf");
+        assert_eq!(query.build(BING), "rust filetype:pdf");
+        assert_eq!(query.build(DUCKDUCKGO), "rust filetype:pdf");
+        assert_eq!(query.build(GITHUB), "rust path:*.pdf");
+        assert_eq!(query.build(DOCSRS), "rust pdf");
+    }
+
+    #[test]
+    fn intitle() {
+        let query = SearchQuery::new("rust").intitle(Some("getting started".to_string()));
+        assert_eq!(query.build(GOOGLE), "rust intitle:\"getting started\"");
+        assert_eq!(query.build(BING), "rust intitle:\"getting started\"");
+        assert_eq!(query.build(DUCKDUCKGO), "rust intitle:\"getting started\"");
+        assert_eq!(query.build(GITHUB), "rust \"getting started\"");
+        assert_eq!(query.build(DOCSRS), "rust \"getting started\"");
+    }
+
+    #[test]
+    fn inurl() {
+        let query = SearchQuery::new("rust").inurl(Some("docs".to_string()));
+        assert_eq!(query.build(GOOGLE), "rust inurl:docs");
+        assert_eq!(query.build(BING), "rust inurl:docs");
+        assert_eq!(query.build(DUCKDUCKGO), "rust inurl:docs");
+        assert_eq!(query.build(GITHUB), "rust path:docs");
+        assert_eq!(query.build(DOCSRS), "rust docs");
+
+        let words = SearchQuery::new("rust").inurl(Some("release notes".to_string()));
+        assert_eq!(words.build(GOOGLE), "rust inurl:\"release notes\"");
+        assert_eq!(words.build(GITHUB), "rust path:\"release notes\"");
+    }
+
+    #[test]
+    fn allintext() {
+        let query = SearchQuery::new("rust").allintext(Some("borrow".to_string()));
+        assert_eq!(query.build(GOOGLE), "rust allintext:borrow");
+        assert_eq!(query.build(BING), "rust inbody:borrow");
+        assert_eq!(query.build(DUCKDUCKGO), "rust borrow");
+        assert_eq!(query.build(GITHUB), "rust borrow");
+        assert_eq!(query.build(DOCSRS), "rust borrow");
+
+        let words = SearchQuery::new("rust").allintext(Some("borrow checker".to_string()));
+        assert_eq!(words.build(GOOGLE), "rust allintext:\"borrow checker\"");
+        assert_eq!(words.build(BING), "rust inbody:\"borrow checker\"");
+        assert_eq!(words.build(DUCKDUCKGO), "rust \"borrow checker\"");
+    }
+
+    #[test]
+    fn after_and_before() {
+        let after = SearchQuery::new("rust").after(date("2024-01-01"));
+        assert_eq!(after.build(GOOGLE), "rust after:2024-01-01");
+
+        let before = SearchQuery::new("rust").before(date("2024-06-30"));
+        assert_eq!(before.build(GOOGLE), "rust before:2024-06-30");
+
+        //  Other engines do not support dates.
+        let both = after.before(date("2024-06-30"));
+        assert_eq!(
+            both.build(GOOGLE),
+            "rust after:2024-01-01 before:2024-06-30"
+        );
+        for engine in [BING, DUCKDUCKGO, GITHUB, DOCSRS] {
+            assert_eq!(both.build(engine), "rust");
+        }
+    }
+
+    #[test]
+    fn any_of() {
+        let one = SearchQuery::new("rust").any_of(&strings(&["tokio"]));
+        assert_eq!(one.build(GOOGLE), "rust tokio");
+
+        let query = SearchQuery::new("rust").any_of(&strings(&["tokio", "async std"]));
+        assert_eq!(query.build(GOOGLE), "rust (tokio OR \"async std\")");
+        assert_eq!(query.build(BING), "rust (tokio OR \"async std\")");
+        assert_eq!(query.build(DUCKDUCKGO), "rust (tokio OR \"async std\")");
+        assert_eq!(query.build(GITHUB), "rust (tokio OR \"async std\")");
+        assert_eq!(query.build(DOCSRS), "rust tokio \"async std\"");
+    }
+
+    #[test]
+    fn sites() {
+        let one = SearchQuery::new("rust").sites(&strings(&["doc.rust-lang.org"]));
+        assert_eq!(one.build(GOOGLE), "rust site:doc.rust-lang.org");
+
+        let query = SearchQuery::new("rust").sites(&strings(&[
+            "doc.rust-lang.org",
+            " ",
+            "https://github.com/rust-lang/rust/",
+        ]));
+        assert_eq!(query.sites.len(), 2);
+        assert_eq!(
+            query.build(GOOGLE),
+            "rust (site:doc.rust-lang.org OR site:https://github.com/rust-lang/rust/)"
+        );
+        assert_eq!(
+            query.build(DUCKDUCKGO),
+            "rust (site:doc.rust-lang.org OR site:https://github.com/rust-lang/rust/)"
+        );
+        //  Only repos can be searched on GitHub.
+        assert_eq!(query.build(GITHUB), "rust repo:rust-lang/rust");
+        assert_eq!(query.build(DOCSRS), "rust");
+    }
+
+    #[test]
+    fn every_operator() {
+        let query = SearchQuery::new("rust")
+            .exact(Some("error handling".to_string()))
+            .exclude(&strings(&["java"]))
+            .sites(&strings(&["a.com", "b.org"]))
+            .filetype(Some("pdf".to_string()))
+            .intitle(Some("guide".to_string()))
+            .inurl(Some("docs".to_string()))
+            .allintext(Some("result".to_string()))
+            .after(date("2024-01-01"))
+            .before(date("2024-06-30"))
+            .any_of(&strings(&["anyhow", "thiserror"]));
+        assert_eq!(
+            query.build(GOOGLE),
+            "rust \"error handling\" (site:a.com OR site:b.org) -java filetype:pdf \
+             intitle:guide inurl:docs allintext:result after:2024-01-01 before:2024-06-30 \
+             (anyhow OR thiserror)"
+        );
+        assert_eq!(query.to_string(), query.build(GOOGLE));
+    }
+
+    #[test]
+    fn empty_operators_are_left_out() {
+        let query = SearchQuery::new("rust")
+            .exact(Some("  ".to_string()))
+            .exclude(&strings(&[""]))
+            .intitle(None)
+            .any_of(&strings(&[" "]));
+        assert_eq!(query, SearchQuery::new("rust"));
+    }
+
+    #[test]
+    fn history_entries_keep_operators() {
+        let query = SearchQuery::new("rust")
+            .exclude(&strings(&["java", "go"]))
+            .sites(&strings(&["a.com"]))
+            .after(date("2024-01-01"))
+            .any_of(&strings(&["tokio", "smol"]));
+        assert_eq!(SearchQuery::from(&query.entry(None)), query);
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{IsTerminal, Write, stdin, stdout},
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::http::{self, HttpError};
+use crate::{Colorize, layout};
+
+use super::core::{SearchParams, open_url};
+
+/// A single result from the Custom Search JSON API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub title: String,
+    pub link: String,
+    #[serde(default)]
+    pub snippet: String,
+    #[serde(default)]
+    pub display_link: String,
+}
+
+/// A response from the Custom Search JSON API.
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    /// Missing when the search has no results.
+    #[serde(default)]
+    items: Vec<SearchResult>,
+    error: Option<ApiError>,
+}
+
+/// An error returned by the Custom Search JSON API.
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: u16,
+    message: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Search API error {}: {}", self.code, self.message)
+    }
+}
+
+/// Fetch the results of a search from the Custom Search JSON API.
+///
+/// Responses are cached for `search_cache_ttl` seconds unless `no_cache` is
+/// set.
+pub fn fetch_results(options: SearchParams, no_cache: bool) -> crate::Result<Vec<SearchResult>> {
+    let ttl = crate::ENV.lock().unwrap().search_cache_ttl;
+    let cache_path = cache_path(&options);
+    let url: Url = options.into();
+
+    if !no_cache
+        && ttl > 0
+        && let Some(body) = read_cache(&cache_path, ttl)
+    {
+        return parse_response(&body);
+    }
+
+    let body = match http::get_text(url.as_str()) {
+        Ok(body) => body,
+        Err(e) => {
+            //  The API explains its errors in the body.
+            if let HttpError::Status { body, .. } = &e
+                && let Ok(SearchResponse {
+                    error: Some(error), ..
+                }) = serde_json::from_str(body)
+            {
+                return Err(error.to_string().into());
+            }
+            return Err(e.into());
+        }
+    };
+    let results = parse_response(&body)?;
+    if ttl > 0 {
+        //  Failing to cache should not fail the search.
+        let _ = write_cache(&cache_path, &body);
+    }
+    Ok(results)
+}
+
+/// Deserialize a response body, turning API errors into errors.
+fn parse_response(body: &str) -> crate::Result<Vec<SearchResult>> {
+    let response: SearchResponse = serde_json::from_str(body)?;
+    match response.error {
+        Some(e) => Err(e.to_string().into()),
+        None => Ok(response.items),
+    }
+}
+
+/// The cache file of a search.
+///
+/// The file name is a hash of the search URL and parameters. The API key is
+/// left out so that it is never written to disk.
+fn cache_path(options: &SearchParams) -> PathBuf {
+    let mut args: Vec<(&String, &String)> =
+        options.args.iter().filter(|(k, _)| *k != "key").collect();
+    args.sort();
+
+    let mut hasher = DefaultHasher::new();
+    options.url.hash(&mut hasher);
+    args.hash(&mut hasher);
+    crate::CACHE_DIR
+        .join("search")
+        .join(format!("{:016x}.json", hasher.finish()))
+}
+
+/// Read a cached response if it is younger than `ttl` seconds.
+fn read_cache(path: &PathBuf, ttl: u64) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now().duration_since(modified).ok()?;
+    if age > Duration::from_secs(ttl) {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+fn write_cache(path: &PathBuf, body: &str) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, body)?;
+    Ok(())
+}
+
+/// Print search results as a numbered list.
+pub fn print_results(results: &[SearchResult]) {
+    if results.is_empty() {
+        println!("No results found.");
+        return;
+    }
+    let width = layout::text_width();
+    for (i, result) in results.iter().enumerate() {
+        let number = format!("{:>2}. ", i + 1);
+        let indent = " ".repeat(number.len());
+        println!("{}{}", number, result.title.cyan_bright());
+        println!("{}{}", indent, result.display_link.green());
+        if !result.snippet.is_empty() {
+            println!(
+                "{}",
+                layout::wrap_str(&result.snippet, width, &indent, &indent)
+            );
+        }
+        println!();
+    }
+}
+
+/// Open result `n`, counting from 1.
+pub fn open_result(results: &[SearchResult], n: usize) -> crate::Result<()> {
+    match n.checked_sub(1).and_then(|i| results.get(i)) {
+        Some(result) => open_url(&result.link),
+        None => Err(format!("No result {}. Pick from 1 to {}.", n, results.len()).into()),
+    }
+}
+
+/// Ask which result to open.
+///
+/// Nothing is asked when stdin is not a terminal, so the output can be piped.
+pub fn prompt_open(results: &[SearchResult]) -> crate::Result<()> {
+    if results.is_empty() || !stdin().is_terminal() {
+        return Ok(());
+    }
+    print!("Open result (1-{}, enter to skip): ", results.len());
+    stdout().flush()?;
+    let mut line = String::new();
+    stdin().read_line(&mut line)?;
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(());
+    }
+    match line.parse::<usize>() {
+        Ok(n) => open_result(results, n),
+        Err(_) => Err(format!("Not a result number: {}", line).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{StubServer, TestEnv, response};
+
+    const RESULTS: &str = r#"{
+        "kind": "customsearch#search",
+        "items": [
+            {
+                "title": "The Rust Programming Language",
+                "link": "https://www.rust-lang.org/",
+                "snippet": "A language empowering everyone.",
+                "displayLink": "www.rust-lang.org"
+            },
+            { "title": "Rust (video game)", "link": "https://rust.facepunch.com/" }
+        ]
+    }"#;
+
+    const API_ERROR: &str = r#"{
+        "error": { "code": 400, "message": "API key not valid. Please pass a valid API key.", "status": "INVALID_ARGUMENT" }
+    }"#;
+
+    fn params(query: &str, key: &str) -> SearchParams {
+        SearchParams {
+            url: "https://www.googleapis.com/customsearch/v1".to_string(),
+            args: [("q", query), ("key", key), ("cx", "engine")]
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_response_reads_items() {
+        let results = parse_response(RESULTS).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "The Rust Programming Language");
+        assert_eq!(results[0].link, "https://www.rust-lang.org/");
+        assert_eq!(results[0].snippet, "A language empowering everyone.");
+        assert_eq!(results[0].display_link, "www.rust-lang.org");
+        assert_eq!(results[1].snippet, "");
+        assert_eq!(results[1].display_link, "");
+    }
+
+    #[test]
+    fn parse_response_without_items_is_empty() {
+        assert!(
+            parse_response(r#"{ "kind": "customsearch#search" }"#)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parse_response_reports_api_errors() {
+        let error = parse_response(API_ERROR).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Search API error 400: API key not valid. Please pass a valid API key."
+        );
+    }
+
+    #[test]
+    fn cache_path_leaves_out_the_api_key() {
+        assert_eq!(
+            cache_path(&params("rust", "first key")),
+            cache_path(&params("rust", "second key"))
+        );
+        assert_ne!(
+            cache_path(&params("rust", "key")),
+            cache_path(&params("zig", "key"))
+        );
+        let path = cache_path(&params("rust", "secret key"));
+        assert!(!path.to_string_lossy().contains("secret"));
+    }
+
+    #[test]
+    fn fetch_results_from_a_stub_server() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![response("200 OK", &[], RESULTS)]);
+        let locale = {
+            let mut env = crate::ENV.lock().unwrap();
+            //  Keep the responses out of the real cache.
+            env.search_cache_ttl = 0;
+            env.google_search_api_url = format!("{}customsearch/v1", server.url);
+            env.google_search_api_key = "secret".to_string();
+            env.search_locale()
+        };
+
+        let results = fetch_results(SearchParams::new_json("rust lang", &locale), true).unwrap();
+
+        assert_eq!(results.len(), 2);
+        let request = &server.requests()[0];
+        assert!(request.starts_with("GET /customsearch/v1?"));
+        assert!(request.contains("q=rust+lang"));
+        assert!(request.contains("key=secret"));
+    }
+
+    #[test]
+    fn fetch_results_reports_api_errors() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![response("400 Bad Request", &[], API_ERROR)]);
+        let locale = {
+            let mut env = crate::ENV.lock().unwrap();
+            //  Keep the responses out of the real cache.
+            env.search_cache_ttl = 0;
+            env.google_search_api_url = server.url.clone();
+            env.search_locale()
+        };
+
+        let error = fetch_results(SearchParams::new_json("rust", &locale), true).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Search API error 400: API key not valid. Please pass a valid API key."
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::io::{IsTerminal, Write, stdout};
+use std::process::{Command, Stdio};
+
+use clap::Args;
+
+use crate::commands::CommandHandler;
+use crate::database::{self, SnapshotInfo};
+use crate::{Colorize, http, layout};
+
+use super::html::{extract_text, html_title};
+
+/// Pager used when `PAGER` is not set.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Options for `web save`.
+#[derive(Debug, Args)]
+pub(crate) struct SaveArgs {
+    /// URL of the page to save.
+    url: String,
+    /// Title of the page. Defaults to the page's own title.
+    #[arg(short = None, long)]
+    title: Option<String>,
+}
+
+/// Options for `web read`.
+#[derive(Debug, Args)]
+pub(crate) struct ReadArgs {
+    /// ID of the saved page. Lists the saved pages if not given.
+    id: Option<i32>,
+    /// Print the page instead of opening it in the pager.
+    #[arg(short = None, long)]
+    no_pager: bool,
+    /// Delete the saved page.
+    #[arg(short = None, long, requires = "id", conflicts_with = "no_pager")]
+    delete: bool,
+}
+
+impl CommandHandler for SaveArgs {
+    fn handle(&self) -> crate::Result<()> {
+        let html = http::get_text(&self.url)?;
+
+        let text = extract_text(&html);
+        if text.is_empty() {
+            return Err(format!("No readable text found at {}", self.url).into());
+        }
+        let title = self.title.clone().or_else(|| html_title(&html));
+        let now = chrono::Local::now().naive_local();
+        let snapshot = database::sqlite::insert_snapshot(&self.url, title, &text, now)
+            .map_err(|e| e.to_string())?;
+
+        println!("Saved page {}", snapshot.id);
+        print_snapshot(&snapshot);
+        Ok(())
+    }
+}
+
+impl CommandHandler for ReadArgs {
+    fn handle(&self) -> crate::Result<()> {
+        let Some(id) = self.id else {
+            return list_snapshots();
+        };
+        if self.delete {
+            database::sqlite::delete_snapshot(id).map_err(|e| e.to_string())?;
+            println!("Deleted saved page {}", id);
+            return Ok(());
+        }
+
+        let snapshot = database::sqlite::get_snapshot(id).map_err(|e| e.to_string())?;
+        let width = layout::text_width();
+        let mut page = String::new();
+        if let Some(title) = &snapshot.title {
+            page.push_str(&format!("{}\n", title.cyan_bright()));
+        }
+        page.push_str(&format!(
+            "{}\nSaved {}\n\n",
+            snapshot.url.green(),
+            snapshot.time_stamp.format("%Y-%m-%d %H:%M")
+        ));
+        for line in snapshot.text()?.lines() {
+            page.push_str(&wrap_line(line, width));
+            page.push('\n');
+        }
+
+        if self.no_pager || !stdout().is_terminal() {
+            print!("{}", page);
+            return Ok(());
+        }
+        page_text(&page)
+    }
+}
+
+fn list_snapshots() -> crate::Result<()> {
+    let snapshots = database::sqlite::get_snapshots().map_err(|e| e.to_string())?;
+    if snapshots.is_empty() {
+        println!("No saved pages found.");
+        return Ok(());
+    }
+    for snapshot in &snapshots {
+        print_snapshot(snapshot);
+    }
+    Ok(())
+}
+
+fn print_snapshot(snapshot: &SnapshotInfo) {
+    let title = snapshot.title.as_deref().unwrap_or(&snapshot.url);
+    println!("({:02}) {}", snapshot.id, title.cyan_bright());
+    println!(
+        "     {} ({}, {})",
+        snapshot.url.green(),
+        format_size(snapshot.size),
+        snapshot.time_stamp.format("%Y-%m-%d %H:%M")
+    );
+}
+
+fn format_size(bytes: i32) -> String {
+    match bytes {
+        ..1024 => format!("{} B", bytes),
+        _ => format!("{:.1} KiB", bytes as f64 / 1024.0),
+    }
+}
+
+/// Wrap a line of a saved page to `width`.
+///
+/// Lines that fit are kept as they are, so that preformatted text keeps its
+/// spacing. Bullets get a hanging indent.
+fn wrap_line(line: &str, width: usize) -> String {
+    if layout::display_width(line) <= width {
+        return line.to_string();
+    }
+    let hanging = if line.starts_with("• ") { "  " } else { "" };
+    layout::wrap_str(line, width, "", hanging)
+}
+
+/// Show text in the pager named by `PAGER`, or print it if the pager cannot
+/// be started.
+fn page_text(text: &str) -> crate::Result<()> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_PAGER);
+
+    let child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn();
+    let Ok(mut child) = child else {
+        print!("{}", text);
+        return Ok(());
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        //  The pager closes its input when it quits before the end.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::sqlite::{FtsTable, find};
+    use crate::testing::{StubServer, TestEnv, response};
+
+    #[test]
+    fn save_a_page_from_a_server() {
+        let _env = TestEnv::new();
+        let filler = "Filler words for the page. ".repeat(200);
+        let page = format!(
+            "<html><head><title>Ownership &amp; Borrowing</title></head><body>
+               <nav>Menu</nav>
+               <article><p>Each value has an owner.</p><p>{}</p><p>Zebra</p></article>
+             </body></html>",
+            filler
+        );
+        let server = StubServer::new(vec![response("200 OK", &[], &page)]);
+        let url = format!("{}book/ownership.html", server.url);
+
+        let args = SaveArgs {
+            url: url.clone(),
+            title: None,
+        };
+        args.handle().unwrap();
+        assert!(server.requests()[0].starts_with("GET /book/ownership.html "));
+
+        let saved = database::sqlite::get_snapshots().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].url, url);
+        assert_eq!(saved[0].title.as_deref(), Some("Ownership & Borrowing"));
+
+        let snapshot = database::sqlite::get_snapshot(saved[0].id).unwrap();
+        let text = snapshot.text().unwrap();
+        assert_eq!(text, extract_text(&page));
+        assert!(text.starts_with("Each value has an owner."));
+        assert!(!text.contains("Menu"));
+        assert_eq!(snapshot.size as usize, text.len());
+
+        //  The title and the start of the text are indexed, the end is not.
+        assert_eq!(find(FtsTable::Snapshots, "borrowing", 10).unwrap().len(), 1);
+        assert_eq!(find(FtsTable::Snapshots, "owner", 10).unwrap().len(), 1);
+        assert!(find(FtsTable::Snapshots, "zebra", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn pages_without_text_are_not_saved() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![response(
+            "200 OK",
+            &[],
+            "<html><script>app()</script></html>",
+        )]);
+        let args = SaveArgs {
+            url: server.url.clone(),
+            title: None,
+        };
+        assert!(args.handle().is_err());
+        assert!(database::sqlite::get_snapshots().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use clap::Args;
+use serde::Serialize;
+
+use crate::commands::CommandHandler;
+use crate::database::{self, SearchEntry, sqlite::SearchFilter};
+use crate::{Colorize, dates, layout};
+
+use super::query::split_list;
+
+/// Characters of a sparkline, from lowest to highest.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Options for `web history stats`.
+#[derive(Debug, Args)]
+pub(crate) struct StatsArgs {
+    /// Only count searches made at or after this time, such as
+    /// `2025-01-31`, `yesterday` or `30d`.
+    #[arg(short = None, long, value_parser = dates::parse_from)]
+    from: Option<NaiveDateTime>,
+    /// Only count searches made at or before this time.
+    #[arg(short = None, long, value_parser = dates::parse_to)]
+    to: Option<NaiveDateTime>,
+    /// Number of top queries and sites to show.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    top: usize,
+    /// Print the statistics in JSON format.
+    #[arg(short = None, long)]
+    json: bool,
+}
+
+/// Statistics of the search history.
+#[derive(Debug, Serialize)]
+pub struct HistoryStats {
+    pub total: usize,
+    /// Day of the first search counted.
+    pub first: Option<String>,
+    /// Day of the last search counted.
+    pub last: Option<String>,
+    pub top_queries: Vec<Count>,
+    pub top_sites: Vec<Count>,
+    /// Searches per day from the first to the last day, including days
+    /// without searches.
+    pub per_day: Vec<Count>,
+    /// Searches per week, keyed by the Monday starting the week.
+    pub per_week: Vec<Count>,
+    /// Searches per hour of the day, from 0 to 23.
+    pub per_hour: Vec<usize>,
+}
+
+/// Number of searches of a query, site or period.
+#[derive(Debug, Clone, Serialize)]
+pub struct Count {
+    pub key: String,
+    pub count: usize,
+}
+
+impl CommandHandler for StatsArgs {
+    fn handle(&self) -> crate::Result<()> {
+        let filter = SearchFilter {
+            from: self.from,
+            to: self.to,
+            ..SearchFilter::default()
+        };
+        let searches = database::sqlite::get_search_by(&filter).map_err(|e| e.to_string())?;
+        let stats = HistoryStats::new(&searches, self.top);
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        } else {
+            print_stats(&stats);
+        }
+        Ok(())
+    }
+}
+
+impl HistoryStats {
+    /// Compute the statistics of `searches`, keeping the `top` most common
+    /// queries and sites.
+    pub fn new(searches: &[SearchEntry], top: usize) -> Self {
+        let mut queries: HashMap<String, usize> = HashMap::new();
+        let mut sites: HashMap<String, usize> = HashMap::new();
+        let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        let mut per_hour = vec![0; 24];
+
+        for search in searches {
+            let query = search.query.trim().to_lowercase();
+            if !query.is_empty() {
+                *queries.entry(query).or_default() += 1;
+            }
+            for site in split_list(&search.website) {
+                if !site.is_empty() {
+                    *sites.entry(site.to_lowercase()).or_default() += 1;
+                }
+            }
+            *days.entry(search.time_stamp.date()).or_default() += 1;
+            per_hour[search.time_stamp.hour() as usize] += 1;
+        }
+
+        let (per_day, per_week) = match (days.keys().next(), days.keys().last()) {
+            (Some(first), Some(last)) => {
+                let mut per_day = vec![];
+                let mut weeks: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+                for day in first.iter_days().take_while(|day| day <= last) {
+                    let count = days.get(&day).copied().unwrap_or(0);
+                    per_day.push(Count {
+                        key: day.to_string(),
+                        count,
+                    });
+                    *weeks.entry(week_start(day)).or_default() += count;
+                }
+                let per_week = weeks
+                    .into_iter()
+                    .map(|(week, count)| Count {
+                        key: week.to_string(),
+                        count,
+                    })
+                    .collect();
+                (per_day, per_week)
+            }
+            _ => (vec![], vec![]),
+        };
+
+        HistoryStats {
+            total: searches.len(),
+            first: days.keys().next().map(|d| d.to_string()),
+            last: days.keys().last().map(|d| d.to_string()),
+            top_queries: top_counts(queries, top),
+            top_sites: top_counts(sites, top),
+            per_day,
+            per_week,
+            per_hour,
+        }
+    }
+
+    /// The busiest hours of the day, busiest first.
+    pub fn peak_hours(&self, count: usize) -> Vec<(usize, usize)> {
+        let mut hours: Vec<(usize, usize)> = self
+            .per_hour
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, n)| *n > 0)
+            .collect();
+        hours.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hours.truncate(count);
+        hours
+    }
+}
+
+/// The Monday starting the week of `day`.
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+/// The `top` largest counts, largest first. Ties are sorted by key.
+fn top_counts(counts: HashMap<String, usize>, top: usize) -> Vec<Count> {
+    let mut counts: Vec<Count> = counts
+        .into_iter()
+        .map(|(key, count)| Count { key, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    counts.truncate(top);
+    counts
+}
+
+/// Draw counts as a sparkline scaled to the largest count.
+pub fn sparkline(counts: &[usize]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    counts
+        .iter()
+        .map(|&n| match n {
+            0 => SPARKS[0],
+            _ => SPARKS[(n * (SPARKS.len() - 1)).div_ceil(max.max(1))],
+        })
+        .collect()
+}
+
+fn print_stats(stats: &HistoryStats) {
+    let (Some(first), Some(last)) = (&stats.first, &stats.last) else {
+        println!("No searches found.");
+        return;
+    };
+    println!(
+        "{} {} from {} to {}",
+        "Searches:".magenta_bright(),
+        stats.total,
+        first,
+        last
+    );
+
+    for (title, counts) in [
+        ("Top queries:", &stats.top_queries),
+        ("Top sites:", &stats.top_sites),
+    ] {
+        if counts.is_empty() {
+            continue;
+        }
+        println!("\n{}", title.magenta_bright());
+        let width = counts[0].count.to_string().len();
+        for count in counts {
+            println!("  {:>width$}  {}", count.count, count.key);
+        }
+    }
+
+    //  Only the most recent periods fit on one line.
+    let room = layout::text_width().saturating_sub(12).max(10);
+    for (title, counts) in [("Per day:", &stats.per_day), ("Per week:", &stats.per_week)] {
+        let shown = &counts[counts.len().saturating_sub(room)..];
+        let values: Vec<usize> = shown.iter().map(|c| c.count).collect();
+        println!("\n{}", title.magenta_bright());
+        println!("  {}", sparkline(&values).cyan());
+        if let (Some(start), Some(end)) = (shown.first(), shown.last()) {
+            println!(
+                "  {} to {}, at most {}",
+                start.key,
+                end.key,
+                values.iter().max().unwrap()
+            );
+        }
+    }
+
+    println!("\n{}", "Peak hours:".magenta_bright());
+    println!("  {}", sparkline(&stats.per_hour).cyan());
+    println!("  0     6     12    18   23");
+    let peaks: Vec<String> = stats
+        .peak_hours(3)
+        .iter()
+        .map(|(hour, count)| format!("{:02}:00 ({})", hour, count))
+        .collect();
+    println!("  Busiest: {}", peaks.join(", "));
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use clap::Args;
+
+use crate::commands::CommandHandler;
+use crate::database::{self, SearchEntry};
+
+/// Score multiplier of starred queries.
+const STAR_BOOST: f64 = 2.0;
+
+/// Suggest past queries starting with a prefix.
+///
+/// Queries are ranked by frecency and printed one per line, so the output can
+/// be piped into `fzf` or used for shell completion.
+#[derive(Debug, Args)]
+pub(crate) struct SuggestArgs {
+    /// Start of the query. Lists every query if empty.
+    #[arg(default_value = "")]
+    prefix: String,
+    /// Maximum number of suggestions.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    limit: usize,
+    /// Print the score of each query before it, separated by a tab.
+    #[arg(short = None, long)]
+    scores: bool,
+}
+
+impl CommandHandler for SuggestArgs {
+    fn handle(&self) -> crate::Result<()> {
+        let entries = database::sqlite::get_search_by_query(self.prefix.clone())
+            .map_err(|e| e.to_string())?;
+        let now = chrono::Local::now().naive_local();
+        for (query, score) in rank(&entries, now).into_iter().take(self.limit) {
+            if self.scores {
+                println!("{:.0}\t{}", score, query);
+            } else {
+                println!("{}", query);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rank queries by frecency, highest first.
+///
+/// Every search adds a weight that shrinks with its age, so a query searched
+/// often and recently ranks first. Ties are broken by the query text.
+pub fn rank(entries: &[SearchEntry], now: NaiveDateTime) -> Vec<(String, f64)> {
+    let mut scores: HashMap<&str, (f64, bool)> = HashMap::new();
+    for entry in entries {
+        let query = entry.query.trim();
+        if query.is_empty() {
+            continue;
+        }
+        let score = scores.entry(query).or_default();
+        score.0 += recency_weight(now - entry.time_stamp);
+        score.1 |= entry.starred;
+    }
+
+    let mut ranked: Vec<(String, f64)> = scores
+        .into_iter()
+        .map(|(query, (score, starred))| {
+            let boost = if starred { STAR_BOOST } else { 1.0 };
+            (query.to_string(), score * boost)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+/// Weight of a search made `age` ago.
+fn recency_weight(age: chrono::TimeDelta) -> f64 {
+    match age.num_days() {
+        ..4 => 100.0,
+        4..14 => 70.0,
+        14..31 => 50.0,
+        31..90 => 30.0,
+        _ => 10.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeDelta};
+
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 6, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn search(query: &str, days_ago: i64, starred: bool) -> SearchEntry {
+        SearchEntry {
+            id: 0,
+            query: query.to_string(),
+            website: None,
+            allintext: None,
+            time_stamp: now() - TimeDelta::days(days_ago),
+            url: None,
+            exact: None,
+            exclude: None,
+            filetype: None,
+            intitle: None,
+            inurl: None,
+            after: None,
+            before: None,
+            any_of: None,
+            profile: None,
+            starred,
+        }
+    }
+
+    fn ranked(entries: &[SearchEntry]) -> Vec<(String, f64)> {
+        rank(entries, now())
+    }
+
+    #[test]
+    fn frequent_and_recent_queries_rank_first() {
+        let entries = [
+            search("go", 1, false),
+            search("rust", 0, false),
+            search("rust", 2, false),
+            search("zig", 100, false),
+            search("zig", 200, false),
+        ];
+        assert_eq!(
+            ranked(&entries),
+            [
+                ("rust".to_string(), 200.0),
+                ("go".to_string(), 100.0),
+                ("zig".to_string(), 20.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn weights_shrink_with_age() {
+        let weights: Vec<f64> = [0, 3, 4, 13, 14, 30, 31, 89, 90, 1000]
+            .iter()
+            .map(|days| ranked(&[search("q", *days, false)])[0].1)
+            .collect();
+        assert_eq!(
+            weights,
+            [100.0, 100.0, 70.0, 70.0, 50.0, 50.0, 30.0, 30.0, 10.0, 10.0]
+        );
+    }
+
+    #[test]
+    fn starred_queries_are_boosted() {
+        let entries = [
+            search("rust", 0, false),
+            search("rust", 0, false),
+            search("go", 0, false),
+            search("go", 40, true),
+            search("zig", 0, false),
+        ];
+        //  One starred search boosts every search of the query.
+        assert_eq!(
+            ranked(&entries),
+            [
+                ("go".to_string(), 260.0),
+                ("rust".to_string(), 200.0),
+                ("zig".to_string(), 100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn queries_are_trimmed_and_ties_sorted() {
+        let entries = [
+            search(" rust ", 0, false),
+            search("go", 0, false),
+            search("   ", 0, false),
+            search("rust", 5, false),
+            search("c", 5, false),
+            search("c", 5, false),
+        ];
+        assert_eq!(
+            ranked(&entries),
+            [
+                ("rust".to_string(), 170.0),
+                ("c".to_string(), 140.0),
+                ("go".to_string(), 100.0),
+            ]
+        );
+        let ties = [search("b", 0, false), search("a", 0, false)];
+        let names: Vec<String> = ranked(&ties).into_iter().map(|(q, _)| q).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(ranked(&[]).is_empty());
+    }
+}
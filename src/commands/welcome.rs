@@ -0,0 +1,203 @@
+use chrono::{Duration, Local, NaiveDateTime};
+use git2::Repository;
+
+use crate::Colorize;
+use crate::commands::environment::core::WelcomeSection;
+use crate::commands::git::{core::is_dirty, find_repos};
+use crate::commands::greeting::welcome_msg;
+use crate::commands::quotes::core::get_daily;
+use crate::database::models::task::{Task, TaskStatus, task_status_utf8};
+use crate::database::sqlite::{get_recent_searches, get_tasks_by_due_date, get_tasks_by_status};
+use crate::{expand_sanitized_home, layout};
+
+/// Print the `welcome` dashboard.
+///
+/// The sections and their order are read from the `welcome` table of the
+/// config file. Every section is printed under its heading. A section whose
+/// data cannot be loaded prints a short notice instead of failing the whole
+/// dashboard.
+pub fn show_dashboard() -> crate::Result<()> {
+    let (sections, search_count) = {
+        let env = crate::ENV.lock().unwrap();
+        (env.welcome.enabled_sections(), env.welcome.search_count)
+    };
+
+    let mut output: Vec<String> = vec![];
+    for section in sections {
+        let res = match section {
+            WelcomeSection::Greeting => Ok(indent(welcome_msg().trim_end())),
+            WelcomeSection::Quote => get_daily(None).map(|q| indent(&q.to_string())),
+            WelcomeSection::Tasks => due_tasks(),
+            WelcomeSection::Timers => timers(),
+            WelcomeSection::Repos => dirty_repos(),
+            WelcomeSection::Searches => recent_searches(search_count),
+        };
+        output.push(section_text(section, res));
+    }
+    println!("{}", output.join("\n\n"));
+    Ok(())
+}
+
+/// A section under its heading, or a notice if it failed to load.
+fn section_text(section: WelcomeSection, res: crate::Result<String>) -> String {
+    let body = res.unwrap_or_else(|e| format!("  {}", unavailable(e)));
+    format!("{}\n{}", heading(section), body)
+}
+
+/// Indent every line of a section body, like the lines of list sections.
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The heading printed above a section.
+fn heading(section: WelcomeSection) -> String {
+    let title = match section {
+        WelcomeSection::Greeting => "Welcome",
+        WelcomeSection::Quote => "Daily Quote",
+        WelcomeSection::Tasks => "Due Today",
+        WelcomeSection::Timers => "In Progress",
+        WelcomeSection::Repos => "Uncommitted Changes",
+        WelcomeSection::Searches => "Recent Searches",
+    };
+    format!("{}:", title).magenta_bright()
+}
+
+/// The notice printed in place of a section that failed to load.
+fn unavailable(e: Box<dyn std::error::Error>) -> String {
+    let msg = e.to_string();
+    let msg = msg.lines().next().unwrap_or_default().to_string();
+    format!("unavailable ({})", msg).yellow()
+}
+
+/// A single line describing a task.
+fn task_line(task: &Task) -> String {
+    format!(
+        "  {} ({:02}) {}",
+        task_status_utf8(&TaskStatus::from(task.status)),
+        task.id,
+        task.task
+    )
+}
+
+/// Tasks that are due today or overdue.
+fn due_tasks() -> crate::Result<String> {
+    let now = Local::now().naive_local();
+    let end_of_day = (now.date() + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or(now);
+    let tasks: Vec<Task> = get_tasks_by_due_date(end_of_day)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|t| !matches!(TaskStatus::from(t.status), TaskStatus::Complete))
+        .collect();
+
+    let mut lines = vec![];
+    if tasks.is_empty() {
+        lines.push("  Nothing due today.".to_string());
+    }
+    for task in tasks {
+        let Some(due) = task.due_date else {
+            continue;
+        };
+        let due_str = if due < now {
+            format!("overdue since {}", timestamp_display(due)).red()
+        } else {
+            format!("due {}", due.format("%H:%M")).green()
+        };
+        lines.push(format!("{} {}", task_line(&task), due_str));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Tasks that are in progress.
+fn timers() -> crate::Result<String> {
+    let tasks = get_tasks_by_status(TaskStatus::InProgress).map_err(|e| e.to_string())?;
+
+    let mut lines = vec![];
+    if tasks.is_empty() {
+        lines.push("  No tasks in progress.".to_string());
+    }
+    for task in tasks {
+        let started = format!("since {}", timestamp_display(task.time_stamp)).cyan();
+        lines.push(format!("{} {}", task_line(&task), started));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Repos under `git_dir` with uncommitted changes.
+fn dirty_repos() -> crate::Result<String> {
+    let git_dir = expand_sanitized_home(&crate::ENV.lock().unwrap().git_dir.clone());
+    let mut dirty = vec![];
+    for path in find_repos()? {
+        //  Directories that are not repos are skipped.
+        let Ok(repo) = Repository::open(&path) else {
+            continue;
+        };
+        if is_dirty(&repo).unwrap_or(false) {
+            let name = path.strip_prefix(&git_dir).unwrap_or(&path);
+            dirty.push(format!("  {}", name.display()));
+        }
+    }
+
+    let mut lines = vec![];
+    if dirty.is_empty() {
+        lines.push("  All repos are clean.".to_string());
+    }
+    lines.extend(dirty);
+    Ok(lines.join("\n"))
+}
+
+/// The most recent searches.
+fn recent_searches(count: i64) -> crate::Result<String> {
+    let searches = get_recent_searches(count).map_err(|e| e.to_string())?;
+    let width = layout::terminal_width();
+
+    let mut lines = vec![];
+    if searches.is_empty() {
+        lines.push("  No searches yet.".to_string());
+    }
+    for search in searches {
+        let mut query = search.query.clone();
+        if let Some(site) = &search.website {
+            query.push_str(&format!(" ({})", site));
+        }
+        let label = format!("  {} ", search.time_stamp.format("%Y-%m-%d %H:%M"));
+        lines.push(layout::labeled(&label, &query, width));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Format a timestamp without seconds.
+fn timestamp_display(date: NaiveDateTime) -> String {
+    date.format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_section_has_a_heading() {
+        let loaded = section_text(WelcomeSection::Quote, Ok(indent("To be.\n— Someone")));
+        assert_eq!(
+            loaded,
+            format!("{}\n  To be.\n  — Someone", heading(WelcomeSection::Quote))
+        );
+
+        let failed = section_text(
+            WelcomeSection::Repos,
+            Err("No such directory\nmore details".into()),
+        );
+        assert_eq!(
+            failed,
+            format!(
+                "{}\n  {}",
+                heading(WelcomeSection::Repos),
+                "unavailable (No such directory)".yellow()
+            )
+        );
+    }
+}
@@ -7,8 +7,11 @@ mod schema;
 pub mod sqlite;
 
 pub use models::{
-    quote::{DailyQuote, NewDailyQuote, NewQuote, Quote},
+    author::{Author, AuthorAlias},
+    bookmark::{Bookmark, BookmarkTag},
+    quote::{DailyQuote, NewDailyQuote, NewQuote, Quote, QuoteTag, Tag},
     search::SearchEntry,
+    snapshot::{Snapshot, SnapshotInfo},
 };
 
 /// A wrapper for [std::result::Result] with a [Box]ed [std::error::Error].
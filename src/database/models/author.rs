@@ -0,0 +1,19 @@
+use crate::database::schema::{author_aliases, authors};
+use diesel::prelude::*;
+
+/// A quote author with a canonical name.
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = authors)]
+pub struct Author {
+    pub id: i32,
+    pub name: String,
+}
+
+/// An alternative spelling that resolves to an [`Author`].
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = author_aliases)]
+pub struct AuthorAlias {
+    pub id: i32,
+    pub author_id: i32,
+    pub alias: String,
+}
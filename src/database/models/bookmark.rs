@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::database::schema::{bookmark_tags, bookmarks};
+
+/// A bookmarked URL.
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = bookmarks)]
+pub struct Bookmark {
+    pub id: i32,
+    pub url: String,
+    /// Title of the page, if known.
+    pub title: Option<String>,
+    pub time_stamp: NaiveDateTime,
+}
+
+/// A relation between a bookmark and a tag.
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = bookmark_tags)]
+pub struct BookmarkTag {
+    pub id: i32,
+    pub bookmark_id: i32,
+    pub tag_id: i32,
+}
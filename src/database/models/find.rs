@@ -0,0 +1,25 @@
+use diesel::prelude::*;
+use diesel::sql_types::{Double, Integer, Text};
+
+/// Start of a matching term in the text of a [`FindHit`].
+pub const MATCH_START: char = '\u{2}';
+/// End of a matching term in the text of a [`FindHit`].
+pub const MATCH_END: char = '\u{3}';
+
+/// A full text search match.
+///
+/// Matching terms in `title` and `detail` are wrapped in [`MATCH_START`] and
+/// [`MATCH_END`].
+#[derive(Debug, Clone, QueryableByName)]
+pub struct FindHit {
+    /// ID of the matching row.
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
+    pub title: String,
+    #[diesel(sql_type = Text)]
+    pub detail: String,
+    /// BM25 score of the match. Lower is better.
+    #[diesel(sql_type = Double)]
+    pub rank: f64,
+}
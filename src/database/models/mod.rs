@@ -1,5 +1,9 @@
+pub mod author;
+pub mod bookmark;
+pub mod find;
 pub mod quote;
 pub mod search;
+pub mod snapshot;
 pub mod task;
 
 pub use task::TaskRelation;
@@ -1,6 +1,7 @@
 use crate::{
     Colorize,
-    database::schema::{daily_quotes, quotes},
+    database::schema::{daily_quotes, quote_tags, quotes, tags},
+    layout,
 };
 use diesel::prelude::*;
 
@@ -15,24 +16,19 @@ pub struct Quote {
 
 impl std::fmt::Display for Quote {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut quote_str = String::new();
-        let max_chars = 80;
-        let mut char_cnt = 0;
-        let words = self.quote.split_whitespace();
-        for word in words {
-            if char_cnt + word.len() > max_chars {
-                quote_str.push_str("\n");
-                char_cnt = 0;
-            }
-            quote_str.push_str(word);
-            quote_str.push_str(" ");
-            char_cnt += word.len();
-        }
-        f.write_str(&format!(
-            "{}\n\t- {}",
-            &quote_str.magenta_bright(),
-            &self.author.green_bright()
-        ))
+        let width = layout::text_width();
+        let quote_str = layout::wrap(&self.quote, width, "", "")
+            .iter()
+            .map(|line| line.magenta_bright())
+            .collect::<Vec<String>>()
+            .join("\n");
+        let attribution = format!("— {}", self.author.green_bright());
+        write!(
+            f,
+            "{}\n{}",
+            quote_str,
+            layout::right_align(&attribution, width)
+        )
     }
 }
 
@@ -53,6 +49,10 @@ pub struct DailyQuote {
     pub id: i32,
     pub quote_id: i32,
     pub time_stamp: chrono::NaiveDateTime,
+    /// Tag the quote was picked for, or `None` for the untagged daily quote.
+    pub tag: Option<String>,
+    /// Whether the quote was the first draw of a deck.
+    pub deck_start: bool,
 }
 
 /// A new daily quote.
@@ -63,4 +63,23 @@ pub struct DailyQuote {
 pub struct NewDailyQuote {
     pub quote_id: i32,
     pub time_stamp: chrono::NaiveDateTime,
+    pub tag: Option<String>,
+    pub deck_start: bool,
+}
+
+/// A tag that can be applied to quotes and bookmarks.
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = tags)]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+}
+
+/// A relation between a quote and a tag.
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = quote_tags)]
+pub struct QuoteTag {
+    pub id: i32,
+    pub quote_id: i32,
+    pub tag_id: i32,
 }
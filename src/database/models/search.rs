@@ -1,5 +1,5 @@
 use crate::database::schema::searches;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
 
 /// A google search history item.
@@ -12,25 +12,20 @@ pub struct SearchEntry {
     pub allintext: Option<String>,
     #[diesel(sql_type = Timestamp)]
     pub time_stamp: NaiveDateTime,
-}
-
-impl std::fmt::Display for SearchEntry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let _ = f.write_fmt(format_args!("Query: {}\n", self.query));
-        if let Some(website) = &self.website {
-            let _ = f.write_fmt(format_args!("Website: {}\n", website));
-        }
-        if let Some(all_text) = &self.allintext {
-            let _ = f.write_fmt(format_args!("All in text: {}\n", all_text));
-        }
-        let _ = f.write_fmt(format_args!("Time: {}\n", self.time_stamp));
-        f.write_fmt(format_args!(
-            "Query String: {}\n",
-            crate::commands::web::core::query_string_builder(
-                &self.query,
-                &self.website,
-                &self.allintext,
-            )
-        ))
-    }
+    /// URL that was opened for the search.
+    pub url: Option<String>,
+    pub exact: Option<String>,
+    /// Comma separated terms.
+    pub exclude: Option<String>,
+    pub filetype: Option<String>,
+    pub intitle: Option<String>,
+    pub inurl: Option<String>,
+    pub after: Option<NaiveDate>,
+    pub before: Option<NaiveDate>,
+    /// Comma separated terms.
+    pub any_of: Option<String>,
+    /// Name of the search profile used for the search.
+    pub profile: Option<String>,
+    /// Whether the search is marked as a favorite.
+    pub starred: bool,
 }
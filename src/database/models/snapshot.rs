@@ -0,0 +1,42 @@
+use std::io::Read;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use flate2::read::GzDecoder;
+
+use crate::database::schema::snapshots;
+
+/// A page saved for reading offline.
+#[derive(Debug, Queryable, AsChangeset, Selectable, Clone, Insertable)]
+#[diesel(table_name = snapshots)]
+pub struct Snapshot {
+    pub id: i32,
+    pub url: String,
+    /// Title of the page, if it has one.
+    pub title: Option<String>,
+    /// Readable text of the page, compressed with gzip.
+    pub content: Vec<u8>,
+    /// Length of the text before compression, in bytes.
+    pub size: i32,
+    pub time_stamp: NaiveDateTime,
+}
+
+/// A saved page without its content, for listing.
+#[derive(Debug, Queryable, Selectable, Clone)]
+#[diesel(table_name = snapshots)]
+pub struct SnapshotInfo {
+    pub id: i32,
+    pub url: String,
+    pub title: Option<String>,
+    pub size: i32,
+    pub time_stamp: NaiveDateTime,
+}
+
+impl Snapshot {
+    /// Decompress the text of the page.
+    pub fn text(&self) -> std::io::Result<String> {
+        let mut text = String::new();
+        GzDecoder::new(self.content.as_slice()).read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
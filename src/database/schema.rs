@@ -1,10 +1,52 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    author_aliases (id) {
+        id -> Integer,
+        author_id -> Integer,
+        alias -> Text,
+    }
+}
+
+diesel::table! {
+    authors (id) {
+        id -> Integer,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    bookmark_tags (id) {
+        id -> Integer,
+        bookmark_id -> Integer,
+        tag_id -> Integer,
+    }
+}
+
+diesel::table! {
+    bookmarks (id) {
+        id -> Integer,
+        url -> Text,
+        title -> Nullable<Text>,
+        time_stamp -> Timestamp,
+    }
+}
+
 diesel::table! {
     daily_quotes (id) {
         id -> Integer,
         quote_id -> Integer,
         time_stamp -> Timestamp,
+        tag -> Nullable<Text>,
+        deck_start -> Bool,
+    }
+}
+
+diesel::table! {
+    quote_tags (id) {
+        id -> Integer,
+        quote_id -> Integer,
+        tag_id -> Integer,
     }
 }
 
@@ -23,6 +65,35 @@ diesel::table! {
         website -> Nullable<Text>,
         allintext -> Nullable<Text>,
         time_stamp -> Timestamp,
+        url -> Nullable<Text>,
+        exact -> Nullable<Text>,
+        exclude -> Nullable<Text>,
+        filetype -> Nullable<Text>,
+        intitle -> Nullable<Text>,
+        inurl -> Nullable<Text>,
+        after -> Nullable<Date>,
+        before -> Nullable<Date>,
+        any_of -> Nullable<Text>,
+        profile -> Nullable<Text>,
+        starred -> Bool,
+    }
+}
+
+diesel::table! {
+    snapshots (id) {
+        id -> Integer,
+        url -> Text,
+        title -> Nullable<Text>,
+        content -> Binary,
+        size -> Integer,
+        time_stamp -> Timestamp,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Integer,
+        name -> Text,
     }
 }
 
@@ -46,12 +117,24 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(author_aliases -> authors (author_id));
+diesel::joinable!(bookmark_tags -> bookmarks (bookmark_id));
+diesel::joinable!(bookmark_tags -> tags (tag_id));
 diesel::joinable!(daily_quotes -> quotes (quote_id));
+diesel::joinable!(quote_tags -> quotes (quote_id));
+diesel::joinable!(quote_tags -> tags (tag_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    author_aliases,
+    authors,
+    bookmark_tags,
+    bookmarks,
     daily_quotes,
+    quote_tags,
     quotes,
     searches,
+    snapshots,
+    tags,
     task_relations,
     tasks,
 );
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+
+use crate::database::sqlite::establish_connection;
+
+use super::{Author, AuthorAlias, DbResult};
+
+diesel::define_sql_function!(fn lower(text: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// Minimum similarity for an existing author to be suggested for a new name.
+const SUGGESTION_THRESHOLD: f64 = 0.88;
+
+/// Find the canonical name for an author name or alias.
+///
+/// Names are compared case-insensitively and ignoring surrounding whitespace.
+pub(crate) fn find_canonical(
+    conn: &mut SqliteConnection,
+    author_name: &str,
+) -> DbResult<Option<Author>> {
+    use crate::database::schema::{author_aliases, authors};
+
+    let key = author_name.trim().to_lowercase();
+    let all_authors = authors::table
+        .select(Author::as_select())
+        .load(conn)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(author) = all_authors.iter().find(|a| a.name.to_lowercase() == key) {
+        return Ok(Some(author.clone()));
+    }
+
+    let aliases = author_aliases::table
+        .select(AuthorAlias::as_select())
+        .load(conn)
+        .map_err(|e| e.to_string())?;
+
+    Ok(aliases
+        .iter()
+        .find(|a| a.alias.to_lowercase() == key)
+        .and_then(|alias| all_authors.iter().find(|a| a.id == alias.author_id))
+        .cloned())
+}
+
+/// Resolve an author name to its canonical spelling.
+///
+/// Unknown authors are registered under the given name.
+pub(crate) fn resolve_author(conn: &mut SqliteConnection, author_name: &str) -> DbResult<String> {
+    use crate::database::schema::authors::dsl::*;
+
+    if let Some(author) = find_canonical(conn, author_name)? {
+        return Ok(author.name);
+    }
+
+    let last_id = authors
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|e| e.to_string())?;
+    let new_author = Author {
+        id: last_id.map(|i| i + 1).unwrap_or(0),
+        name: author_name.trim().to_string(),
+    };
+
+    let result = diesel::insert_into(authors)
+        .values(&new_author)
+        .execute(conn);
+
+    match result {
+        Ok(_) => Ok(new_author.name),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Get the canonical name of a known author or alias.
+///
+/// Returns `None` if the name is not known.
+pub fn get_canonical_author(author_name: &str) -> DbResult<Option<String>> {
+    let conn = &mut establish_connection()?;
+    Ok(find_canonical(conn, author_name)?.map(|a| a.name))
+}
+
+/// Suggest an existing author whose name is similar to `author_name`.
+///
+/// Exact matches of names and aliases are not suggested.
+pub fn suggest_author(author_name: &str) -> DbResult<Option<String>> {
+    use crate::database::schema::authors::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    if find_canonical(conn, author_name)?.is_some() {
+        return Ok(None);
+    }
+
+    let key = author_name.trim().to_lowercase();
+    let names = authors.select(name).load::<String>(conn);
+
+    let best = names
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|n| (strsim::jaro_winkler(&key, &n.to_lowercase()), n))
+        .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+
+    Ok(best.map(|(_, n)| n))
+}
+
+/// Get every author with its number of quotes and aliases.
+pub fn get_author_stats() -> DbResult<Vec<(Author, i64, Vec<String>)>> {
+    use crate::database::schema::{author_aliases, authors, quotes};
+
+    let conn = &mut establish_connection()?;
+    let all_authors = authors::table
+        .order(authors::name.asc())
+        .select(Author::as_select())
+        .load(conn)
+        .map_err(|e| e.to_string())?;
+
+    let counts: HashMap<String, i64> = quotes::table
+        .group_by(quotes::author)
+        .select((quotes::author, diesel::dsl::count(quotes::id)))
+        .load::<(String, i64)>(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+
+    let mut aliases: HashMap<i32, Vec<String>> = HashMap::new();
+    for alias in author_aliases::table
+        .select(AuthorAlias::as_select())
+        .load(conn)
+        .map_err(|e| e.to_string())?
+    {
+        aliases
+            .entry(alias.author_id)
+            .or_default()
+            .push(alias.alias);
+    }
+
+    Ok(all_authors
+        .into_iter()
+        .map(|a| {
+            let count = counts.get(&a.name).copied().unwrap_or(0);
+            let names = aliases.remove(&a.id).unwrap_or_default();
+            (a, count, names)
+        })
+        .collect())
+}
+
+/// Record `alias` as an alternative spelling of `author_name`.
+///
+/// Quotes attributed to the alias are moved to the canonical author and an
+/// author registered under the alias is merged away. Like lookups, these
+/// compare names case-insensitively. Returns the number of quotes that were
+/// updated.
+pub fn add_author_alias(alias_name: &str, author_name: &str) -> DbResult<usize> {
+    use crate::database::schema::{author_aliases, authors, quotes};
+
+    let conn = &mut establish_connection()?;
+    let Some(author) = find_canonical(conn, author_name)? else {
+        return Err(format!("No author found with name: {}", author_name).into());
+    };
+    let alias_name = alias_name.trim().to_string();
+    let key = alias_name.to_lowercase();
+    if key == author.name.to_lowercase() {
+        return Err("An author cannot be an alias of itself.".into());
+    }
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        //  Merge an author that was registered under the alias.
+        let merged = authors::table
+            .filter(lower(authors::name).eq(&key))
+            .select(authors::id)
+            .first::<i32>(conn)
+            .optional()?;
+        if let Some(merged_id) = merged {
+            diesel::update(author_aliases::table.filter(author_aliases::author_id.eq(merged_id)))
+                .set(author_aliases::author_id.eq(author.id))
+                .execute(conn)?;
+            diesel::delete(authors::table.find(merged_id)).execute(conn)?;
+        }
+
+        diesel::delete(author_aliases::table.filter(lower(author_aliases::alias).eq(&key)))
+            .execute(conn)?;
+        let last_id = author_aliases::table
+            .select(diesel::dsl::max(author_aliases::id))
+            .first::<Option<i32>>(conn)?;
+        let new_alias = AuthorAlias {
+            id: last_id.map(|i| i + 1).unwrap_or(0),
+            author_id: author.id,
+            alias: alias_name.clone(),
+        };
+        diesel::insert_into(author_aliases::table)
+            .values(&new_alias)
+            .execute(conn)?;
+
+        diesel::update(quotes::table.filter(lower(quotes::author).eq(&key)))
+            .set(quotes::author.eq(&author.name))
+            .execute(conn)
+    });
+
+    result.map_err(|e| e.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewQuote;
+    use crate::database::sqlite::{get_quotes, insert_quote};
+    use crate::testing::TestEnv;
+
+    fn add(quote: &str, author: &str) -> i32 {
+        insert_quote(NewQuote {
+            quote: quote.to_string(),
+            author: author.to_string(),
+        })
+        .unwrap()
+    }
+
+    fn author_of(q_id: i32) -> String {
+        let quotes = get_quotes().unwrap();
+        quotes.into_iter().find(|q| q.id == q_id).unwrap().author
+    }
+
+    #[test]
+    fn names_and_aliases_are_case_insensitive() {
+        let _env = TestEnv::new();
+        let canonical = |name| get_canonical_author(name).unwrap();
+        assert_eq!(
+            canonical(" richard FEYNMAN ").as_deref(),
+            Some("Richard Feynman")
+        );
+        assert_eq!(
+            canonical("friederich nietzsche").as_deref(),
+            Some("Friedrich Nietzsche")
+        );
+        assert_eq!(canonical("Ada Lovelace"), None);
+
+        let q_id = add("A quote.", "immanuel KANT");
+        assert_eq!(author_of(q_id), "Immanuel Kant");
+    }
+
+    #[test]
+    fn suggests_similar_authors() {
+        let _env = TestEnv::new();
+        let suggest = |name| suggest_author(name).unwrap();
+        assert_eq!(
+            suggest("Friedrich Nietzche").as_deref(),
+            Some("Friedrich Nietzsche")
+        );
+        assert_eq!(
+            suggest("richard feynmann").as_deref(),
+            Some("Richard Feynman")
+        );
+        //  Known names and aliases are not suggested again.
+        assert_eq!(suggest("Richard Feynman"), None);
+        assert_eq!(suggest("Friederich Nietzsche"), None);
+        assert_eq!(suggest("Ada Lovelace"), None);
+    }
+
+    #[test]
+    fn alias_merges_case_variants() {
+        let _env = TestEnv::new();
+        let first = add("First.", "Feynman");
+        let second = add("Second.", "feynman");
+        assert_eq!(author_of(second), "Feynman");
+
+        assert_eq!(add_author_alias("FEYNMAN", "richard feynman").unwrap(), 2);
+        assert_eq!(author_of(first), "Richard Feynman");
+        assert_eq!(author_of(second), "Richard Feynman");
+        assert_eq!(
+            get_canonical_author("feynman").unwrap().as_deref(),
+            Some("Richard Feynman")
+        );
+
+        let stats = get_author_stats().unwrap();
+        let names: Vec<_> = stats.iter().map(|(a, _, _)| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Friedrich Nietzsche", "Immanuel Kant", "Richard Feynman"]
+        );
+        let (_, count, aliases) = &stats[2];
+        assert_eq!(*count, 6);
+        assert_eq!(aliases, &["FEYNMAN"]);
+
+        //  Aliasing again replaces the spelling instead of adding one.
+        add_author_alias("Feynman", "Richard Feynman").unwrap();
+        assert_eq!(get_author_stats().unwrap()[2].2, ["Feynman"]);
+    }
+
+    #[test]
+    fn invalid_aliases() {
+        let _env = TestEnv::new();
+        assert!(add_author_alias("Someone", "Ada Lovelace").is_err());
+        assert!(add_author_alias(" immanuel kant", "Immanuel Kant").is_err());
+    }
+}
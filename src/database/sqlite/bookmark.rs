@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::database::sqlite::{establish_connection, get_or_insert_tag, normalize_tag};
+
+use super::{Bookmark, BookmarkTag, DbResult};
+
+/// Get a bookmark by its ID.
+pub fn get_bookmark(bookmark_id: i32) -> DbResult<Bookmark> {
+    use crate::database::schema::bookmarks::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = bookmarks
+        .find(bookmark_id)
+        .first::<Bookmark>(conn)
+        .optional();
+
+    match result {
+        Ok(Some(b)) => Ok(b),
+        Ok(None) => Err(format!("No bookmark found with ID: {}", bookmark_id).into()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Get a bookmark by its URL.
+pub fn get_bookmark_by_url(bookmark_url: &str) -> DbResult<Option<Bookmark>> {
+    use crate::database::schema::bookmarks::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = bookmarks
+        .filter(url.eq(bookmark_url))
+        .first::<Bookmark>(conn)
+        .optional();
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get all bookmarks, newest first.
+pub fn get_bookmarks() -> DbResult<Vec<Bookmark>> {
+    use crate::database::schema::bookmarks::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = bookmarks
+        .order((time_stamp.desc(), id.desc()))
+        .load::<Bookmark>(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get the bookmarks whose URL, title or tags contain `text`, newest first.
+pub fn search_bookmarks(text: &str) -> DbResult<Vec<Bookmark>> {
+    use crate::database::schema::{bookmark_tags, bookmarks, tags};
+
+    let conn = &mut establish_connection()?;
+    let pattern = format!("%{}%", text.trim());
+    let tagged = bookmark_tags::table
+        .inner_join(tags::table)
+        .filter(tags::name.like(pattern.clone()))
+        .select(bookmark_tags::bookmark_id);
+    let result = bookmarks::table
+        .filter(
+            bookmarks::url
+                .like(pattern.clone())
+                .or(bookmarks::title.like(pattern))
+                .or(bookmarks::id.eq_any(tagged)),
+        )
+        .order((bookmarks::time_stamp.desc(), bookmarks::id.desc()))
+        .select(Bookmark::as_select())
+        .load(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get all bookmarks with a tag, newest first.
+pub fn get_bookmarks_by_tag(tag_name: &str) -> DbResult<Vec<Bookmark>> {
+    use crate::database::schema::{bookmark_tags, bookmarks, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = bookmarks::table
+        .inner_join(bookmark_tags::table.inner_join(tags::table))
+        .filter(tags::name.eq(normalize_tag(tag_name)))
+        .order((bookmarks::time_stamp.desc(), bookmarks::id.desc()))
+        .select(Bookmark::as_select())
+        .load(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get the tag names of every tagged bookmark, keyed by bookmark ID.
+pub fn get_all_bookmark_tags() -> DbResult<HashMap<i32, Vec<String>>> {
+    use crate::database::schema::{bookmark_tags, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = bookmark_tags::table
+        .inner_join(tags::table)
+        .order(tags::name.asc())
+        .select((bookmark_tags::bookmark_id, tags::name))
+        .load::<(i32, String)>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut map: HashMap<i32, Vec<String>> = HashMap::new();
+    for (b_id, name) in result {
+        map.entry(b_id).or_default().push(name);
+    }
+    Ok(map)
+}
+
+/// Get the names of the tags applied to a bookmark.
+pub fn get_bookmark_tags(b_id: i32) -> DbResult<Vec<String>> {
+    use crate::database::schema::{bookmark_tags, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = bookmark_tags::table
+        .inner_join(tags::table)
+        .filter(bookmark_tags::bookmark_id.eq(b_id))
+        .order(tags::name.asc())
+        .select(tags::name)
+        .load::<String>(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Insert a bookmark and apply its tags.
+///
+/// Returns the new bookmark. Fails if the URL is already bookmarked.
+pub fn insert_bookmark(
+    bookmark_url: &str,
+    bookmark_title: Option<String>,
+    added: NaiveDateTime,
+    tag_names: &[String],
+) -> DbResult<Bookmark> {
+    use crate::database::schema::bookmarks::dsl::*;
+
+    if get_bookmark_by_url(bookmark_url)?.is_some() {
+        return Err(format!("{} is already bookmarked.", bookmark_url).into());
+    }
+
+    let conn = &mut establish_connection()?;
+    let last_id = bookmarks
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|e| e.to_string())?;
+    let bookmark = Bookmark {
+        id: last_id.map(|i| i + 1).unwrap_or(0),
+        url: bookmark_url.to_string(),
+        title: bookmark_title,
+        time_stamp: added,
+    };
+
+    diesel::insert_into(bookmarks)
+        .values(&bookmark)
+        .execute(conn)
+        .map_err(|e| e.to_string())?;
+    for tag_name in tag_names {
+        tag_bookmark_with(conn, bookmark.id, tag_name)?;
+    }
+    Ok(bookmark)
+}
+
+/// Apply a tag to a bookmark.
+///
+/// The tag is created if it does not exist. Tagging a bookmark twice with
+/// the same tag has no effect.
+pub fn tag_bookmark(b_id: i32, tag_name: &str) -> DbResult<()> {
+    get_bookmark(b_id)?;
+    let conn = &mut establish_connection()?;
+    tag_bookmark_with(conn, b_id, tag_name)
+}
+
+fn tag_bookmark_with(conn: &mut SqliteConnection, b_id: i32, tag_name: &str) -> DbResult<()> {
+    use crate::database::schema::bookmark_tags::dsl::*;
+
+    let tag_name = normalize_tag(tag_name);
+    if tag_name.is_empty() {
+        return Ok(());
+    }
+    let t_id = get_or_insert_tag(conn, &tag_name)?;
+
+    let exists = bookmark_tags
+        .filter(bookmark_id.eq(b_id).and(tag_id.eq(t_id)))
+        .select(BookmarkTag::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    if exists.is_some() {
+        return Ok(());
+    }
+
+    let last_id = bookmark_tags
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|e| e.to_string())?;
+    let relation = BookmarkTag {
+        id: last_id.map(|i| i + 1).unwrap_or(0),
+        bookmark_id: b_id,
+        tag_id: t_id,
+    };
+
+    let result = diesel::insert_into(bookmark_tags)
+        .values(&relation)
+        .execute(conn);
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Delete a bookmark matching its ID.
+///
+/// The bookmark's tags are removed with it.
+pub fn delete_bookmark(b_id: i32) -> DbResult<()> {
+    use crate::database::schema::{bookmark_tags, bookmarks};
+
+    let conn = &mut establish_connection()?;
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(bookmark_tags::table.filter(bookmark_tags::bookmark_id.eq(b_id)))
+            .execute(conn)?;
+        diesel::delete(bookmarks::table.find(b_id)).execute(conn)
+    });
+
+    match result {
+        Ok(0) => Err(format!("No bookmark found with ID: {}", b_id).into()),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::sqlite::{get_tag_counts, tag_quote};
+    use crate::testing::TestEnv;
+
+    #[test]
+    fn bookmark_tags_are_normalized() {
+        let _env = TestEnv::new();
+        let now = chrono::Local::now().naive_local();
+        let tags = [" rust".to_string(), "Docs".to_string(), "docs".to_string()];
+        let bookmark = insert_bookmark("https://doc.rust-lang.org/", None, now, &tags).unwrap();
+
+        assert_eq!(get_bookmark_tags(bookmark.id).unwrap(), ["docs", "rust"]);
+        assert_eq!(get_bookmarks_by_tag("DOCS").unwrap().len(), 1);
+        assert!(insert_bookmark("https://doc.rust-lang.org/", None, now, &[]).is_err());
+    }
+
+    #[test]
+    fn bookmark_tags_are_not_counted_as_quote_tags() {
+        let _env = TestEnv::new();
+        let before = get_tag_counts().unwrap();
+        assert!(before.iter().all(|(_, count)| *count > 0));
+
+        let now = chrono::Local::now().naive_local();
+        let tags = ["bookmarked-only".to_string(), "shared".to_string()];
+        insert_bookmark("https://example.com", None, now, &tags).unwrap();
+        tag_quote(0, "shared").unwrap();
+
+        let counts = get_tag_counts().unwrap();
+        assert!(!counts.iter().any(|(name, _)| name == "bookmarked-only"));
+        assert!(counts.contains(&("shared".to_string(), 1)));
+        assert_eq!(counts.len(), before.len() + 1);
+    }
+}
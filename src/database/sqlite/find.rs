@@ -0,0 +1,83 @@
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+
+use crate::database::models::find::FindHit;
+use crate::database::sqlite::establish_connection;
+
+use super::DbResult;
+
+/// Tables with a full text index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FtsTable {
+    Searches,
+    Quotes,
+    Tasks,
+    Snapshots,
+}
+
+impl FtsTable {
+    /// The query for the best matches of the table, taking the match
+    /// expression and the limit as parameters.
+    fn sql(&self) -> &'static str {
+        match self {
+            FtsTable::Searches => {
+                "SELECT rowid AS id,
+                    highlight(searches_fts, 0, char(2), char(3)) AS title,
+                    coalesce(highlight(searches_fts, 1, char(2), char(3)), '') AS detail,
+                    bm25(searches_fts) AS rank
+                FROM searches_fts WHERE searches_fts MATCH ? ORDER BY rank LIMIT ?"
+            }
+            FtsTable::Quotes => {
+                "SELECT rowid AS id,
+                    snippet(quotes_fts, 0, char(2), char(3), '…', 16) AS title,
+                    highlight(quotes_fts, 1, char(2), char(3)) AS detail,
+                    bm25(quotes_fts) AS rank
+                FROM quotes_fts WHERE quotes_fts MATCH ? ORDER BY rank LIMIT ?"
+            }
+            FtsTable::Tasks => {
+                "SELECT rowid AS id,
+                    highlight(tasks_fts, 0, char(2), char(3)) AS title,
+                    coalesce(snippet(tasks_fts, 1, char(2), char(3), '…', 12), '') AS detail,
+                    bm25(tasks_fts) AS rank
+                FROM tasks_fts WHERE tasks_fts MATCH ? ORDER BY rank LIMIT ?"
+            }
+            FtsTable::Snapshots => {
+                "SELECT rowid AS id,
+                    coalesce(highlight(snapshots_fts, 0, char(2), char(3)), url) AS title,
+                    snippet(snapshots_fts, 2, char(2), char(3), '…', 16) AS detail,
+                    bm25(snapshots_fts) AS rank
+                FROM snapshots_fts WHERE snapshots_fts MATCH ? ORDER BY rank LIMIT ?"
+            }
+        }
+    }
+}
+
+/// Find the rows of `table` best matching `text`, best first.
+///
+/// Every word of `text` must match, and the last word also matches as a
+/// prefix. FTS5 query syntax in `text` is treated as plain text.
+pub fn find(table: FtsTable, text: &str, limit: i64) -> DbResult<Vec<FindHit>> {
+    let Some(expr) = match_expression(text) else {
+        return Ok(vec![]);
+    };
+    let conn = &mut establish_connection()?;
+    diesel::sql_query(table.sql())
+        .bind::<Text, _>(expr)
+        .bind::<BigInt, _>(limit)
+        .load::<FindHit>(conn)
+        .map_err(|e| e.to_string().into())
+}
+
+/// Turn text into an FTS5 match expression of quoted terms.
+fn match_expression(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"", word))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
+}
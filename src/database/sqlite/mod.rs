@@ -1,7 +1,11 @@
 #![allow(unused)]
 
+mod author;
+mod bookmark;
+mod find;
 mod quote;
 mod search;
+mod snapshot;
 mod task;
 
 use std::fmt::format;
@@ -9,10 +13,17 @@ use std::fmt::format;
 use chrono::{DateTime, Local, NaiveDateTime};
 use diesel::{dsl::now, prelude::*};
 
-use super::{DailyQuote, DbResult, NewDailyQuote, NewQuote, Quote, SearchEntry};
+use super::{
+    Author, AuthorAlias, Bookmark, BookmarkTag, DailyQuote, DbResult, NewDailyQuote, NewQuote,
+    Quote, QuoteTag, SearchEntry, Snapshot, SnapshotInfo, Tag,
+};
 
+pub use author::*;
+pub use bookmark::*;
+pub use find::*;
 pub use quote::*;
 pub use search::*;
+pub use snapshot::*;
 pub use task::*;
 
 /// Create a connection to the sqlite database.
@@ -31,3 +42,11 @@ fn random_i32(max: i32) -> i32 {
     let mut rng = rand::rng();
     rng.random_range(0..max)
 }
+
+/// Escape `\`, `%` and `_` so that they match themselves in a LIKE pattern
+/// with `ESCAPE '\'`.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
@@ -1,30 +1,26 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::format;
 
 use chrono::{DateTime, Local, NaiveDateTime};
 use diesel::{dsl::now, prelude::*};
 
-use crate::database::sqlite::{establish_connection, random_i32};
+use crate::commands::environment::core::QuoteRotation;
+use crate::database::sqlite::{
+    escape_like, establish_connection, find_canonical, random_i32, resolve_author,
+};
 
-use super::{DailyQuote, DbResult, NewDailyQuote, NewQuote, Quote, SearchEntry};
+use super::{DailyQuote, DbResult, NewDailyQuote, NewQuote, Quote, QuoteTag, SearchEntry, Tag};
 
 /// Get all quotes from the database.
+///
+/// Returns an empty list if there are no quotes.
 pub fn get_quotes() -> DbResult<Vec<Quote>> {
     use crate::database::schema::quotes::dsl::*;
 
     let conn = &mut establish_connection()?;
     let result = quotes.select(Quote::as_select()).load(conn);
 
-    let mut res: Vec<Quote> = vec![];
-    if let Ok(items) = &result {
-        if items.is_empty() {
-            return Err("No quotes found".into());
-        }
-        println!("Found {} quotes", items.len());
-        for item in items {
-            res.push(item.clone());
-        }
-    }
-    Ok(res)
+    result.map_err(|e| e.to_string().into())
 }
 
 /// Get a quote by its ID.
@@ -39,90 +35,265 @@ pub fn get_quote(quote_id: i32) -> DbResult<Quote> {
     }
 }
 
-/// Get the most recent daily quote.
-pub fn get_daily_quote() -> DbResult<Quote> {
-    use crate::database::schema::daily_quotes::dsl::*;
+/// Get today's daily quote, picking one if there is none yet.
+///
+/// There is one daily quote per day and tag. If `tag` is provided, today's
+/// untagged daily quote is kept when it carries the tag. Otherwise a daily
+/// quote is picked for the tag from the quotes with that tag using the
+/// `rotation` policy. `days` is the window used by [`QuoteRotation::Recent`].
+pub fn get_daily_quote(tag: Option<&str>, rotation: QuoteRotation, days: u32) -> DbResult<Quote> {
+    use crate::database::schema::daily_quotes;
 
+    let for_tag = tag.map(normalize_tag);
     let conn = &mut establish_connection()?;
-    let result = daily_quotes.order(id.desc()).first::<DailyQuote>(conn);
+    let current_date = Local::now().date_naive();
+    let todays: Vec<DailyQuote> = daily_quotes::table
+        .order(daily_quotes::id.desc())
+        .select(DailyQuote::as_select())
+        .load(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .take_while(|d| d.time_stamp.date() == current_date)
+        .collect();
+
+    //  Keep today's quote for the tag.
+    if let Some(d) = todays.iter().find(|d| d.tag == for_tag)
+        && let Ok(daily) = get_quote(d.quote_id)
+    {
+        return Ok(daily);
+    }
+    //  Or today's untagged quote if it carries the tag.
+    if let Some(t) = &for_tag
+        && let Some(d) = todays.iter().find(|d| d.tag.is_none())
+        && get_quote_tags(d.quote_id)?.contains(t)
+        && let Ok(daily) = get_quote(d.quote_id)
+    {
+        return Ok(daily);
+    }
 
-    match result {
-        //  If there is a valid daily quote, check if it is from today.
-        //  If it is not from today, get a new random quote and insert it as the daily quote.
-        Ok(q) => {
-            let current_date = chrono::Local::now().date_naive();
-            let recent_date = q.time_stamp.date();
-            if recent_date == current_date {
-                let new_quote = get_quote(q.quote_id)?;
-                Ok(new_quote)
+    //  Otherwise draw a new daily quote.
+    draw_daily_quote(tag, rotation, days)
+}
+
+/// Pick a new daily quote according to a rotation policy and insert it.
+fn draw_daily_quote(tag: Option<&str>, rotation: QuoteRotation, days: u32) -> DbResult<Quote> {
+    let (new_quote, deck_start) = pick_daily_quote(tag, rotation, days)?;
+    let new_daily_quote = NewDailyQuote {
+        quote_id: new_quote.id,
+        time_stamp: Local::now().naive_local(),
+        tag: tag.map(normalize_tag),
+        deck_start,
+    };
+    insert_daily_quote(new_daily_quote)?;
+    Ok(new_quote)
+}
+
+/// Pick a new daily quote according to a rotation policy, returning whether
+/// it starts a new deck.
+///
+/// Only daily quotes picked for the same tag count as history. If the policy
+/// excludes every quote, any quote other than the most recent daily quote is
+/// picked.
+fn pick_daily_quote(
+    tag: Option<&str>,
+    rotation: QuoteRotation,
+    days: u32,
+) -> DbResult<(Quote, bool)> {
+    use crate::database::schema::daily_quotes;
+
+    let pool = get_quote_pool(tag)?;
+    let pool_ids: HashSet<i32> = pool.iter().map(|q| q.id).collect();
+    let for_tag = tag.map(normalize_tag);
+
+    let conn = &mut establish_connection()?;
+    //  Daily quote history for the tag, most recent first.
+    let history: Vec<DailyQuote> = daily_quotes::table
+        .order(daily_quotes::id.desc())
+        .select(DailyQuote::as_select())
+        .load(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|d| d.tag == for_tag)
+        .collect();
+
+    let mut deck_start = false;
+    let excluded: HashSet<i32> = match rotation {
+        QuoteRotation::Random => HashSet::new(),
+        QuoteRotation::Recent => {
+            let cutoff = Local::now().naive_local() - chrono::Duration::days(days as i64);
+            history
+                .iter()
+                .filter(|d| d.time_stamp >= cutoff)
+                .map(|d| d.quote_id)
+                .collect()
+        }
+        //  The current deck holds the draws since the one that started it.
+        //  Quotes added to the pool since are drawn in it too, and quotes
+        //  removed from the pool are ignored.
+        QuoteRotation::Deck => {
+            let start = history
+                .iter()
+                .position(|d| d.deck_start)
+                .map_or(history.len(), |i| i + 1);
+            let drawn: HashSet<i32> = history[..start]
+                .iter()
+                .map(|d| d.quote_id)
+                .filter(|q_id| pool_ids.contains(q_id))
+                .collect();
+            if drawn.is_empty() || drawn == pool_ids {
+                deck_start = true;
+                //  Do not start a new deck with the last quote of the previous one.
+                history.iter().take(1).map(|d| d.quote_id).collect()
             } else {
-                let rand_quote = get_quote_random()?;
-                let new_daily_quote = NewDailyQuote {
-                    quote_id: rand_quote.id,
-                    time_stamp: Local::now().naive_local(),
-                };
-                insert_daily_quote(new_daily_quote)?;
-                Ok(rand_quote)
+                drawn
             }
         }
-        //  If there is no daily quote, get a new random quote and insert it as the daily quote.
-        Err(e) => {
-            let rand_quote = get_quote_random()?;
-            let new_daily_quote = NewDailyQuote {
-                quote_id: rand_quote.id,
-                time_stamp: Local::now().naive_local(),
-            };
-            insert_daily_quote(new_daily_quote)?;
-            Ok(rand_quote)
+    };
+
+    let mut candidates: Vec<&Quote> = pool.iter().filter(|q| !excluded.contains(&q.id)).collect();
+    if candidates.is_empty() {
+        let last_id = history.first().map(|d| d.quote_id);
+        candidates = pool.iter().filter(|q| Some(q.id) != last_id).collect();
+    }
+    if candidates.is_empty() {
+        candidates = pool.iter().collect();
+    }
+
+    let index = random_i32(candidates.len() as i32);
+    Ok((candidates[index as usize].clone(), deck_start))
+}
+
+/// Get the quotes with a tag, or every quote if `tag` is `None`.
+///
+/// Returns an error if no quotes are found.
+fn get_quote_pool(tag: Option<&str>) -> DbResult<Vec<Quote>> {
+    use crate::database::schema::quotes::dsl::*;
+
+    let q = match tag {
+        Some(t) => get_quotes_by_tag(t)?,
+        None => {
+            let conn = &mut establish_connection()?;
+            quotes
+                .select(Quote::as_select())
+                .load::<Quote>(conn)
+                .map_err(|e| e.to_string())?
         }
+    };
+
+    if q.is_empty() {
+        return match tag {
+            Some(t) => Err(format!("No quotes found with tag: {}", t).into()),
+            None => Err("No quotes found".into()),
+        };
     }
+    Ok(q)
 }
 
 /// Gets a random quote from the database.
-pub fn get_quote_random() -> DbResult<Quote> {
+///
+/// If `tag` is provided, only quotes with that tag are considered.
+pub fn get_quote_random(tag: Option<&str>) -> DbResult<Quote> {
+    let q = get_quote_pool(tag)?;
+    let index = random_i32(q.len() as i32);
+    Ok(q[index as usize].clone())
+}
+
+/// Get the daily quote history, most recent first.
+///
+/// If `days` is provided, only daily quotes from the last `days` days are returned.
+pub fn get_daily_history(days: Option<u32>) -> DbResult<Vec<(DailyQuote, Quote)>> {
+    use crate::database::schema::{daily_quotes, quotes};
+
+    let conn = &mut establish_connection()?;
+    let mut query = daily_quotes::table
+        .inner_join(quotes::table)
+        .order(daily_quotes::id.desc())
+        .select((DailyQuote::as_select(), Quote::as_select()))
+        .into_boxed();
+
+    if let Some(d) = days {
+        let cutoff = Local::now().naive_local() - chrono::Duration::days(d as i64);
+        query = query.filter(daily_quotes::time_stamp.ge(cutoff));
+    }
+
+    query.load(conn).map_err(|e| e.to_string().into())
+}
+
+/// Search quotes whose text or author contains `text`.
+///
+/// The match is case-insensitive. `%` and `_` in `text` match themselves.
+pub fn search_quotes(text: &str) -> DbResult<Vec<Quote>> {
+    use crate::database::schema::quotes::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let pattern = format!("%{}%", escape_like(text));
+    let result = quotes
+        .filter(
+            quote
+                .like(&pattern)
+                .escape('\\')
+                .or(author.like(&pattern).escape('\\')),
+        )
+        .select(Quote::as_select())
+        .load(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Update the text and/or author of a quote.
+///
+/// Fields that are `None` are left unchanged.
+pub fn update_quote(
+    quote_id: i32,
+    new_quote: Option<String>,
+    new_author: Option<String>,
+) -> DbResult<()> {
     use crate::database::schema::quotes::dsl::*;
 
     let conn = &mut establish_connection()?;
-    let result = quotes.select(Quote::as_select()).load::<Quote>(conn);
+    let mut current = quotes
+        .find(quote_id)
+        .first::<Quote>(conn)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(q) = new_quote {
+        current.quote = q;
+    }
+    if let Some(a) = new_author {
+        current.author = resolve_author(conn, &a)?;
+    }
+
+    let result = diesel::update(quotes.find(quote_id))
+        .set(&current)
+        .execute(conn);
 
     match result {
-        Ok(q) => {
-            let index = random_i32(q.len() as i32);
-            Ok(q[index as usize].clone())
-        }
+        Ok(_) => Ok(()),
         Err(e) => Err(e.to_string().into()),
     }
 }
 
 /// Insert a new quote.
-pub fn insert_quote(new_quote: NewQuote) -> DbResult<()> {
+///
+/// Returns the ID of the new quote.
+pub fn insert_quote(new_quote: NewQuote) -> DbResult<i32> {
     use crate::database::schema::quotes::dsl::*;
 
     let conn = &mut establish_connection()?;
 
-    let mut new_id = 0;
-
     // Get the last quote ID.
     let quote_res = quotes.select(Quote::as_select()).load(conn);
 
     // If there are quotes in the database, get the last quote ID.
-    if quote_res.is_ok() {
-        let quotes_vec = quote_res.unwrap();
-
-        if !quotes_vec.is_empty() {
-            let last_quote = quotes_vec.iter().last();
-            // println!("Last quote: {:?}", last_quote);
-            if last_quote.is_some() {
-                let last_id = last_quote.unwrap().id;
-                new_id = last_id + 1;
-            }
-        }
-    }
+    let new_id = match quote_res {
+        Ok(quotes_vec) => quotes_vec.iter().last().map(|q| q.id + 1).unwrap_or(0),
+        Err(_) => 0,
+    };
 
     let final_quote = Quote {
         id: new_id,
         quote: new_quote.quote.clone(),
-        author: new_quote.author.clone(),
+        author: resolve_author(conn, &new_quote.author)?,
     };
 
     let result = diesel::insert_into(quotes)
@@ -130,37 +301,75 @@ pub fn insert_quote(new_quote: NewQuote) -> DbResult<()> {
         .execute(conn);
 
     match result {
-        Ok(_) => Ok(()),
+        Ok(_) => Ok(final_quote.id),
         Err(e) => Err(e.to_string().into()),
     }
 }
 
+/// Insert quotes and their tags in one transaction.
+///
+/// Each quote is passed to `is_new` with the canonical spelling of its
+/// author, and skipped if it returns false. Nothing is inserted if any quote
+/// fails. Returns the IDs of the new quotes.
+pub fn insert_quotes(
+    new_quotes: Vec<(NewQuote, Vec<String>)>,
+    mut is_new: impl FnMut(&str, &str) -> bool,
+) -> DbResult<Vec<i32>> {
+    use crate::database::schema::quotes::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    conn.transaction(|conn| {
+        let last_id = quotes
+            .select(diesel::dsl::max(id))
+            .first::<Option<i32>>(conn)?;
+        let mut next_id = last_id.map(|i| i + 1).unwrap_or(0);
+        let mut ids = vec![];
+
+        for (new_quote, tag_names) in new_quotes {
+            let canonical = find_canonical(conn, &new_quote.author)?
+                .map(|a| a.name)
+                .unwrap_or_else(|| new_quote.author.trim().to_string());
+            if !is_new(&new_quote.quote, &canonical) {
+                continue;
+            }
+            let final_quote = Quote {
+                id: next_id,
+                quote: new_quote.quote,
+                author: resolve_author(conn, &new_quote.author)?,
+            };
+            diesel::insert_into(quotes)
+                .values(&final_quote)
+                .execute(conn)?;
+            for tag_name in tag_names.iter().filter(|t| !t.trim().is_empty()) {
+                let t_id = get_or_insert_tag(conn, &normalize_tag(tag_name))?;
+                insert_quote_tag(conn, next_id, t_id)?;
+            }
+            ids.push(next_id);
+            next_id += 1;
+        }
+        Ok(ids)
+    })
+}
+
 /// Insert a new daily quote.
 pub fn insert_daily_quote(new_daily_quote: NewDailyQuote) -> DbResult<()> {
     use crate::database::schema::daily_quotes::dsl::*;
 
     let conn = &mut establish_connection()?;
 
-    let mut new_id = 0;
-
     let daily_res = daily_quotes.select(DailyQuote::as_select()).load(conn);
 
-    if daily_res.is_ok() {
-        let daily_vec = daily_res.unwrap();
-
-        if !daily_vec.is_empty() {
-            let last_daily = daily_vec.iter().last();
-            if last_daily.is_some() {
-                let last_id = last_daily.unwrap().id;
-                new_id = last_id + 1;
-            }
-        }
-    }
+    let new_id = match daily_res {
+        Ok(daily_vec) => daily_vec.iter().last().map(|d| d.id + 1).unwrap_or(0),
+        Err(_) => 0,
+    };
 
     let final_daily_quote = DailyQuote {
         id: new_id,
         quote_id: new_daily_quote.quote_id,
         time_stamp: new_daily_quote.time_stamp,
+        tag: new_daily_quote.tag,
+        deck_start: new_daily_quote.deck_start,
     };
 
     let result = diesel::insert_into(daily_quotes)
@@ -174,14 +383,393 @@ pub fn insert_daily_quote(new_daily_quote: NewDailyQuote) -> DbResult<()> {
 }
 
 /// Delete a quote matching its ID.
+///
+/// The quote's tags and daily quote entries are removed with it.
 pub fn delete_quote(id: i32) -> DbResult<()> {
-    use crate::database::schema::quotes::dsl::*;
+    use crate::database::schema::{daily_quotes, quote_tags, quotes};
+
+    let conn = &mut establish_connection()?;
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(quote_tags::table.filter(quote_tags::quote_id.eq(id))).execute(conn)?;
+        diesel::delete(daily_quotes::table.filter(daily_quotes::quote_id.eq(id))).execute(conn)?;
+        diesel::delete(quotes::table.find(id)).execute(conn)
+    });
+
+    match result {
+        Ok(0) => Err(format!("No quote found with ID: {}", id).into()),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Normalize a tag name so that tags are matched case-insensitively.
+pub(super) fn normalize_tag(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Get all tags.
+pub fn get_tags() -> DbResult<Vec<Tag>> {
+    use crate::database::schema::tags::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = tags.order(name.asc()).select(Tag::as_select()).load(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get the tags of quotes with the number of quotes that carry them.
+///
+/// Tags only used by bookmarks are left out.
+pub fn get_tag_counts() -> DbResult<Vec<(String, i64)>> {
+    use crate::database::schema::{quote_tags, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = tags::table
+        .inner_join(quote_tags::table)
+        .group_by(tags::name)
+        .order(tags::name.asc())
+        .select((tags::name, diesel::dsl::count(quote_tags::id)))
+        .load::<(String, i64)>(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get the names of the tags applied to a quote.
+pub fn get_quote_tags(quote_id: i32) -> DbResult<Vec<String>> {
+    use crate::database::schema::{quote_tags, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = quote_tags::table
+        .inner_join(tags::table)
+        .filter(quote_tags::quote_id.eq(quote_id))
+        .order(tags::name.asc())
+        .select(tags::name)
+        .load::<String>(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get the tag names of every tagged quote, keyed by quote ID.
+pub fn get_all_quote_tags() -> DbResult<HashMap<i32, Vec<String>>> {
+    use crate::database::schema::{quote_tags, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = quote_tags::table
+        .inner_join(tags::table)
+        .order(tags::name.asc())
+        .select((quote_tags::quote_id, tags::name))
+        .load::<(i32, String)>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut map: HashMap<i32, Vec<String>> = HashMap::new();
+    for (q_id, name) in result {
+        map.entry(q_id).or_default().push(name);
+    }
+    Ok(map)
+}
+
+/// Get all quotes with a tag.
+pub fn get_quotes_by_tag(tag_name: &str) -> DbResult<Vec<Quote>> {
+    use crate::database::schema::{quote_tags, quotes, tags};
+
+    let conn = &mut establish_connection()?;
+    let result = quotes::table
+        .inner_join(quote_tags::table.inner_join(tags::table))
+        .filter(tags::name.eq(normalize_tag(tag_name)))
+        .select(Quote::as_select())
+        .load(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Get the ID of a tag, creating the tag if it does not exist.
+pub(super) fn get_or_insert_tag(conn: &mut SqliteConnection, tag_name: &str) -> DbResult<i32> {
+    use crate::database::schema::tags::dsl::*;
+
+    let existing = tags
+        .filter(name.eq(tag_name))
+        .select(id)
+        .first::<i32>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(tag_id) = existing {
+        return Ok(tag_id);
+    }
+
+    let last_id = tags
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|e| e.to_string())?;
+    let new_tag = Tag {
+        id: last_id.map(|i| i + 1).unwrap_or(0),
+        name: tag_name.to_string(),
+    };
+
+    let result = diesel::insert_into(tags).values(&new_tag).execute(conn);
+
+    match result {
+        Ok(_) => Ok(new_tag.id),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Apply a tag to a quote.
+///
+/// The tag is created if it does not exist. Tagging a quote twice with the
+/// same tag has no effect.
+pub fn tag_quote(q_id: i32, tag_name: &str) -> DbResult<()> {
+    get_quote(q_id)?;
+
+    let conn = &mut establish_connection()?;
+    let t_id = get_or_insert_tag(conn, &normalize_tag(tag_name))?;
+    insert_quote_tag(conn, q_id, t_id)
+}
+
+/// Link a quote to a tag, unless they are already linked.
+fn insert_quote_tag(conn: &mut SqliteConnection, q_id: i32, t_id: i32) -> DbResult<()> {
+    use crate::database::schema::quote_tags::dsl::*;
+
+    let exists = quote_tags
+        .filter(quote_id.eq(q_id).and(tag_id.eq(t_id)))
+        .select(QuoteTag::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    if exists.is_some() {
+        return Ok(());
+    }
+
+    let last_id = quote_tags
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|e| e.to_string())?;
+    let relation = QuoteTag {
+        id: last_id.map(|i| i + 1).unwrap_or(0),
+        quote_id: q_id,
+        tag_id: t_id,
+    };
+
+    let result = diesel::insert_into(quote_tags)
+        .values(&relation)
+        .execute(conn);
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Remove a tag from a quote.
+pub fn untag_quote(q_id: i32, tag_name: &str) -> DbResult<()> {
+    use crate::database::schema::{quote_tags, tags};
 
     let conn = &mut establish_connection()?;
-    let result = diesel::delete(quotes.find(id)).execute(conn);
+    let t_id = tags::table
+        .filter(tags::name.eq(normalize_tag(tag_name)))
+        .select(tags::id)
+        .first::<i32>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let Some(t_id) = t_id else {
+        return Err(format!("No tag found with name: {}", tag_name).into());
+    };
+
+    let result = diesel::delete(
+        quote_tags::table.filter(
+            quote_tags::quote_id
+                .eq(q_id)
+                .and(quote_tags::tag_id.eq(t_id)),
+        ),
+    )
+    .execute(conn);
 
     match result {
         Ok(_) => Ok(()),
         Err(e) => Err(e.to_string().into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    /// Tag seeded quotes so that they form a pool of their own.
+    fn pool(tag: &str, ids: &[i32]) {
+        for q_id in ids {
+            tag_quote(*q_id, tag).unwrap();
+        }
+    }
+
+    fn draw(tag: &str, rotation: QuoteRotation, count: usize) -> Vec<i32> {
+        (0..count)
+            .map(|_| draw_daily_quote(Some(tag), rotation, 30).unwrap().id)
+            .collect()
+    }
+
+    fn set(ids: &[i32]) -> HashSet<i32> {
+        ids.iter().copied().collect()
+    }
+
+    #[test]
+    fn deck_draws_every_quote_once() {
+        let _env = TestEnv::new();
+        pool("deck", &[0, 1, 2]);
+
+        let first = draw("deck", QuoteRotation::Deck, 3);
+        assert_eq!(set(&first), set(&[0, 1, 2]));
+        let second = draw("deck", QuoteRotation::Deck, 3);
+        assert_eq!(set(&second), set(&[0, 1, 2]));
+        assert_ne!(first[2], second[0]);
+    }
+
+    #[test]
+    fn deck_follows_changes_to_the_pool() {
+        let _env = TestEnv::new();
+        pool("deck", &[0, 1, 2]);
+        let mut deck = draw("deck", QuoteRotation::Deck, 1);
+
+        //  A quote added mid-deck is drawn in the current deck.
+        pool("deck", &[3]);
+        deck.extend(draw("deck", QuoteRotation::Deck, 3));
+        assert_eq!(set(&deck), set(&[0, 1, 2, 3]));
+
+        //  A quote removed mid-deck neither repeats nor skips the others.
+        let next = draw("deck", QuoteRotation::Deck, 1)[0];
+        let removed = (0..4).find(|q_id| *q_id != next).unwrap();
+        untag_quote(removed, "deck").unwrap();
+        let rest = draw("deck", QuoteRotation::Deck, 2);
+        let mut left = set(&[0, 1, 2, 3]);
+        left.remove(&next);
+        left.remove(&removed);
+        assert_eq!(set(&rest), left);
+    }
+
+    #[test]
+    fn recent_skips_recent_quotes() {
+        let _env = TestEnv::new();
+        pool("recent", &[4, 5, 6]);
+
+        let drawn = draw("recent", QuoteRotation::Recent, 3);
+        assert_eq!(set(&drawn), set(&[4, 5, 6]));
+        //  Once every quote is recent, only the last one is skipped.
+        let next = draw("recent", QuoteRotation::Recent, 1);
+        assert_ne!(next[0], drawn[2]);
+        assert!(set(&[4, 5, 6]).contains(&next[0]));
+    }
+
+    #[test]
+    fn random_draws_from_the_pool() {
+        let _env = TestEnv::new();
+        pool("random", &[7, 8]);
+
+        let drawn = draw("random", QuoteRotation::Random, 10);
+        assert!(drawn.iter().all(|q_id| [7, 8].contains(q_id)));
+        assert!(draw_daily_quote(Some("missing"), QuoteRotation::Random, 30).is_err());
+    }
+
+    fn add(text: &str, by: &str) -> i32 {
+        insert_quote(NewQuote {
+            quote: text.to_string(),
+            author: by.to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn search_matches_percent_and_underscore_literally() {
+        let _env = TestEnv::new();
+        let percent = add("Give 110% every day.", "Coach");
+        let underscore = add("Name it snake_case.", "Pythonista");
+
+        let found = |text: &str| -> Vec<i32> {
+            let mut ids: Vec<i32> = search_quotes(text).unwrap().iter().map(|q| q.id).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(found("110%"), [percent]);
+        assert_eq!(found("%"), [percent]);
+        assert_eq!(found("e_c"), [underscore]);
+        assert!(found("snake%case").is_empty());
+        assert_eq!(found("PYTHONISTA"), [underscore]);
+    }
+
+    #[test]
+    fn tags_are_normalized() {
+        let _env = TestEnv::new();
+        let q_id = add("Tagged quote.", "Someone");
+        tag_quote(q_id, " Wisdom ").unwrap();
+        tag_quote(q_id, "wisdom").unwrap();
+
+        assert_eq!(get_quote_tags(q_id).unwrap(), ["wisdom"]);
+        let tagged: Vec<i32> = get_quotes_by_tag("WISDOM")
+            .unwrap()
+            .iter()
+            .map(|q| q.id)
+            .collect();
+        assert_eq!(tagged, [q_id]);
+        assert!(
+            get_tag_counts()
+                .unwrap()
+                .contains(&("wisdom".to_string(), 1))
+        );
+
+        untag_quote(q_id, "Wisdom").unwrap();
+        assert!(get_quote_tags(q_id).unwrap().is_empty());
+        assert!(get_quotes_by_tag("wisdom").unwrap().is_empty());
+    }
+
+    #[test]
+    fn edit_and_delete() {
+        let _env = TestEnv::new();
+        let q_id = add("First draft.", "Writer");
+        update_quote(q_id, Some("Final draft.".to_string()), None).unwrap();
+        let edited = get_quote(q_id).unwrap();
+        assert_eq!(edited.quote, "Final draft.");
+        assert_eq!(edited.author, "Writer");
+
+        tag_quote(q_id, "drafts").unwrap();
+        get_daily_quote(Some("drafts"), QuoteRotation::Random, 30).unwrap();
+        delete_quote(q_id).unwrap();
+        assert!(get_quote(q_id).is_err());
+        assert!(get_quotes_by_tag("drafts").unwrap().is_empty());
+        assert!(get_daily_history(None).unwrap().is_empty());
+        assert!(delete_quote(q_id).is_err());
+    }
+
+    #[test]
+    fn one_daily_quote_per_tag() {
+        let _env = TestEnv::new();
+        pool("a", &[0, 1]);
+        pool("b", &[2, 3]);
+
+        let untagged = get_daily_quote(None, QuoteRotation::Random, 30).unwrap();
+        let a = get_daily_quote(Some("a"), QuoteRotation::Random, 30).unwrap();
+        let b = get_daily_quote(Some("B"), QuoteRotation::Random, 30).unwrap();
+        assert!([0, 1].contains(&a.id));
+        assert!([2, 3].contains(&b.id));
+
+        //  The same quotes are kept for the rest of the day.
+        for _ in 0..3 {
+            let rotation = QuoteRotation::Random;
+            assert_eq!(get_daily_quote(None, rotation, 30).unwrap().id, untagged.id);
+            assert_eq!(get_daily_quote(Some("a"), rotation, 30).unwrap().id, a.id);
+            assert_eq!(get_daily_quote(Some("b"), rotation, 30).unwrap().id, b.id);
+        }
+    }
+
+    #[test]
+    fn untagged_daily_quote_is_kept_for_its_tags() {
+        let _env = TestEnv::new();
+        let untagged = get_daily_quote(None, QuoteRotation::Random, 30).unwrap();
+        tag_quote(untagged.id, "today").unwrap();
+        tag_quote((untagged.id + 1) % 12, "today").unwrap();
+
+        let tagged = get_daily_quote(Some("today"), QuoteRotation::Random, 30).unwrap();
+        assert_eq!(tagged.id, untagged.id);
+        assert_eq!(get_daily_history(None).unwrap().len(), 1);
+    }
+}
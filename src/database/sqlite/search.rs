@@ -1,18 +1,20 @@
 use std::fmt::format;
 
-use chrono::{DateTime, Local, NaiveDateTime};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
 use diesel::{dsl::now, prelude::*};
 
-use crate::database::sqlite::establish_connection;
+use crate::database::schema::searches;
+use crate::database::sqlite::{escape_like, establish_connection};
+use diesel::sqlite::Sqlite;
 
 use super::{DailyQuote, DbResult, NewDailyQuote, NewQuote, Quote, SearchEntry};
 
 /// Get a search by its ID.
-pub fn get_search(id: i32) -> DbResult<SearchEntry> {
+pub fn get_search(search_id: i32) -> DbResult<SearchEntry> {
     use crate::database::schema::searches::dsl::*;
 
     let conn = &mut establish_connection()?;
-    let result = searches.find(id).first::<SearchEntry>(conn);
+    let result = searches.find(search_id).first::<SearchEntry>(conn);
 
     match result {
         Ok(q) => Ok(q),
@@ -20,13 +22,19 @@ pub fn get_search(id: i32) -> DbResult<SearchEntry> {
     }
 }
 
-/// Get a search by its query.
+/// Get the searches whose query starts with `query_str`.
+///
+/// `%` and `_` in the prefix match themselves.
 pub fn get_search_by_query(query_str: String) -> DbResult<Vec<SearchEntry>> {
     use crate::database::schema::searches::dsl::*;
 
     let conn = &mut establish_connection()?;
     let result = searches
-        .filter(query.like(&format!("{}%", query_str)))
+        .filter(
+            query
+                .like(format!("{}%", escape_like(&query_str)))
+                .escape('\\'),
+        )
         .load::<SearchEntry>(conn);
 
     match result {
@@ -35,135 +43,158 @@ pub fn get_search_by_query(query_str: String) -> DbResult<Vec<SearchEntry>> {
     }
 }
 
-/// Get a search by its query, website, and allintext.
-pub fn get_search_by(
-    query_str: Option<String>,
-    site_str: Option<String>,
-    allintext_str: Option<String>,
-) -> DbResult<Vec<SearchEntry>> {
+/// Filters for searching the search history.
+///
+/// Every filter that is set must match. Text filters match any part of the
+/// stored value, and `%` and `_` in them match themselves.
+#[derive(Debug, Default, Clone)]
+pub struct SearchFilter {
+    pub query: Option<String>,
+    pub site: Option<String>,
+    pub allintext: Option<String>,
+    pub exact: Option<String>,
+    pub exclude: Option<String>,
+    pub filetype: Option<String>,
+    pub intitle: Option<String>,
+    pub inurl: Option<String>,
+    pub any_of: Option<String>,
+    /// Only match searches for results published after a date at or later
+    /// than this one.
+    pub after: Option<NaiveDate>,
+    /// Only match searches for results published before a date at or
+    /// earlier than this one.
+    pub before: Option<NaiveDate>,
+    /// Matches the profile name exactly.
+    pub profile: Option<String>,
+    /// Only match starred searches.
+    pub starred: bool,
+    /// Only match searches made at or after this time.
+    pub from: Option<NaiveDateTime>,
+    /// Only match searches made at or before this time.
+    pub to: Option<NaiveDateTime>,
+    /// Maximum number of searches to return.
+    pub limit: Option<i64>,
+    /// Number of matching searches to skip.
+    pub offset: Option<i64>,
+}
+
+/// Build a query for the searches matching every filter that is set.
+///
+/// The limit and offset are not applied.
+fn filtered(filter: &SearchFilter) -> searches::BoxedQuery<'static, Sqlite> {
     use crate::database::schema::searches::dsl::*;
 
-    let conn = &mut establish_connection()?;
-    let result;
-    if query_str.is_some() && site_str.is_none() && allintext_str.is_none() {
-        result = searches
-            .filter(query.like(&format!("%{}%", query_str.unwrap())))
-            .load::<SearchEntry>(conn);
-    } else if query_str.is_some() && site_str.is_none() && allintext_str.is_some() {
-        result = searches
-            .filter(
-                query
-                    .like(&format!("%{}%", query_str.unwrap()))
-                    .and(allintext.like(&format!("%{}%", allintext_str.unwrap_or("".to_string())))),
-            )
-            .load(conn);
-    } else if query_str.is_some() && site_str.is_some() && allintext_str.is_none() {
-        result = searches
-            .filter(
-                query
-                    .like(&format!("%{}%", query_str.unwrap()))
-                    .and(website.like(&format!("%{}%", site_str.unwrap_or("".to_string())))),
-            )
-            .load(conn);
-    } else if query_str.is_some() && site_str.is_some() && allintext_str.is_some() {
-        result = searches
-            .filter(
-                query
-                    .like(&format!("%{}%", query_str.unwrap()))
-                    .and(website.like(&format!("%{}%", site_str.unwrap())))
-                    .and(allintext.like(&format!("%{}%", allintext_str.unwrap()))),
-            )
-            .load(conn);
-    } else if site_str.is_some() && allintext_str.is_none() {
-        result = searches
-            .filter(website.like(&format!("%{}%", site_str.unwrap())))
-            .load(conn);
-    } else if site_str.is_some() && allintext_str.is_some() {
-        result = searches
-            .filter(
-                website
-                    .like(&format!("%{}%", site_str.unwrap()))
-                    .and(allintext.like(&format!("%{}%", allintext_str.unwrap()))),
-            )
-            .load(conn);
-    } else if allintext_str.is_some() {
-        result = searches
-            .filter(allintext.like(&format!("%{}%", allintext_str.unwrap())))
-            .load(conn);
-    } else {
-        result = searches.load(conn);
+    let mut sql = searches.into_boxed();
+    let pattern = |s: &String| format!("%{}%", escape_like(s));
+
+    if let Some(s) = &filter.query {
+        sql = sql.filter(query.like(pattern(s)).escape('\\'));
     }
-    match result {
-        Ok(q) => Ok(q),
-        Err(e) => Err(e.to_string().into()),
+    if let Some(s) = &filter.site {
+        sql = sql.filter(website.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.allintext {
+        sql = sql.filter(allintext.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.exact {
+        sql = sql.filter(exact.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.exclude {
+        sql = sql.filter(exclude.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.filetype {
+        sql = sql.filter(filetype.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.intitle {
+        sql = sql.filter(intitle.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.inurl {
+        sql = sql.filter(inurl.like(pattern(s)).escape('\\'));
+    }
+    if let Some(s) = &filter.any_of {
+        sql = sql.filter(any_of.like(pattern(s)).escape('\\'));
+    }
+    if let Some(date) = filter.after {
+        sql = sql.filter(after.ge(date));
+    }
+    if let Some(date) = filter.before {
+        sql = sql.filter(before.le(date));
     }
+    if let Some(s) = &filter.profile {
+        sql = sql.filter(profile.eq(s.clone()));
+    }
+    if filter.starred {
+        sql = sql.filter(starred.eq(true));
+    }
+    if let Some(from) = filter.from {
+        sql = sql.filter(time_stamp.ge(from));
+    }
+    if let Some(to) = filter.to {
+        sql = sql.filter(time_stamp.le(to));
+    }
+    sql
 }
 
-/// Get all search history items within a range.
-///
-/// If `from` is empty, all items before `to` are returned.
-/// If `to` is empty, all items after `from` are returned.
-/// If both are empty, all items are returned.
-pub fn get_search_range(from: String, to: String) -> DbResult<Vec<SearchEntry>> {
+/// Get the searches matching every filter that is set, newest first.
+pub fn get_search_by(filter: &SearchFilter) -> DbResult<Vec<SearchEntry>> {
     use crate::database::schema::searches::dsl::*;
 
     let conn = &mut establish_connection()?;
-    let result: Result<Vec<SearchEntry>, diesel::result::Error>;
-
-    if from.is_empty() && to.is_empty() {
-        result = searches.load(conn);
-    } else if from.is_empty() {
-        result = searches.filter(time_stamp.le(to)).load(conn);
-    } else if to.is_empty() {
-        result = searches.filter(time_stamp.ge(from)).load(conn);
-    } else {
-        result = searches
-            .filter(time_stamp.ge(from))
-            .filter(time_stamp.le(to))
-            .load(conn);
+    let mut sql = filtered(filter).order((time_stamp.desc(), id.desc()));
+    if let Some(limit) = filter.limit {
+        sql = sql.limit(limit);
     }
-
-    match result {
-        Ok(q) => Ok(q),
-        Err(e) => Err(e.to_string().into()),
+    if let Some(offset) = filter.offset {
+        sql = sql.offset(offset);
     }
+
+    sql.load::<SearchEntry>(conn)
+        .map_err(|e| e.to_string().into())
 }
 
-/// Insert a new search history item.
-pub fn insert_search(new_search: SearchEntry) -> DbResult<()> {
+/// Count the searches matching every filter that is set.
+///
+/// The limit and offset are ignored.
+pub fn count_searches(filter: &SearchFilter) -> DbResult<i64> {
+    let conn = &mut establish_connection()?;
+    filtered(filter)
+        .count()
+        .get_result(conn)
+        .map_err(|e| e.to_string().into())
+}
+
+/// Get the most recent search history items, newest first.
+pub fn get_recent_searches(count: i64) -> DbResult<Vec<SearchEntry>> {
     use crate::database::schema::searches::dsl::*;
 
-    // println!("Inserting search: {:?}", new_search);
     let conn = &mut establish_connection()?;
+    let result = searches
+        .order((time_stamp.desc(), id.desc()))
+        .limit(count)
+        .load::<SearchEntry>(conn);
 
-    let search_res = searches.select(SearchEntry::as_select()).load(conn);
+    result.map_err(|e| e.to_string().into())
+}
 
-    let mut new_id = 0;
+/// Insert a new search history item.
+///
+/// The ID and time stamp of `new_search` are replaced.
+pub fn insert_search(new_search: SearchEntry) -> DbResult<()> {
+    use crate::database::schema::searches::dsl::*;
 
-    if search_res.is_ok() {
-        let search_vec = search_res.unwrap();
+    let conn = &mut establish_connection()?;
 
-        if !search_vec.is_empty() {
-            let last_search = search_vec.iter().last();
-            if last_search.is_some() {
-                let last_id = last_search.unwrap().id;
-                new_id = last_id + 1;
-            }
-        }
-    } else {
-        return Err("Failed to get search history".into());
-    }
+    let last_id = searches
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|_| "Failed to get search history")?;
 
     let search = SearchEntry {
-        id: new_id,
-        query: new_search.query.clone(),
-        website: new_search.website.clone(),
-        allintext: new_search.allintext.clone(),
+        id: last_id.map(|i| i + 1).unwrap_or(0),
         time_stamp: chrono::Local::now().naive_local(),
+        ..new_search
     };
 
-    // println!("Inserting search: {:?}", search);
-
     let result = diesel::insert_into(searches).values(&search).execute(conn);
 
     match result {
@@ -172,47 +203,174 @@ pub fn insert_search(new_search: SearchEntry) -> DbResult<()> {
     }
 }
 
-/// Delete a search history item matching its ID.
-pub fn delete_search(id: i32) -> DbResult<()> {
+/// Mark a search as a favorite, or remove the mark.
+pub fn star_search(search_id: i32, star: bool) -> DbResult<()> {
     use crate::database::schema::searches::dsl::*;
 
     let conn = &mut establish_connection()?;
-    let result = diesel::delete(searches.find(id)).execute(conn);
+    let result = diesel::update(searches.find(search_id))
+        .set(starred.eq(star))
+        .execute(conn);
 
     match result {
+        Ok(0) => Err(format!("No search found with ID: {}", search_id).into()),
         Ok(_) => Ok(()),
         Err(e) => Err(e.to_string().into()),
     }
 }
 
-/// Delete search history items within a range.
-///
-/// If `from` is empty, all items before `to` are deleted.
-/// If `to` is empty, all items after `from` are deleted.
-/// If both are empty, all items are deleted.
-pub fn delete_search_range(from: String, to: String) -> DbResult<()> {
+/// Delete a search history item matching its ID.
+pub fn delete_search(search_id: i32) -> DbResult<()> {
     use crate::database::schema::searches::dsl::*;
 
     let conn = &mut establish_connection()?;
-    let result: Result<usize, diesel::result::Error>;
-
-    if from.is_empty() && to.is_empty() {
-        result = diesel::delete(searches).execute(conn);
-    } else if from.is_empty() {
-        result = diesel::delete(searches.filter(time_stamp.le(to))).execute(conn);
-    } else if to.is_empty() {
-        result = diesel::delete(searches.filter(time_stamp.ge(from))).execute(conn);
-    } else {
-        result = diesel::delete(
-            searches
-                .filter(time_stamp.ge(from))
-                .filter(time_stamp.le(to)),
-        )
-        .execute(conn);
-    }
+    let result = diesel::delete(searches.find(search_id)).execute(conn);
 
     match result {
         Ok(_) => Ok(()),
         Err(e) => Err(e.to_string().into()),
     }
 }
+
+/// Delete the searches matching every filter that is set.
+///
+/// The limit and offset are ignored. Returns the number of deleted searches.
+pub fn delete_searches(filter: &SearchFilter) -> DbResult<usize> {
+    use crate::database::schema::searches::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let ids: Vec<i32> = filtered(filter)
+        .select(id)
+        .load(conn)
+        .map_err(|e| e.to_string())?;
+    diesel::delete(searches.filter(id.eq_any(ids)))
+        .execute(conn)
+        .map_err(|e| e.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn entry(text: &str, site: Option<&str>) -> SearchEntry {
+        SearchEntry {
+            id: 0,
+            query: text.to_string(),
+            website: site.map(str::to_string),
+            allintext: None,
+            time_stamp: Local::now().naive_local(),
+            url: None,
+            exact: None,
+            exclude: None,
+            filetype: None,
+            intitle: None,
+            inurl: None,
+            after: None,
+            before: None,
+            any_of: None,
+            profile: None,
+            starred: false,
+        }
+    }
+
+    fn queries(filter: &SearchFilter) -> Vec<String> {
+        let mut found: Vec<String> = get_search_by(filter)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.query)
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn filter_by_result_dates() {
+        let _env = TestEnv::new();
+        let searches = [
+            ("none", None, None),
+            ("early", Some("2024-01-01"), Some("2024-03-31")),
+            ("late", Some("2024-06-01"), Some("2024-12-31")),
+        ];
+        for (text, after, before) in searches {
+            insert_search(SearchEntry {
+                after: after.map(date),
+                before: before.map(date),
+                ..entry(text, None)
+            })
+            .unwrap();
+        }
+
+        let after = SearchFilter {
+            after: Some(date("2024-02-01")),
+            ..SearchFilter::default()
+        };
+        assert_eq!(queries(&after), ["late"]);
+
+        let before = SearchFilter {
+            before: Some(date("2024-03-31")),
+            ..SearchFilter::default()
+        };
+        assert_eq!(queries(&before), ["early"]);
+
+        let both = SearchFilter {
+            after: Some(date("2024-01-01")),
+            before: Some(date("2024-12-31")),
+            ..SearchFilter::default()
+        };
+        assert_eq!(queries(&both), ["early", "late"]);
+        assert_eq!(count_searches(&both).unwrap(), 2);
+    }
+
+    #[test]
+    fn wildcards_match_themselves() {
+        let _env = TestEnv::new();
+        insert_search(entry("100% rust", Some("my_site.com"))).unwrap();
+        insert_search(entry("1000 rust", Some("mysite.com"))).unwrap();
+
+        let query = SearchFilter {
+            query: Some("100%".to_string()),
+            ..SearchFilter::default()
+        };
+        assert_eq!(queries(&query), ["100% rust"]);
+
+        let site = SearchFilter {
+            site: Some("y_s".to_string()),
+            ..SearchFilter::default()
+        };
+        assert_eq!(queries(&site), ["100% rust"]);
+
+        let both = SearchFilter {
+            query: Some("rust".to_string()),
+            site: Some("%".to_string()),
+            ..SearchFilter::default()
+        };
+        assert!(queries(&both).is_empty());
+    }
+
+    #[test]
+    fn query_prefix_matches_wildcards_literally() {
+        let _env = TestEnv::new();
+        for text in ["100% rust", "1000 rust", "a_b", "axb", "rust"] {
+            insert_search(entry(text, None)).unwrap();
+        }
+        let prefixed = |prefix: &str| {
+            let mut found: Vec<String> = get_search_by_query(prefix.to_string())
+                .unwrap()
+                .into_iter()
+                .map(|s| s.query)
+                .collect();
+            found.sort();
+            found
+        };
+        assert_eq!(prefixed("100%"), ["100% rust"]);
+        assert_eq!(prefixed("100"), ["100% rust", "1000 rust"]);
+        assert_eq!(prefixed("a_"), ["a_b"]);
+        assert_eq!(prefixed("%"), Vec::<String>::new());
+        assert_eq!(prefixed("").len(), 5);
+    }
+}
@@ -0,0 +1,125 @@
+use std::io::Write;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Nullable, Text};
+use flate2::{Compression, write::GzEncoder};
+
+use crate::database::sqlite::establish_connection;
+
+use super::{DbResult, Snapshot, SnapshotInfo};
+
+/// Number of bytes at the start of the text of a page that are indexed.
+const SUMMARY_SIZE: usize = 4096;
+
+/// Get a saved page by its ID.
+pub fn get_snapshot(snapshot_id: i32) -> DbResult<Snapshot> {
+    use crate::database::schema::snapshots::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = snapshots
+        .find(snapshot_id)
+        .first::<Snapshot>(conn)
+        .optional();
+
+    match result {
+        Ok(Some(s)) => Ok(s),
+        Ok(None) => Err(format!("No saved page found with ID: {}", snapshot_id).into()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Get all saved pages without their content, newest first.
+pub fn get_snapshots() -> DbResult<Vec<SnapshotInfo>> {
+    use crate::database::schema::snapshots::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = snapshots
+        .order((time_stamp.desc(), id.desc()))
+        .select(SnapshotInfo::as_select())
+        .load(conn);
+
+    result.map_err(|e| e.to_string().into())
+}
+
+/// Save the readable text of a page.
+///
+/// The text is stored compressed. Its first few kilobytes are added to the
+/// full text index along with the title and URL. Saving a URL again keeps
+/// the earlier copies.
+pub fn insert_snapshot(
+    page_url: &str,
+    page_title: Option<String>,
+    text: &str,
+    saved: NaiveDateTime,
+) -> DbResult<SnapshotInfo> {
+    use crate::database::schema::snapshots::dsl::*;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(text.as_bytes())?;
+    let compressed = encoder.finish()?;
+
+    let conn = &mut establish_connection()?;
+    let last_id = snapshots
+        .select(diesel::dsl::max(id))
+        .first::<Option<i32>>(conn)
+        .map_err(|e| e.to_string())?;
+    let snapshot = Snapshot {
+        id: last_id.map(|i| i + 1).unwrap_or(0),
+        url: page_url.to_string(),
+        title: page_title,
+        content: compressed,
+        size: i32::try_from(text.len()).map_err(|e| e.to_string())?,
+        time_stamp: saved,
+    };
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(snapshots)
+            .values(&snapshot)
+            .execute(conn)?;
+        diesel::sql_query(
+            "INSERT INTO snapshots_fts(rowid, title, url, summary) VALUES (?, ?, ?, ?)",
+        )
+        .bind::<Integer, _>(snapshot.id)
+        .bind::<Nullable<Text>, _>(&snapshot.title)
+        .bind::<Text, _>(&snapshot.url)
+        .bind::<Text, _>(summary(text))
+        .execute(conn)
+    });
+
+    match result {
+        Ok(_) => Ok(SnapshotInfo {
+            id: snapshot.id,
+            url: snapshot.url,
+            title: snapshot.title,
+            size: snapshot.size,
+            time_stamp: snapshot.time_stamp,
+        }),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// Delete a saved page matching its ID.
+///
+/// A trigger removes it from the full text index.
+pub fn delete_snapshot(snapshot_id: i32) -> DbResult<()> {
+    use crate::database::schema::snapshots::dsl::*;
+
+    let conn = &mut establish_connection()?;
+    let result = diesel::delete(snapshots.find(snapshot_id)).execute(conn);
+
+    match result {
+        Ok(0) => Err(format!("No saved page found with ID: {}", snapshot_id).into()),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string().into()),
+    }
+}
+
+/// The start of a text, at most `SUMMARY_SIZE` bytes long.
+fn summary(text: &str) -> &str {
+    let mut end = text.len().min(SUMMARY_SIZE);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
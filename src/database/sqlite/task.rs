@@ -207,14 +207,16 @@ pub fn contains_task_id(task_id: i32) -> DbResult<bool> {
     }
 }
 
-pub fn get_tasks_by_due_date(due_date: chrono::NaiveDateTime) -> DbResult<Vec<Task>> {
+/// Get the tasks that are due on or before `before`.
+pub fn get_tasks_by_due_date(before: chrono::NaiveDateTime) -> DbResult<Vec<Task>> {
     use crate::database::schema::tasks::dsl::*;
     use crate::database::sqlite::establish_connection;
 
     let mut conn = establish_connection()?;
 
     let res = tasks
-        .filter(due_date.le(due_date))
+        .filter(due_date.le(before))
+        .order(due_date.asc())
         .load::<Task>(&mut conn);
 
     res.map_err(|e| e.to_string().into())
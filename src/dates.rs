@@ -0,0 +1,257 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+/// Description of the accepted date formats, for error messages.
+pub const FORMATS: &str = "YYYY-MM-DD, YYYY-MM-DD HH:MM[:SS], today, yesterday, tomorrow, \
+    or an offset such as 3d, 2w, 12h, 3 days ago or in 2 weeks";
+
+/// Which way an offset without `ago` or `in` counts from now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Past,
+    Future,
+}
+
+/// A parsed date and whether it names a whole day rather than a moment.
+struct Parsed {
+    time: NaiveDateTime,
+    whole_day: bool,
+}
+
+/// Parse the start of a range, such as `--from`.
+///
+/// Offsets count back from now, so `7d` is a week ago. Days start at
+/// midnight.
+pub fn parse_from(input: &str) -> Result<NaiveDateTime, String> {
+    parse(input, Direction::Past).map(|p| p.time)
+}
+
+/// Parse the end of a range, such as `--to`.
+///
+/// Offsets count back from now. Days include their last second, so
+/// `--to 2025-01-31` includes all of January 31st.
+pub fn parse_to(input: &str) -> Result<NaiveDateTime, String> {
+    parse(input, Direction::Past).map(end_of)
+}
+
+/// Parse a due date in local time.
+///
+/// Offsets count forward from now, so `3d` is three days from now. A day
+/// without a time is due at its end.
+pub fn parse_due(input: &str) -> Result<DateTime<Local>, String> {
+    let time = parse(input, Direction::Future).map(end_of)?;
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .ok_or_else(|| format!("{} does not exist in the local time zone.", time))
+}
+
+fn end_of(parsed: Parsed) -> NaiveDateTime {
+    if parsed.whole_day {
+        parsed
+            .time
+            .date()
+            .and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap())
+    } else {
+        parsed.time
+    }
+}
+
+fn parse(input: &str, bare: Direction) -> Result<Parsed, String> {
+    let text = input.trim().to_lowercase();
+    let now = Local::now().naive_local();
+    let day = |date: NaiveDate| Parsed {
+        time: date.and_time(NaiveTime::MIN),
+        whole_day: true,
+    };
+
+    match text.as_str() {
+        "now" => {
+            return Ok(Parsed {
+                time: now,
+                whole_day: false,
+            });
+        }
+        "today" => return Ok(day(now.date())),
+        "yesterday" => return Ok(day(now.date() - Duration::days(1))),
+        "tomorrow" => return Ok(day(now.date() + Duration::days(1))),
+        _ => (),
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+        return Ok(day(date));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dt%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dt%H:%M",
+    ] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(&text, format) {
+            return Ok(Parsed {
+                time,
+                whole_day: false,
+            });
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(input.trim()) {
+        return Ok(Parsed {
+            time: time.with_timezone(&Local).naive_local(),
+            whole_day: false,
+        });
+    }
+
+    let offset = parse_offset(&text, bare)
+        .ok_or_else(|| format!("Invalid date: {}. Use {}.", input, FORMATS))?;
+    let time = now + offset;
+    //  Whole days and weeks refer to the day rather than the time of day.
+    let whole_day = offset.num_seconds() % Duration::days(1).num_seconds() == 0;
+    Ok(Parsed { time, whole_day })
+}
+
+/// Parse an offset from now such as `3d`, `-2w`, `+12h`, `3 days ago` or
+/// `in 2 weeks`.
+fn parse_offset(text: &str, bare: Direction) -> Option<Duration> {
+    let (text, direction) = if let Some(rest) = text.strip_suffix("ago") {
+        (rest.trim(), Direction::Past)
+    } else if let Some(rest) = text.strip_prefix("in ") {
+        (rest.trim(), Direction::Future)
+    } else if let Some(rest) = text.strip_prefix('-') {
+        (rest, Direction::Past)
+    } else if let Some(rest) = text.strip_prefix('+') {
+        (rest, Direction::Future)
+    } else {
+        (text, bare)
+    };
+
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let (count, unit) = text.split_at(split);
+    let count: i64 = count.parse().ok()?;
+    let offset = match unit.trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(count),
+        "h" | "hour" | "hours" => Duration::hours(count),
+        "d" | "day" | "days" => Duration::days(count),
+        "w" | "week" | "weeks" => Duration::weeks(count),
+        _ => return None,
+    };
+    match direction {
+        Direction::Past => Some(-offset),
+        Direction::Future => Some(offset),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        Local::now().date_naive()
+    }
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn iso_dates() {
+        assert_eq!(parse_from("2025-01-31").unwrap(), at("2025-01-31 00:00:00"));
+        assert_eq!(parse_to(" 2025-01-31 ").unwrap(), at("2025-01-31 23:59:59"));
+        assert_eq!(
+            parse_due("2025-01-31").unwrap().naive_local(),
+            at("2025-01-31 23:59:59")
+        );
+    }
+
+    #[test]
+    fn iso_times() {
+        for text in [
+            "2025-01-31 08:30",
+            "2025-01-31T08:30",
+            "2025-01-31t08:30:00",
+        ] {
+            assert_eq!(parse_from(text).unwrap(), at("2025-01-31 08:30:00"));
+            assert_eq!(parse_to(text).unwrap(), at("2025-01-31 08:30:00"));
+        }
+        assert_eq!(
+            parse_from("2025-01-31 08:30:15").unwrap(),
+            at("2025-01-31 08:30:15")
+        );
+
+        let utc = DateTime::parse_from_rfc3339("2025-01-31T08:30:00Z").unwrap();
+        assert_eq!(
+            parse_from("2025-01-31T08:30:00Z").unwrap(),
+            utc.with_timezone(&Local).naive_local()
+        );
+    }
+
+    #[test]
+    fn named_days() {
+        assert_eq!(
+            parse_from("today").unwrap(),
+            today().and_time(NaiveTime::MIN)
+        );
+        assert_eq!(
+            parse_from("Yesterday").unwrap().date(),
+            today() - Duration::days(1)
+        );
+        let tomorrow = parse_due("tomorrow").unwrap().naive_local();
+        assert_eq!(tomorrow.date(), today() + Duration::days(1));
+        assert_eq!(
+            tomorrow.time(),
+            NaiveTime::from_hms_opt(23, 59, 59).unwrap()
+        );
+    }
+
+    #[test]
+    fn bare_offsets_count_back_for_ranges_and_forward_for_due_dates() {
+        assert_eq!(
+            parse_from("3d").unwrap().date(),
+            today() - Duration::days(3)
+        );
+        assert_eq!(parse_to("1w").unwrap().date(), today() - Duration::weeks(1));
+        assert_eq!(
+            parse_to("1w").unwrap().time(),
+            NaiveTime::from_hms_opt(23, 59, 59).unwrap()
+        );
+
+        let due = parse_due("3d").unwrap().naive_local();
+        assert_eq!(due.date(), today() + Duration::days(3));
+        assert_eq!(due.time(), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+    }
+
+    #[test]
+    fn explicit_offsets() {
+        assert_eq!(
+            parse_due("3 days ago").unwrap().date_naive(),
+            today() - Duration::days(3)
+        );
+        assert_eq!(
+            parse_from("in 2 weeks").unwrap().date(),
+            today() + Duration::weeks(2)
+        );
+        assert_eq!(
+            parse_from("+1d").unwrap().date(),
+            today() + Duration::days(1)
+        );
+        assert_eq!(
+            parse_due("-1 day").unwrap().date_naive(),
+            today() - Duration::days(1)
+        );
+
+        //  Hours and minutes keep the time of day.
+        let expected = Local::now().naive_local() + Duration::hours(12);
+        let due = parse_due("12h").unwrap().naive_local();
+        assert!((due - expected).num_seconds().abs() < 60);
+        let from = parse_from("90 minutes ago").unwrap();
+        let expected = Local::now().naive_local() - Duration::minutes(90);
+        assert!((from - expected).num_seconds().abs() < 60);
+    }
+
+    #[test]
+    fn invalid_dates() {
+        for text in ["", "soon", "3x", "d", "2025-13-01", "in weeks"] {
+            let error = parse_from(text).unwrap_err();
+            assert!(error.starts_with("Invalid date"), "{}", error);
+            assert!(error.contains(FORMATS));
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Shared client for web requests.
+//!
+//! Requests run on a small [tokio] runtime owned by the client, so callers
+//! stay synchronous. Failed requests are retried with exponential backoff
+//! when the failure may be temporary.
+
+use std::time::Duration;
+
+use reqwest::{Proxy, StatusCode, header::RETRY_AFTER};
+use tokio::runtime::Runtime;
+
+/// Delay before the first retry. Each retry waits twice as long as the last.
+const BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest delay between retries, including delays asked for by servers.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// An error from a web request.
+#[derive(Debug)]
+pub enum HttpError {
+    /// The client could not be set up, such as with an invalid proxy URL.
+    Client(String),
+    /// The server could not be reached.
+    Connect { url: String, message: String },
+    /// The server did not answer in time.
+    Timeout { url: String },
+    /// The server answered with an error status.
+    Status {
+        url: String,
+        code: u16,
+        /// Body of the response, which often explains the error.
+        body: String,
+    },
+    /// The response could not be read.
+    Body { url: String, message: String },
+}
+
+impl HttpError {
+    fn from_reqwest(url: &str, e: reqwest::Error) -> Self {
+        let url = url.to_string();
+        if e.is_timeout() {
+            HttpError::Timeout { url }
+        } else if e.is_connect() || e.is_request() {
+            HttpError::Connect {
+                url,
+                message: root_cause(&e),
+            }
+        } else {
+            HttpError::Body {
+                url,
+                message: root_cause(&e),
+            }
+        }
+    }
+
+    /// Whether the request may succeed if it is sent again.
+    fn is_temporary(&self) -> bool {
+        match self {
+            HttpError::Connect { .. } | HttpError::Timeout { .. } => true,
+            HttpError::Status { code, .. } => {
+                *code == StatusCode::TOO_MANY_REQUESTS.as_u16() || *code >= 500
+            }
+            HttpError::Client(_) | HttpError::Body { .. } => false,
+        }
+    }
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::Client(message) => write!(f, "Could not set up the web client: {}", message),
+            HttpError::Connect { url, message } => {
+                write!(f, "Could not connect to {}: {}", url, message)
+            }
+            HttpError::Timeout { url } => write!(f, "Timed out waiting for {}", url),
+            HttpError::Status { url, code, .. } => {
+                let reason = StatusCode::from_u16(*code)
+                    .ok()
+                    .and_then(|s| s.canonical_reason())
+                    .unwrap_or("Unknown status");
+                write!(f, "{} answered {} {}", url, code, reason)
+            }
+            HttpError::Body { url, message } => {
+                write!(f, "Could not read the response from {}: {}", url, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// The innermost message of an error, which names the actual problem
+/// rather than the request that failed.
+fn root_cause(e: &(dyn std::error::Error + 'static)) -> String {
+    let mut cause = e;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    cause.to_string()
+}
+
+/// A client for web requests, configured by the `http_*` settings.
+///
+/// The `http_proxy` setting is used for every request. When it is empty, the
+/// `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY` environment variables are
+/// honored instead.
+pub struct HttpClient {
+    client: reqwest::Client,
+    runtime: Runtime,
+    timeout: Duration,
+    retries: u32,
+}
+
+impl HttpClient {
+    /// Create a client from the settings.
+    pub fn new() -> Result<Self, HttpError> {
+        let (timeout, retries, user_agent, proxy) = {
+            let env = crate::ENV.lock().unwrap();
+            (
+                env.http_timeout,
+                env.http_retries,
+                env.http_user_agent.clone(),
+                env.http_proxy.clone(),
+            )
+        };
+
+        let mut builder = reqwest::Client::builder().user_agent(user_agent);
+        if !proxy.trim().is_empty() {
+            let proxy = Proxy::all(proxy.trim()).map_err(|e| {
+                HttpError::Client(format!(
+                    "invalid proxy {}: {}",
+                    proxy.trim(),
+                    root_cause(&e)
+                ))
+            })?;
+            builder = builder.proxy(proxy);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| HttpError::Client(root_cause(&e)))?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| HttpError::Client(e.to_string()))?;
+
+        Ok(HttpClient {
+            client,
+            runtime,
+            timeout: Duration::from_secs(timeout.max(1)),
+            retries,
+        })
+    }
+
+    /// Wait at most `timeout` for each attempt instead of the `http_timeout`
+    /// setting.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry failed requests `retries` times instead of the `http_retries`
+    /// setting.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Get the body of a page as text.
+    pub fn get_text(&self, url: &str) -> Result<String, HttpError> {
+        self.runtime.block_on(self.fetch_text(url))
+    }
+
+    /// Get the body of a page as text, retrying temporary failures.
+    pub async fn fetch_text(&self, url: &str) -> Result<String, HttpError> {
+        let mut attempt = 0;
+        loop {
+            let (error, wait) = match self.try_fetch(url).await {
+                Ok(body) => return Ok(body),
+                Err(failure) => failure,
+            };
+            if attempt >= self.retries || !error.is_temporary() {
+                return Err(error);
+            }
+            let backoff = BACKOFF.saturating_mul(2u32.saturating_pow(attempt));
+            tokio::time::sleep(wait.unwrap_or(backoff).min(MAX_BACKOFF)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a request once.
+    ///
+    /// Failures come with the delay the server asked for before retrying,
+    /// if any.
+    async fn try_fetch(&self, url: &str) -> Result<String, (HttpError, Option<Duration>)> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| (HttpError::from_reqwest(url, e), None))?;
+
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| (HttpError::from_reqwest(url, e), None))?;
+
+        if status.is_success() {
+            Ok(body)
+        } else {
+            let error = HttpError::Status {
+                url: url.to_string(),
+                code: status.as_u16(),
+                body,
+            };
+            Err((error, retry_after))
+        }
+    }
+}
+
+/// Get the body of a page as text with a client from the settings.
+pub fn get_text(url: &str) -> Result<String, HttpError> {
+    HttpClient::new()?.get_text(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::testing::{Reply, StubServer, TestEnv, response};
+
+    fn client(retries: u32) -> HttpClient {
+        HttpClient::new().unwrap().retries(retries)
+    }
+
+    #[test]
+    fn temporary_errors() {
+        let status = |code| HttpError::Status {
+            url: String::new(),
+            code,
+            body: String::new(),
+        };
+        assert!(status(429).is_temporary());
+        assert!(status(500).is_temporary());
+        assert!(status(503).is_temporary());
+        assert!(!status(404).is_temporary());
+        assert!(!status(400).is_temporary());
+        assert!(HttpError::Timeout { url: String::new() }.is_temporary());
+        assert!(
+            HttpError::Connect {
+                url: String::new(),
+                message: String::new()
+            }
+            .is_temporary()
+        );
+        assert!(!HttpError::Client(String::new()).is_temporary());
+    }
+
+    #[test]
+    fn unavailable_is_retried() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![
+            response("503 Service Unavailable", &[], "busy"),
+            response("200 OK", &[], "hello"),
+        ]);
+        assert_eq!(client(1).get_text(&server.url).unwrap(), "hello");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn retries_run_out() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![
+            response("500 Internal Server Error", &[], "one"),
+            response("500 Internal Server Error", &[], "two"),
+            response("200 OK", &[], "hello"),
+        ]);
+        let error = client(1).get_text(&server.url).unwrap_err();
+        assert!(matches!(error, HttpError::Status { code: 500, ref body, .. } if body == "two"));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn not_found_is_not_retried() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![
+            response("404 Not Found", &[], "missing"),
+            response("200 OK", &[], "hello"),
+        ]);
+        let error = client(2).get_text(&server.url).unwrap_err();
+        assert!(matches!(error, HttpError::Status { code: 404, .. }));
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            error.to_string(),
+            format!("{} answered 404 Not Found", server.url)
+        );
+    }
+
+    #[test]
+    fn retry_after_is_honored() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![
+            response("429 Too Many Requests", &[("Retry-After", "1")], ""),
+            response("200 OK", &[], "hello"),
+        ]);
+        let start = Instant::now();
+        assert_eq!(client(1).get_text(&server.url).unwrap(), "hello");
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn slow_servers_time_out() {
+        let _env = TestEnv::new();
+        let server = StubServer::new(vec![Reply::Hang(Duration::from_secs(2))]);
+        let error = client(0)
+            .timeout(Duration::from_millis(200))
+            .get_text(&server.url)
+            .unwrap_err();
+        assert!(matches!(error, HttpError::Timeout { ref url } if *url == server.url));
+    }
+
+    #[test]
+    fn requests_go_through_the_proxy() {
+        let _env = TestEnv::new();
+        let proxy = StubServer::new(vec![response("200 OK", &[], "proxied")]);
+        crate::ENV.lock().unwrap().http_proxy = proxy.url.clone();
+
+        let body = get_text("http://example.invalid/page").unwrap();
+        assert_eq!(body, "proxied");
+        assert!(proxy.requests()[0].starts_with("GET http://example.invalid/page HTTP/1.1"));
+    }
+
+    #[test]
+    fn invalid_proxy() {
+        let _env = TestEnv::new();
+        crate::ENV.lock().unwrap().http_proxy = "http://[::1".to_string();
+        assert!(matches!(HttpClient::new(), Err(HttpError::Client(_))));
+    }
+}
@@ -0,0 +1,233 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Width used when the terminal width cannot be detected.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Maximum width of wrapped prose such as quotes and descriptions.
+pub const MAX_TEXT_WIDTH: usize = 100;
+
+/// Returns the number of terminal columns a string occupies.
+///
+/// ANSI escape codes such as the ones added by [`crate::Colorize`] are not
+/// counted, and wide characters count as two columns.
+pub fn display_width(text: &str) -> usize {
+    strip_ansi(text).width()
+}
+
+/// Removes ANSI escape codes from a string.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            //  Skip until the final byte of the escape sequence.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Returns the width of the terminal.
+///
+/// The `COLUMNS` environment variable takes precedence. If stdout is not a
+/// terminal, [`DEFAULT_WIDTH`] is returned.
+pub fn terminal_width() -> usize {
+    if let Some(cols) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .filter(|c| *c > 0)
+    {
+        return cols;
+    }
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(w), _)) if w > 0 => w as usize,
+        _ => DEFAULT_WIDTH,
+    }
+}
+
+/// Returns the width that prose should be wrapped to.
+///
+/// This is the terminal width capped at [`MAX_TEXT_WIDTH`].
+pub fn text_width() -> usize {
+    terminal_width().min(MAX_TEXT_WIDTH)
+}
+
+/// Wraps text into lines that fit within `width` columns.
+///
+/// The first line is prefixed with `indent` and every following line with
+/// `hanging`. Whitespace in `text` is collapsed, lines never end in
+/// whitespace, and words longer than a line are split.
+pub fn wrap(text: &str, width: usize, indent: &str, hanging: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    let mut line = indent.to_string();
+    let mut line_width = display_width(indent);
+    //  True while the current line has no words.
+    let mut empty = true;
+
+    for word in text.split_whitespace() {
+        let word_width = word.width();
+        let sep = if empty { 0 } else { 1 };
+
+        if line_width + sep + word_width > width && !empty {
+            lines.push(line);
+            line = hanging.to_string();
+            line_width = display_width(hanging);
+            empty = true;
+        }
+
+        if !empty {
+            line.push(' ');
+            line_width += 1;
+        }
+
+        //  Split words that do not fit on a line of their own.
+        if line_width + word_width > width {
+            for c in word.chars() {
+                let char_width = c.width().unwrap_or(0);
+                if line_width + char_width > width && !empty {
+                    lines.push(line);
+                    line = hanging.to_string();
+                    line_width = display_width(hanging);
+                }
+                line.push(c);
+                line_width += char_width;
+                empty = false;
+            }
+        } else {
+            line.push_str(word);
+            line_width += word_width;
+        }
+        empty = false;
+    }
+
+    if !empty || lines.is_empty() {
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// Wraps text and joins the lines with newlines.
+///
+/// See [`wrap`].
+pub fn wrap_str(text: &str, width: usize, indent: &str, hanging: &str) -> String {
+    wrap(text, width, indent, hanging).join("\n")
+}
+
+/// Wraps text after a label, indenting following lines to line up with the
+/// start of the text.
+pub fn labeled(label: &str, text: &str, width: usize) -> String {
+    let hanging = " ".repeat(display_width(label));
+    wrap_str(text, width, label, &hanging)
+}
+
+/// Pads text on the left so that it ends at column `width`.
+///
+/// Text wider than `width` is returned unchanged.
+pub fn right_align(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", " ".repeat(padding), text)
+}
+
+/// Pads text on the right so that it occupies `width` columns.
+///
+/// Unlike `format!("{:<width$}")`, the padding accounts for wide characters
+/// and ANSI escape codes.
+pub fn pad_right(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: &str = "\x1b[31m";
+    const RESET: &str = "\x1b[0m";
+
+    #[test]
+    fn strip_ansi_removes_escape_codes() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi(&format!("{}red{} text", RED, RESET)), "red text");
+        assert_eq!(strip_ansi("\x1b[1;35m日本\x1b[0m"), "日本");
+    }
+
+    #[test]
+    fn display_width_counts_columns() {
+        assert_eq!(display_width("abc"), 3);
+        //  Wide characters take two columns, combining marks none.
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("cafe\u{301}"), 4);
+        assert_eq!(display_width(&format!("{}red{}", RED, RESET)), 3);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn wrap_words() {
+        assert_eq!(
+            wrap("the quick  brown\nfox jumps", 10, "", ""),
+            ["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("", 10, "> ", ""), [">"]);
+        assert_eq!(wrap("short", 10, "", ""), ["short"]);
+    }
+
+    #[test]
+    fn wrap_with_indents() {
+        assert_eq!(
+            wrap("one two three four", 9, "- ", "  "),
+            ["- one two", "  three", "  four"]
+        );
+        //  Colored indents count by their visible width.
+        let indent = format!("{}>{} ", RED, RESET);
+        assert_eq!(
+            wrap("one two three", 9, &indent, "  "),
+            [format!("{}one two", indent), "  three".to_string()]
+        );
+    }
+
+    #[test]
+    fn wrap_wide_and_combining_text() {
+        assert_eq!(wrap("日本語 日本語", 8, "", ""), ["日本語", "日本語"]);
+        assert_eq!(
+            wrap("cafe\u{301} cafe\u{301}", 9, "", ""),
+            ["cafe\u{301} cafe\u{301}"]
+        );
+        for line in wrap("日本語の文章です 日本語", 7, "", "") {
+            assert!(display_width(&line) <= 7);
+        }
+    }
+
+    #[test]
+    fn wrap_splits_long_words() {
+        assert_eq!(wrap("abcdefghij", 4, "", ""), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("日本語日本", 5, "", ""), ["日本", "語日", "本"]);
+        assert_eq!(wrap("a abcdefg", 4, "", " "), ["a", " abc", " def", " g"]);
+    }
+
+    #[test]
+    fn labeled_text_hangs_under_the_text() {
+        assert_eq!(
+            labeled("Label: ", "one two three", 14),
+            "Label: one two\n       three"
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        assert_eq!(right_align("abc", 5), "  abc");
+        assert_eq!(right_align("日本", 5), " 日本");
+        assert_eq!(right_align("toolong", 3), "toolong");
+        let red = format!("{}ab{}", RED, RESET);
+        assert_eq!(right_align(&red, 4), format!("  {}", red));
+
+        assert_eq!(pad_right("abc", 5), "abc  ");
+        assert_eq!(pad_right("日本", 5), "日本 ");
+        assert_eq!(pad_right(&red, 4), format!("{}  ", red));
+    }
+}
@@ -13,6 +13,11 @@ use lazy_static::lazy_static;
 mod colors;
 mod commands;
 mod database;
+mod dates;
+mod http;
+mod layout;
+#[cfg(test)]
+mod testing;
 
 /// Wrapper type for std::result::Result.
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -28,6 +33,8 @@ lazy_static! {
     pub static ref CONFIG_FILE: PathBuf = sanitize_path(&APP_DIR.join("config.toml"));
     /// The path to the SQLite database file.
     pub static ref SQL_FILE: PathBuf = sanitize_path(&APP_DIR.join("database.db"));
+    /// The directory for cached data that can be safely deleted.
+    pub static ref CACHE_DIR: PathBuf = sanitize_path(&APP_DIR.join("cache"));
     /// The environment settings.
     pub static ref ENV: Mutex<Environment> = Mutex::new(Environment::load());
 }
@@ -51,6 +58,8 @@ pub fn expand_home(path: &Path) -> PathBuf {
         let sub_path: PathBuf = path.iter().skip(1).collect();
         new_path.push(base_dir);
         new_path.push(sub_path);
+    } else {
+        new_path.push(path);
     }
     new_path
 }
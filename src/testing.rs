@@ -0,0 +1,139 @@
+//! Helpers shared by tests.
+//!
+//! Tests that read or change `ENV` or use the database hold a [`TestEnv`],
+//! which runs them one at a time against a fresh database. Web requests are
+//! served by a [`StubServer`] on the loopback interface.
+
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use diesel::{Connection, sqlite::SqliteConnection};
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+
+use crate::commands::Environment;
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// Serializes tests that use `ENV`.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Number of test environments created, used to name their directories.
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Default settings and a fresh database, for the lifetime of a test.
+pub struct TestEnv {
+    /// Directory holding the database, removed when the test ends.
+    pub dir: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TestEnv {
+    /// Reset `ENV` to the default settings, using a new database with every
+    /// migration applied. Web requests are not retried.
+    pub fn new() -> Self {
+        let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!(
+            "shellcommander-test-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("database.db").display().to_string();
+
+        let mut conn = SqliteConnection::establish(&db).unwrap();
+        conn.run_pending_migrations(MIGRATIONS).unwrap();
+
+        let mut env = crate::ENV.lock().unwrap_or_else(|e| e.into_inner());
+        *env = Environment::default();
+        env.conn_str = db;
+        env.http_retries = 0;
+        env.http_proxy = String::new();
+        TestEnv { dir, _lock: lock }
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// How a [`StubServer`] answers a request.
+pub enum Reply {
+    /// Send a response.
+    Send(String),
+    /// Wait, then close the connection without answering.
+    Hang(Duration),
+}
+
+/// A response with a status line such as `200 OK`, extra headers and a body.
+pub fn response(status: &str, headers: &[(&str, &str)], body: &str) -> Reply {
+    let mut text = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    for (name, value) in headers {
+        text.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    text.push_str("\r\n");
+    text.push_str(body);
+    Reply::Send(text)
+}
+
+/// A local HTTP server answering one request with each reply, in order.
+pub struct StubServer {
+    /// Base URL of the server, ending in a slash.
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl StubServer {
+    pub fn new(replies: Vec<Reply>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let seen = requests.clone();
+
+        thread::spawn(move || {
+            for reply in replies {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut request = vec![];
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                seen.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&request).to_string());
+                match reply {
+                    Reply::Send(text) => {
+                        let _ = stream.write_all(text.as_bytes());
+                    }
+                    Reply::Hang(wait) => thread::sleep(wait),
+                }
+            }
+        });
+
+        StubServer { url, requests }
+    }
+
+    /// The requests received so far, with their headers.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}